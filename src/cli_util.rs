@@ -0,0 +1,325 @@
+// Command Line Front End
+use std::{
+    error::Error,
+    fmt, fs,
+    io::{self, Read},
+};
+
+use math_calc::{
+    expression_util::try_calc_expr,
+    file_util::split_csv_by_column,
+    func_expr_util::{
+        constants::constants_table,
+        expr_types::{AngleMode, Env, EvalConfig},
+        try_evaluate_prefix_expression,
+    },
+    graph_maker_util::{build_plot, GraphInfo},
+    number_conversion_util::{number_as_format, NumberFormat},
+    statistics_util::{data_to_vector, one_dimensional_statistics},
+    unit_conversion_util::value_as_unit,
+};
+
+pub const USAGE: &str = "Usage: math-calc [COMMAND] [ARGS...]
+
+Runs the graphical calculator when no command is given.
+
+Commands:
+  eval [--angle MODE] <EXPR>           Evaluate an infix expression
+  prefix [--angle MODE] <EXPR>         Evaluate a prefix expression, add(1, 2) or (+ 1 2)
+                                       MODE is rad (default), deg or grad
+  constants                            List the named constants both evaluators know
+  convert-base <bin|oct|dec|hex> <N>   Convert an integer (0x, 0o, 0b prefixes allowed)
+  convert-unit <FROM> <VALUE> <TO>     Convert a value between units
+  stats [FILE|-] [--column N]          1D statistics of comma separated data
+  graph <FILE> <OUTPUT> [--3d] [--title T] [--x-label L] [--y-label L] [--z-label L]
+                                       Plot 2 or 3 csv columns and save the figure
+  help                                 Show this message";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CliError {
+    pub message: String,
+}
+
+impl CliError {
+    fn new(message: &str) -> CliError {
+        return CliError {
+            message: message.to_string(),
+        };
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+impl Error for CliError {}
+
+impl From<String> for CliError {
+    fn from(value: String) -> Self {
+        return CliError::new(&value);
+    }
+}
+
+impl From<&str> for CliError {
+    fn from(value: &str) -> Self {
+        return CliError::new(value);
+    }
+}
+
+/// Runs a single command and returns the text to print on success
+pub fn run_cli(args: &[String]) -> Result<String, CliError> {
+    let (command, rest) = match args.split_first() {
+        Some((command, rest)) => (command.as_str(), rest),
+        None => return Err(USAGE.into()),
+    };
+
+    match command {
+        "eval" => {
+            let (config, rest) = eval_config(rest)?;
+            let expr = join_expression(rest)?;
+            return try_calc_expr(&expr, &config).map_err(CliError::from);
+        }
+        "prefix" => {
+            let (config, rest) = eval_config(rest)?;
+            let expr = join_expression(rest)?;
+            return try_evaluate_prefix_expression(&expr, &mut Env::new(), &config)
+                .map_err(CliError::from);
+        }
+        "constants" => Ok(constants_table()),
+        "convert-base" => convert_base(rest),
+        "convert-unit" => convert_unit(rest),
+        "stats" => stats(rest),
+        "graph" => graph(rest),
+        "help" | "-h" | "--help" => Ok(USAGE.to_string()),
+        _ => Err(format!("Unknown Command \"{}\"\n\n{}", command, USAGE).into()),
+    }
+}
+
+/// Reads a leading `--angle MODE`, returning the arguments after it
+fn eval_config(args: &[String]) -> Result<(EvalConfig, &[String]), CliError> {
+    let mut config = EvalConfig::default();
+    if args.first().map(String::as_str) != Some("--angle") {
+        return Ok((config, args));
+    }
+    let mode = flag_value("--angle", args.get(1))?;
+    config.angle_mode = match AngleMode::from_name(mode) {
+        Some(angle_mode) => angle_mode,
+        None => return Err(format!("Unknown Angle Mode \"{}\"", mode).into()),
+    };
+    return Ok((config, &args[2..]));
+}
+
+fn join_expression(args: &[String]) -> Result<String, CliError> {
+    if args.is_empty() {
+        return Err("Missing Expression".into());
+    }
+    return Ok(args.join(" "));
+}
+
+fn convert_base(args: &[String]) -> Result<String, CliError> {
+    if args.len() != 2 {
+        return Err("Expected: convert-base <bin|oct|dec|hex> <N>".into());
+    }
+    let format = match args[0].as_str() {
+        "bin" => NumberFormat::Binary,
+        "oct" => NumberFormat::Octal,
+        "dec" => NumberFormat::Decimal,
+        "hex" => NumberFormat::Hexadecimal,
+        other => return Err(format!("Unknown Number Format \"{}\"", other).into()),
+    };
+    let number = parse_int::parse::<u64>(&args[1]);
+    if number.is_err() {
+        return Err(format!("Invalid Number \"{}\"", args[1]).into());
+    }
+    return Ok(number_as_format(number, format));
+}
+
+fn convert_unit(args: &[String]) -> Result<String, CliError> {
+    if args.len() != 3 {
+        return Err("Expected: convert-unit <FROM> <VALUE> <TO>".into());
+    }
+    let result = value_as_unit(&args[0], &args[1], &args[2]);
+    if result.is_empty() {
+        return Err(format!("Invalid Value \"{}\"", args[1]).into());
+    } else if result == "NaN" {
+        return Err(format!("Cannot Convert \"{}\" to \"{}\"", args[0], args[2]).into());
+    }
+    return Ok(result);
+}
+
+fn stats(args: &[String]) -> Result<String, CliError> {
+    let mut source: Option<&str> = None;
+    let mut column: Option<usize> = None;
+    let mut arg_iter = args.iter();
+    while let Some(arg) = arg_iter.next() {
+        match arg.as_str() {
+            "--column" => column = Some(parse_column(arg_iter.next())?),
+            _ => {
+                if source.is_some() {
+                    return Err(format!("Unexpected Argument \"{}\"", arg).into());
+                }
+                source = Some(arg);
+            }
+        }
+    }
+
+    let contents = read_source(source.unwrap_or("-"))?;
+    let data = match column {
+        Some(index) => split_csv_by_column(index + 1, &contents).swap_remove(index),
+        None => contents,
+    };
+    return one_dimensional_statistics(&mut data_to_vector(&data))
+        .map_err(|err| CliError::new(&err.message));
+}
+
+fn parse_column(arg: Option<&String>) -> Result<usize, CliError> {
+    match arg.map(|raw| raw.parse::<usize>()) {
+        Some(Ok(column)) => Ok(column),
+        _ => Err("--column expects a non-negative integer".into()),
+    }
+}
+
+fn graph(args: &[String]) -> Result<String, CliError> {
+    let mut positional: Vec<&str> = Vec::new();
+    let mut graph_info = GraphInfo {
+        is_2d: true,
+        ..Default::default()
+    };
+    let mut arg_iter = args.iter();
+    while let Some(arg) = arg_iter.next() {
+        match arg.as_str() {
+            "--3d" => graph_info.is_2d = false,
+            "--title" => graph_info.title = flag_value(arg, arg_iter.next())?.to_string(),
+            "--x-label" => graph_info.x_axis_label = flag_value(arg, arg_iter.next())?.to_string(),
+            "--y-label" => graph_info.y_axis_label = flag_value(arg, arg_iter.next())?.to_string(),
+            "--z-label" => graph_info.z_axis_label = flag_value(arg, arg_iter.next())?.to_string(),
+            _ => positional.push(arg),
+        }
+    }
+    if positional.len() != 2 {
+        return Err("Expected: graph <FILE> <OUTPUT>".into());
+    }
+
+    let column_count = if graph_info.is_2d { 2 } else { 3 };
+    let mut columns = split_csv_by_column(column_count, &read_source(positional[0])?).into_iter();
+    graph_info.x_data = columns.next().unwrap_or_default();
+    graph_info.y_data = columns.next().unwrap_or_default();
+    graph_info.z_data = columns.next().unwrap_or_default();
+
+    let plot = build_plot(&graph_info)?;
+    plot.save(positional[1])?;
+    return Ok(format!("Saved Graph to {}", positional[1]));
+}
+
+fn flag_value<'a>(flag: &str, value: Option<&'a String>) -> Result<&'a str, CliError> {
+    match value {
+        Some(value) => Ok(value),
+        None => Err(format!("{} expects a value", flag).into()),
+    }
+}
+
+/// Reads a whole file, or standard input when the path is "-"
+fn read_source(path: &str) -> Result<String, CliError> {
+    if path == "-" {
+        let mut contents = String::new();
+        io::stdin()
+            .read_to_string(&mut contents)
+            .map_err(|err| CliError::new(&err.to_string()))?;
+        return Ok(contents);
+    }
+    return fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err).into());
+}
+
+#[cfg(test)]
+mod cli_tests {
+    use super::*;
+
+    fn args(raw: &[&str]) -> Vec<String> {
+        return raw.iter().map(|s| s.to_string()).collect();
+    }
+
+    #[test]
+    fn eval_joins_arguments() {
+        assert_eq!(
+            Ok("3".to_string()),
+            run_cli(&args(&["eval", "1", "+", "2"]))
+        );
+    }
+
+    #[test]
+    fn prefix_expression() {
+        assert_eq!(
+            Ok("4".to_string()),
+            run_cli(&args(&["prefix", "add(2, 2)"]))
+        );
+    }
+
+    #[test]
+    fn evaluation_errors_fail() {
+        assert_eq!(
+            Err(CliError::new("div: Arg 2 Divides by Zero")),
+            run_cli(&args(&["prefix", "div(1, 0)"]))
+        );
+        assert!(run_cli(&args(&["eval", "1 / 0"])).is_err());
+        assert!(run_cli(&args(&["eval", "1 +"])).is_err());
+    }
+
+    #[test]
+    fn angle_mode_flag() {
+        assert_eq!(
+            Ok("0.5".to_string()),
+            run_cli(&args(&["eval", "--angle", "deg", "sin(30)"]))
+        );
+        assert_eq!(
+            Ok("100".to_string()),
+            run_cli(&args(&["prefix", "--angle", "grad", "arccos(0)"]))
+        );
+        assert!(run_cli(&args(&["eval", "--angle", "turns", "1"])).is_err());
+    }
+
+    #[test]
+    fn constants_listing() {
+        let listing: String = run_cli(&args(&["constants"])).unwrap();
+        assert!(listing.lines().any(|line| line.starts_with("N_A ")));
+    }
+
+    #[test]
+    fn convert_base_hex() {
+        assert_eq!(
+            Ok("FF".to_string()),
+            run_cli(&args(&["convert-base", "hex", "255"]))
+        );
+        assert_eq!(
+            Ok("1010".to_string()),
+            run_cli(&args(&["convert-base", "bin", "0xA"]))
+        );
+    }
+
+    #[test]
+    fn convert_unit_length() {
+        assert_eq!(
+            Ok("100".to_string()),
+            run_cli(&args(&["convert-unit", "m", "1", "cm"]))
+        );
+        assert!(run_cli(&args(&["convert-unit", "m", "1", "Joules"])).is_err());
+    }
+
+    #[test]
+    fn stats_from_file_column() {
+        let output = run_cli(&args(&[
+            "stats",
+            "example_data/multiple-columns.csv",
+            "--column",
+            "1",
+        ]))
+        .unwrap();
+        assert!(output.starts_with("Sum:           18\n"));
+    }
+
+    #[test]
+    fn unknown_command() {
+        assert!(run_cli(&args(&["frobnicate"])).is_err());
+        assert!(run_cli(&[]).is_err());
+    }
+}
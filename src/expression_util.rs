@@ -1,27 +1,163 @@
-use std::f64::consts::{E, PI, TAU};
-
-use math_utils_lib::{
-    eval, parse, /*export, quick_eval, solve, ExportType, StepType,*/ MathLibError, Value,
-    Variable,
+use crate::func_expr_util::{
+    expr_types::{Env, EvalConfig, Expr, Syntax, Value},
+    interp_expr::interp_expr_with_config,
+    parse_expr::parse_expr_with_syntax,
+    print_expr::print_infix,
+    trace_expr::trace_expr,
 };
 
+/// Evaluates infix expressions with the same builtins, constants and numbers as prefix ones
 pub fn calc_expr(raw_expr: &str) -> String {
-    match parse_and_evaluate(raw_expr.into()) {
-        Ok(value) => value.to_string(),
-        Err(error) => error.get_reason(),
+    return calc_expr_with_config(raw_expr, &EvalConfig::default());
+}
+
+/// Like `calc_expr`, with settings such as the angle mode
+pub fn calc_expr_with_config(raw_expr: &str, config: &EvalConfig) -> String {
+    match try_calc_expr(raw_expr, config) {
+        Ok(answer) | Err(answer) => answer,
+    }
+}
+
+/// Like `calc_expr_with_config`, keeping error messages apart from answers
+pub fn try_calc_expr(raw_expr: &str, config: &EvalConfig) -> Result<String, String> {
+    let expr: Expr = parse_expr_with_syntax(raw_expr, Syntax::Infix).map_err(|e| e.to_string())?;
+    match interp_expr_with_config(&expr, config) {
+        // Functions like the ones diff returns read back in the notation they were asked in
+        Ok(Value::FnV { fn_v }) if fn_v.name.is_none() => Ok(print_infix(&fn_v.as_lambda())),
+        Ok(value) => Ok(value.to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Each step of evaluating the infix expression on its own line, written in infix
+pub fn trace_infix_expression(raw_expr: &str, config: &EvalConfig) -> String {
+    match parse_expr_with_syntax(raw_expr, Syntax::Infix) {
+        Ok(expr) => trace_expr(&expr, &mut Env::new(), config, Syntax::Infix).to_string(),
+        Err(e) => e.to_string(),
     }
 }
 
-fn parse_and_evaluate(mut expr: String) -> Result<Value, MathLibError> {
-    expr = expr.trim().split(" ").filter(|s| !s.is_empty()).collect();
-    let parsed = parse(expr)?;
-    let res = eval(
-        &parsed,
-        &vec![
-            Variable::new("pi".to_string(), Value::Scalar(PI)),
-            Variable::new("tau".to_string(), Value::Scalar(TAU)),
-            Variable::new("e".to_string(), Value::Scalar(E)),
-        ],
-    )?;
-    return Ok(res);
+/// The infix expression written in prefix notation, or why it could not be parsed
+pub fn infix_as_prefix(raw_expr: &str) -> String {
+    match parse_expr_with_syntax(raw_expr, Syntax::Infix) {
+        Ok(expr) => expr.to_string(),
+        Err(e) => e.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod expression_tests {
+    use super::*;
+
+    #[test]
+    fn real_expressions_are_unchanged() {
+        assert_eq!("3", calc_expr("1 + 2"));
+        assert_eq!("2", calc_expr("sqrt(4)"));
+    }
+
+    #[test]
+    fn complex_expressions() {
+        assert_eq!("i", calc_expr("sqrt(-1)"));
+        assert_eq!("-1", calc_expr("i * i"));
+        assert_eq!("3+2i", calc_expr("3 + 2i"));
+        assert_eq!("5", calc_expr("abs(3 + 4i)"));
+        assert_eq!("-1", calc_expr("e^(pi * i)").split('+').next().unwrap());
+    }
+
+    #[test]
+    fn shared_builtins_and_exact_numbers() {
+        assert_eq!("4", calc_expr("gcd(12, 8)"));
+        assert_eq!("10", calc_expr("choose(5, 2)"));
+        assert_eq!("7/2", calc_expr("7 / 2"));
+        assert_eq!("6.283185307179586", calc_expr("2pi"));
+        assert_eq!("-4", calc_expr("-2^2"));
+        assert_eq!("0.5", calc_expr("2^-1.0"));
+        assert_eq!("512", calc_expr("2^3^2"));
+        assert_eq!("true", calc_expr("1 + 1 == 2"));
+        assert_eq!("[2, 4, 6]", calc_expr("map(lambda(x, 2x), [1, 2, 3])"));
+        assert_eq!("x: Variable Is Not Defined", calc_expr("x + 1"));
+    }
+
+    #[test]
+    fn vectors_and_calculus_as_before() {
+        // Results the earlier infix evaluator gave for the same input
+        assert_eq!("11", calc_expr("[1,2]*[3,4]"));
+        assert_eq!("[2, 4]", calc_expr("2*[1,2]"));
+        assert_eq!("[4, 6]", calc_expr("[1,2]+[3,4]"));
+        assert_eq!("[1/2, 1]", calc_expr("[1,2]/2"));
+        assert_eq!("[7, 10]", calc_expr("[[1,2],[3,4]]*[1,2]"));
+        assert_eq!("[1  6]\n[2  8]", calc_expr("[[1,2],[3,4]]*[[1,0],[0,2]]"));
+        assert_eq!("5", calc_expr("abs([3,4])"));
+        assert_eq!("4", calc_expr("[3,4,5]?1"));
+        assert_eq!("16", calc_expr("[3,4,5]?1^2"));
+        assert_eq!("[0, 0, 1]", calc_expr("[1,0,0]#[0,1,0]"));
+        assert_eq!("[0, 0, -2]", calc_expr("[1,2]#[3,4]"));
+        let integral: f64 = calc_expr("I(x,x,0,1)").parse().unwrap();
+        assert!((integral - 0.5).abs() < 1e-9);
+        let derivative: f64 = calc_expr("D(x^2,x,3)").parse().unwrap();
+        assert!((derivative - 6.0).abs() < 1e-9);
+        assert_eq!(
+            "get: Arg 2 Must Be Less Than the Length of Arg 1",
+            calc_expr("[3,4,5]?3")
+        );
+        assert_eq!(
+            "mult: Arg 2 Must Be as Long as Arg 1",
+            calc_expr("[1,2,3]*[1,2]")
+        );
+    }
+
+    #[test]
+    fn functions_read_back_in_infix() {
+        let derivative: String = calc_expr("D(x^2 + 2*x, x)");
+        assert_eq!("x -> 2 * x + 2", derivative);
+        assert_eq!("[4, 6]", calc_expr(&format!("map({}, [1, 2])", derivative)));
+        let simplified: String = calc_expr("simplify(x + y + x)");
+        assert_eq!("(x, y) -> 2 * x + y", simplified);
+        assert!(parse_expr_with_syntax(&simplified, Syntax::Infix).is_ok());
+    }
+
+    #[test]
+    fn names_next_to_each_other_do_not_multiply() {
+        assert_eq!(
+            "Expected an Operator Between \"sin\" and \"x\" at Line 1, Column 5\nsin x\n    ^",
+            calc_expr("sin x")
+        );
+        assert!(calc_expr("x and y").starts_with("Expected an Operator Between \"x\" and \"and\""));
+        assert!(calc_expr("2x y").starts_with("Expected an Operator"));
+        assert_eq!("6", calc_expr("2 (1 + 2)"));
+        assert_eq!("6.283185307179586", calc_expr("2 pi"));
+    }
+
+    #[test]
+    fn traces_read_in_infix() {
+        let config = EvalConfig::default();
+        assert_eq!(
+            "2 + 3 * 4\n= 2 + 12\n= 14",
+            trace_infix_expression("2 + 3 * 4", &config)
+        );
+        // 7 / 2 is already an exact fraction, so it never becomes a step of its own
+        assert_eq!(
+            "0 - 2 ^ 2 + 7 / 2\n= 0 - 4 + 7 / 2\n= -4 + 7 / 2\n= -1 / 2",
+            trace_infix_expression("-2^2 + 7 / 2", &config)
+        );
+    }
+
+    #[test]
+    fn prefix_translation() {
+        assert_eq!("add(1, mult(2, x))", infix_as_prefix("1 + 2x"));
+        assert_eq!("sub(0, sin(pi))", infix_as_prefix("-sin(pi)"));
+        assert_eq!("pow(-2, 3)", infix_as_prefix("(-2)^3"));
+        assert_eq!("mult(list(1, 2), 2.5)", infix_as_prefix("[1, 2] * 2.5"));
+        assert_eq!("pow(x, pow(y, z))", infix_as_prefix("x^y^z"));
+        assert_eq!("pow(get(l, 0), 2)", infix_as_prefix("l?0^2"));
+        assert_eq!("mult(cross(a, b), c)", infix_as_prefix("a # b * c"));
+        assert_eq!(
+            "transpose(matrix(list(1, 2), list(3, 4)))",
+            infix_as_prefix("[[1, 2], [3, 4]]")
+        );
+        assert_eq!(
+            "get(integrate(x, x, 0, 1), 0)",
+            infix_as_prefix("I(x, x, 0, 1)")
+        );
+    }
 }
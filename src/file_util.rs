@@ -1,13 +1,18 @@
-use std::{fs, path::PathBuf};
+#[cfg(feature = "gui")]
+use std::fs;
+use std::{env, path::PathBuf};
 
+#[cfg(feature = "gui")]
 use rfd::FileDialog;
 
+#[cfg(feature = "gui")]
 pub fn get_save_path() -> Option<PathBuf> {
     return FileDialog::new()
         .set_title("Select Save Folder")
         .pick_folder();
 }
 
+#[cfg(feature = "gui")]
 pub fn data_from_csv() -> String {
     let mut result: String = "".to_string();
     use rfd::FileDialog;
@@ -23,6 +28,41 @@ pub fn data_from_csv() -> String {
     return result;
 }
 
+/// Asks where to save a LaTeX file and writes `contents` there, returning a message saying
+/// where it went or why it could not be saved. Empty when the dialog was cancelled
+#[cfg(feature = "gui")]
+pub fn save_tex(file_name: &str, contents: &str) -> String {
+    let Some(path) = FileDialog::new()
+        .set_title("Export LaTeX")
+        .add_filter("LaTeX File", &["tex"])
+        .set_file_name(file_name)
+        .save_file()
+    else {
+        return String::new();
+    };
+    return match fs::write(&path, contents) {
+        Ok(()) => format!("Saved {}", path.display()),
+        Err(e) => format!("Could Not Save {}: {}", path.display(), e),
+    };
+}
+
+/// The per-user directory for settings: `%APPDATA%` on Windows,
+/// `~/Library/Application Support` on macOS and `$XDG_CONFIG_HOME` or `~/.config` elsewhere
+pub fn config_dir() -> Option<PathBuf> {
+    let from_env = |name: &str| -> Option<PathBuf> {
+        return env::var_os(name)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from);
+    };
+    if cfg!(windows) {
+        return from_env("APPDATA");
+    } else if cfg!(target_os = "macos") {
+        return from_env("HOME").map(|home| home.join("Library").join("Application Support"));
+    }
+    return from_env("XDG_CONFIG_HOME")
+        .or_else(|| from_env("HOME").map(|home| home.join(".config")));
+}
+
 pub fn split_csv_by_column(column_count: usize, csv_str: &str) -> Vec<String> {
     let mut result_columns: Vec<String> = (0..column_count).map(|_| String::new()).collect();
 
@@ -0,0 +1,243 @@
+use std::f64::consts::{E, PI, TAU};
+
+use super::expr_types::{format_float, Value};
+
+/// A named value every expression can use, in both notations, unless a variable shadows it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Constant {
+    pub name: &'static str,
+    pub value: f64,
+    /// Empty for pure numbers
+    pub unit: &'static str,
+    pub description: &'static str,
+    pub source: &'static str,
+}
+
+const MATH: &str = "Mathematical Constant";
+const SI_EXACT: &str = "SI 2019, Exact";
+const CODATA: &str = "CODATA 2018";
+
+pub const CONSTANTS: [Constant; 19] = [
+    Constant {
+        name: "pi",
+        value: PI,
+        unit: "",
+        description: "Ratio of a Circle's Circumference to Its Diameter",
+        source: MATH,
+    },
+    Constant {
+        name: "tau",
+        value: TAU,
+        unit: "",
+        description: "Ratio of a Circle's Circumference to Its Radius",
+        source: MATH,
+    },
+    Constant {
+        name: "e",
+        value: E,
+        unit: "",
+        description: "Euler's Number",
+        source: MATH,
+    },
+    Constant {
+        name: "phi",
+        value: 1.618_033_988_749_895,
+        unit: "",
+        description: "Golden Ratio",
+        source: MATH,
+    },
+    Constant {
+        name: "euler_gamma",
+        value: 0.577_215_664_901_532_9,
+        unit: "",
+        description: "Euler-Mascheroni Constant",
+        source: MATH,
+    },
+    Constant {
+        name: "c",
+        value: 299_792_458.0,
+        unit: "m/s",
+        description: "Speed of Light in Vacuum",
+        source: SI_EXACT,
+    },
+    Constant {
+        name: "h",
+        value: 6.626_070_15e-34,
+        unit: "J s",
+        description: "Planck Constant",
+        source: SI_EXACT,
+    },
+    Constant {
+        name: "hbar",
+        value: 1.054_571_817e-34,
+        unit: "J s",
+        description: "Reduced Planck Constant",
+        source: CODATA,
+    },
+    Constant {
+        name: "k_B",
+        value: 1.380_649e-23,
+        unit: "J/K",
+        description: "Boltzmann Constant",
+        source: SI_EXACT,
+    },
+    Constant {
+        name: "N_A",
+        value: 6.022_140_76e23,
+        unit: "1/mol",
+        description: "Avogadro Constant",
+        source: SI_EXACT,
+    },
+    Constant {
+        name: "R",
+        value: 8.314_462_618_153_24,
+        unit: "J/(mol K)",
+        description: "Molar Gas Constant",
+        source: SI_EXACT,
+    },
+    Constant {
+        name: "G",
+        value: 6.674_30e-11,
+        unit: "m^3/(kg s^2)",
+        description: "Newtonian Constant of Gravitation",
+        source: CODATA,
+    },
+    Constant {
+        name: "g_n",
+        value: 9.806_65,
+        unit: "m/s^2",
+        description: "Standard Acceleration of Gravity",
+        source: "3rd CGPM 1901, Exact",
+    },
+    Constant {
+        name: "q_e",
+        value: 1.602_176_634e-19,
+        unit: "C",
+        description: "Elementary Charge",
+        source: SI_EXACT,
+    },
+    Constant {
+        name: "m_e",
+        value: 9.109_383_701_5e-31,
+        unit: "kg",
+        description: "Electron Mass",
+        source: CODATA,
+    },
+    Constant {
+        name: "m_p",
+        value: 1.672_621_923_69e-27,
+        unit: "kg",
+        description: "Proton Mass",
+        source: CODATA,
+    },
+    Constant {
+        name: "epsilon_0",
+        value: 8.854_187_812_8e-12,
+        unit: "F/m",
+        description: "Vacuum Electric Permittivity",
+        source: CODATA,
+    },
+    Constant {
+        name: "mu_0",
+        value: 1.256_637_062_12e-6,
+        unit: "N/A^2",
+        description: "Vacuum Magnetic Permeability",
+        source: CODATA,
+    },
+    Constant {
+        name: "sigma_SB",
+        value: 5.670_374_419e-8,
+        unit: "W/(m^2 K^4)",
+        description: "Stefan-Boltzmann Constant",
+        source: CODATA,
+    },
+];
+
+pub fn constant_value(name: &str) -> Option<Value> {
+    return CONSTANTS
+        .iter()
+        .find(|constant| constant.name == name)
+        .map(|constant| Value::FloatV {
+            f_v: constant.value,
+        });
+}
+
+/// Every constant on its own line with the columns lined up
+pub fn constants_table() -> String {
+    let rows: Vec<[String; 5]> = CONSTANTS
+        .iter()
+        .map(|constant| {
+            [
+                constant.name.to_string(),
+                format_float(constant.value),
+                constant.unit.to_string(),
+                constant.description.to_string(),
+                constant.source.to_string(),
+            ]
+        })
+        .collect();
+    let mut widths: [usize; 5] = [0; 5];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let lines: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect();
+            cells.join("  ").trim_end().to_string()
+        })
+        .collect();
+    return lines.join("\n");
+}
+
+#[cfg(test)]
+mod constants_tests {
+    use super::*;
+    use crate::func_expr_util::evaluate_prefix_expression;
+
+    #[test]
+    fn names_are_unique_identifiers() {
+        for (index, constant) in CONSTANTS.iter().enumerate() {
+            assert!(constant
+                .name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_'));
+            assert!(CONSTANTS[..index]
+                .iter()
+                .all(|earlier| earlier.name != constant.name));
+        }
+    }
+
+    #[test]
+    fn table_lists_every_constant() {
+        let table: String = constants_table();
+        assert_eq!(CONSTANTS.len(), table.lines().count());
+        assert!(table.lines().any(|line| {
+            return line.split_whitespace().take(3).collect::<Vec<&str>>()
+                == ["c", "299792458", "m/s"];
+        }));
+        assert!(table.contains("6.62607015e-34"));
+    }
+
+    #[test]
+    fn physical_constants() {
+        assert_eq!("299792458", evaluate_prefix_expression("c"));
+        assert_eq!("true", evaluate_prefix_expression("eq(mult(k_B, N_A), R)"));
+        assert_eq!(
+            "1",
+            evaluate_prefix_expression("round(mult(phi, sub(phi, 1)))")
+        );
+        // Variables shadow constants
+        assert_eq!("3", evaluate_prefix_expression("let(c, 3, c)"));
+        assert_eq!(
+            crate::expression_util::calc_expr("2c"),
+            evaluate_prefix_expression("mult(2, c)")
+        );
+    }
+}
@@ -0,0 +1,717 @@
+use std::{cell::Cell, collections::HashMap};
+
+use super::expr_types::{AngleMode, ArgCount, Builtin, Env, EvalError, Expr, Value};
+
+/// Most session function calls one derivative expands, so recursive functions stop
+const MAX_EXPANSIONS: usize = 1000;
+
+/// The derivative of `expr` with respect to `var`, unsimplified.
+/// Other variables are constants, calls to session functions are expanded from `env`,
+/// and trig derivatives pick up the factor `angle_mode` needs, like pi/180 for degrees
+pub fn differentiate(
+    expr: &Expr,
+    var: &str,
+    env: &Env,
+    angle_mode: AngleMode,
+) -> Result<Expr, EvalError> {
+    let differentiator = Differentiator {
+        var,
+        env,
+        angle_mode,
+        expansions: Cell::new(0),
+    };
+    return differentiator.d(expr);
+}
+
+struct Differentiator<'a> {
+    var: &'a str,
+    env: &'a Env,
+    angle_mode: AngleMode,
+    expansions: Cell<usize>,
+}
+
+impl Differentiator<'_> {
+    fn d(&self, expr: &Expr) -> Result<Expr, EvalError> {
+        match expr {
+            Expr::Integer { .. }
+            | Expr::BigInteger { .. }
+            | Expr::Float { .. }
+            | Expr::Imaginary { .. } => Ok(int(0)),
+            Expr::Var { name } if name == self.var => Ok(int(1)),
+            // A session function on its own stands for calling it with `var`
+            Expr::Var { name } => match one_param_function(name, self.env) {
+                Some(_) => self.d(&Expr::Call {
+                    func: Box::new(expr.clone()),
+                    args: vec![var_expr(self.var)],
+                }),
+                None => Ok(int(0)),
+            },
+            Expr::Let { name, value, body } => {
+                let bindings: HashMap<&str, &Expr> = HashMap::from([(name.as_str(), &**value)]);
+                self.d(&substitute(body, &bindings))
+            }
+            Expr::Call { func, args } => self.expand_call(func, args),
+            Expr::BuiltinFn { name, args } => self.builtin(*name, args),
+            Expr::Boolean { .. } => Err(not_differentiable("Booleans")),
+            Expr::Set { .. } => Err(not_differentiable("set")),
+            Expr::Defn { .. } => Err(not_differentiable("defn")),
+            Expr::Lambda { .. } => Err(not_differentiable("lambda")),
+        }
+    }
+
+    /// Differentiates the body of a session function with the arguments in place of its parameters
+    fn expand_call(&self, func: &Expr, args: &[Expr]) -> Result<Expr, EvalError> {
+        let (name, closure) = match func {
+            Expr::Var { name } => match self.env.get(name) {
+                Some(Value::FnV { fn_v }) if fn_v.captured.is_empty() => (name, fn_v),
+                _ => return Err(not_differentiable(&func.to_string())),
+            },
+            _ => return Err(not_differentiable(&func.to_string())),
+        };
+        if closure.params.len() != args.len() {
+            return Err(EvalError::CallArity {
+                name: name.clone(),
+                expected: closure.params.len(),
+                found: args.len(),
+            });
+        }
+        if self.expansions.get() >= MAX_EXPANSIONS {
+            return Err(EvalError::RecursionLimit {
+                name: name.clone(),
+                limit: MAX_EXPANSIONS,
+            });
+        }
+        self.expansions.set(self.expansions.get() + 1);
+        let bindings: HashMap<&str, &Expr> = closure
+            .params
+            .iter()
+            .map(String::as_str)
+            .zip(args)
+            .collect();
+        return self.d(&substitute(&closure.body, &bindings));
+    }
+
+    fn builtin(&self, name: Builtin, args: &[Expr]) -> Result<Expr, EvalError> {
+        let d = |inner: &Expr| self.d(inner);
+        let arity = |expected: usize| EvalError::Arity {
+            builtin: name,
+            expected: ArgCount::Exactly(expected),
+            found: args.len(),
+        };
+        let single = || -> Result<(&Expr, Expr), EvalError> {
+            match args {
+                [a] => Ok((a, d(a)?)),
+                _ => Err(arity(1)),
+            }
+        };
+        let var: &str = self.var;
+        let env: &Env = self.env;
+        let angle_mode: AngleMode = self.angle_mode;
+        // Angles in degrees or gradians scale the derivative of trig functions
+        let into_radians: Expr = Expr::Float {
+            f: angle_mode.to_radians(1.0),
+        };
+        let from_radians: Expr = Expr::Float {
+            f: angle_mode.from_radians(1.0),
+        };
+        let trig_scale = |derivative: Expr, scale: Expr| -> Expr {
+            if angle_mode == AngleMode::Radians {
+                return derivative;
+            }
+            return call(Builtin::Multiply, vec![scale, derivative]);
+        };
+
+        match name {
+            Builtin::Add => Ok(call(
+                Builtin::Add,
+                args.iter()
+                    .map(d)
+                    .collect::<Result<Vec<Expr>, EvalError>>()?,
+            )),
+            Builtin::Subtract => match args {
+                [a, b] => Ok(call(Builtin::Subtract, vec![d(a)?, d(b)?])),
+                _ => Err(arity(2)),
+            },
+            // One term per factor, with that factor differentiated
+            Builtin::Multiply => {
+                let mut terms: Vec<Expr> = Vec::with_capacity(args.len());
+                for (index, arg) in args.iter().enumerate() {
+                    let mut factors: Vec<Expr> = args.to_vec();
+                    factors[index] = d(arg)?;
+                    terms.push(call(Builtin::Multiply, factors));
+                }
+                Ok(call(Builtin::Add, terms))
+            }
+            Builtin::Divide => match args {
+                [a, b] if !mentions(b, var, env) => {
+                    Ok(call(Builtin::Divide, vec![d(a)?, b.clone()]))
+                }
+                [a, b] => Ok(call(
+                    Builtin::Divide,
+                    vec![
+                        call(
+                            Builtin::Subtract,
+                            vec![
+                                call(Builtin::Multiply, vec![d(a)?, b.clone()]),
+                                call(Builtin::Multiply, vec![a.clone(), d(b)?]),
+                            ],
+                        ),
+                        call(Builtin::Pow, vec![b.clone(), int(2)]),
+                    ],
+                )),
+                _ => Err(arity(2)),
+            },
+            Builtin::Pow => match args {
+                [a, b] => {
+                    let (da, db): (Expr, Expr) = (d(a)?, d(b)?);
+                    let power: Expr = call(Builtin::Pow, vec![a.clone(), b.clone()]);
+                    let ln_a: Expr = match a {
+                        // Euler's number, unless a variable hides the constant
+                        Expr::Var { name } if name == "e" && env.get("e").is_none() => int(1),
+                        _ => call(Builtin::Ln, vec![a.clone()]),
+                    };
+                    if !mentions(b, var, env) {
+                        // Power rule
+                        return Ok(call(
+                            Builtin::Multiply,
+                            vec![
+                                b.clone(),
+                                call(
+                                    Builtin::Pow,
+                                    vec![
+                                        a.clone(),
+                                        call(Builtin::Subtract, vec![b.clone(), int(1)]),
+                                    ],
+                                ),
+                                da,
+                            ],
+                        ));
+                    }
+                    if !mentions(a, var, env) {
+                        return Ok(call(Builtin::Multiply, vec![power, ln_a, db]));
+                    }
+                    // a^b (b' ln(a) + b a' / a)
+                    Ok(call(
+                        Builtin::Multiply,
+                        vec![
+                            power,
+                            call(
+                                Builtin::Add,
+                                vec![
+                                    call(Builtin::Multiply, vec![db, ln_a]),
+                                    call(
+                                        Builtin::Divide,
+                                        vec![
+                                            call(Builtin::Multiply, vec![b.clone(), da]),
+                                            a.clone(),
+                                        ],
+                                    ),
+                                ],
+                            ),
+                        ],
+                    ))
+                }
+                _ => Err(arity(2)),
+            },
+            Builtin::Sqrt => {
+                let (a, da) = single()?;
+                Ok(call(
+                    Builtin::Divide,
+                    vec![
+                        da,
+                        call(
+                            Builtin::Multiply,
+                            vec![int(2), call(Builtin::Sqrt, vec![a.clone()])],
+                        ),
+                    ],
+                ))
+            }
+            Builtin::Abs => {
+                let (a, da) = single()?;
+                Ok(call(
+                    Builtin::Multiply,
+                    vec![
+                        da,
+                        call(
+                            Builtin::Divide,
+                            vec![a.clone(), call(Builtin::Abs, vec![a.clone()])],
+                        ),
+                    ],
+                ))
+            }
+            Builtin::Ln | Builtin::Log10 | Builtin::Log2 => {
+                let (a, da) = single()?;
+                let denominator: Expr = match name {
+                    Builtin::Ln => a.clone(),
+                    Builtin::Log10 => call(
+                        Builtin::Multiply,
+                        vec![a.clone(), call(Builtin::Ln, vec![int(10)])],
+                    ),
+                    _ => call(
+                        Builtin::Multiply,
+                        vec![a.clone(), call(Builtin::Ln, vec![int(2)])],
+                    ),
+                };
+                Ok(call(Builtin::Divide, vec![da, denominator]))
+            }
+            Builtin::Sin => {
+                let (a, da) = single()?;
+                Ok(trig_scale(
+                    call(
+                        Builtin::Multiply,
+                        vec![call(Builtin::Cos, vec![a.clone()]), da],
+                    ),
+                    into_radians,
+                ))
+            }
+            Builtin::Cos => {
+                let (a, da) = single()?;
+                Ok(trig_scale(
+                    negate(call(
+                        Builtin::Multiply,
+                        vec![call(Builtin::Sin, vec![a.clone()]), da],
+                    )),
+                    into_radians,
+                ))
+            }
+            Builtin::Tan => {
+                let (a, da) = single()?;
+                Ok(trig_scale(
+                    call(
+                        Builtin::Divide,
+                        vec![da, square(call(Builtin::Cos, vec![a.clone()]))],
+                    ),
+                    into_radians,
+                ))
+            }
+            Builtin::ArcSin | Builtin::ArcCos => {
+                let (a, da) = single()?;
+                let derivative: Expr = call(
+                    Builtin::Divide,
+                    vec![
+                        da,
+                        call(
+                            Builtin::Sqrt,
+                            vec![call(Builtin::Subtract, vec![int(1), square(a.clone())])],
+                        ),
+                    ],
+                );
+                let derivative: Expr = match name {
+                    Builtin::ArcSin => derivative,
+                    _ => negate(derivative),
+                };
+                Ok(trig_scale(derivative, from_radians))
+            }
+            Builtin::ArcTan => {
+                let (a, da) = single()?;
+                Ok(trig_scale(
+                    call(
+                        Builtin::Divide,
+                        vec![da, call(Builtin::Add, vec![int(1), square(a.clone())])],
+                    ),
+                    from_radians,
+                ))
+            }
+            // Both are linear, so they apply to the derivative unchanged
+            Builtin::Degrees | Builtin::Radians => {
+                let (_, da) = single()?;
+                Ok(call(name, vec![da]))
+            }
+            Builtin::Sinh | Builtin::Cosh => {
+                let (a, da) = single()?;
+                let other: Builtin = match name {
+                    Builtin::Sinh => Builtin::Cosh,
+                    _ => Builtin::Sinh,
+                };
+                Ok(call(
+                    Builtin::Multiply,
+                    vec![call(other, vec![a.clone()]), da],
+                ))
+            }
+            Builtin::Tanh => {
+                let (a, da) = single()?;
+                Ok(call(
+                    Builtin::Divide,
+                    vec![da, square(call(Builtin::Cosh, vec![a.clone()]))],
+                ))
+            }
+            Builtin::ArcSinh | Builtin::ArcCosh => {
+                let (a, da) = single()?;
+                let shift: Expr = match name {
+                    Builtin::ArcSinh => call(Builtin::Add, vec![square(a.clone()), int(1)]),
+                    _ => call(Builtin::Subtract, vec![square(a.clone()), int(1)]),
+                };
+                Ok(call(
+                    Builtin::Divide,
+                    vec![da, call(Builtin::Sqrt, vec![shift])],
+                ))
+            }
+            Builtin::ArcTanh => {
+                let (a, da) = single()?;
+                Ok(call(
+                    Builtin::Divide,
+                    vec![da, call(Builtin::Subtract, vec![int(1), square(a.clone())])],
+                ))
+            }
+            // The inner derivative as an expression, at its point when it has one
+            Builtin::Diff => {
+                let inner_var: &String = match args {
+                    [_, Expr::Var { name }] | [_, Expr::Var { name }, _] => name,
+                    [_, _] | [_, _, _] => {
+                        return Err(EvalError::TypeMismatch {
+                            builtin: name,
+                            arg_index: 1,
+                            expected: "a Variable Name",
+                        })
+                    }
+                    _ => {
+                        return Err(EvalError::Arity {
+                            builtin: name,
+                            expected: ArgCount::Between(2, 3),
+                            found: args.len(),
+                        })
+                    }
+                };
+                let inner: Expr = differentiate(&args[0], inner_var, env, angle_mode)?;
+                match args.get(2) {
+                    Some(at) => {
+                        let bindings: HashMap<&str, &Expr> =
+                            HashMap::from([(inner_var.as_str(), at)]);
+                        d(&substitute(&inner, &bindings))
+                    }
+                    None => d(&inner),
+                }
+            }
+            other => Err(not_differentiable(other.name())),
+        }
+    }
+}
+
+/// Replaces free uses of the bound names, stopping where a `let`, `lambda` or `defn` rebinds one.
+/// Binders that would capture a name used in a replacement are renamed, as in `x_1`
+pub fn substitute(expr: &Expr, bindings: &HashMap<&str, &Expr>) -> Expr {
+    let bindings: HashMap<String, Expr> = bindings
+        .iter()
+        .map(|(name, replacement)| (name.to_string(), (*replacement).clone()))
+        .collect();
+    return replace(expr, &bindings);
+}
+
+fn replace(expr: &Expr, bindings: &HashMap<String, Expr>) -> Expr {
+    if bindings.is_empty() {
+        return expr.clone();
+    }
+    match expr {
+        Expr::Var { name } => match bindings.get(name) {
+            Some(replacement) => replacement.clone(),
+            None => expr.clone(),
+        },
+        Expr::Let { name, value, body } => {
+            let (names, inner) = enter_binder(std::slice::from_ref(name), body, bindings);
+            Expr::Let {
+                name: names[0].clone(),
+                value: Box::new(replace(value, bindings)),
+                body: Box::new(replace(body, &inner)),
+            }
+        }
+        Expr::Set { name, value } => Expr::Set {
+            name: name.clone(),
+            value: Box::new(replace(value, bindings)),
+        },
+        // The function's own name is what it defines, so only its parameters can be renamed
+        Expr::Defn { name, params, body } => {
+            let mut outer: HashMap<String, Expr> = bindings.clone();
+            outer.remove(name);
+            let (params, inner) = enter_binder(params, body, &outer);
+            Expr::Defn {
+                name: name.clone(),
+                params,
+                body: Box::new(replace(body, &inner)),
+            }
+        }
+        Expr::Lambda { params, body } => {
+            let (params, inner) = enter_binder(params, body, bindings);
+            Expr::Lambda {
+                params,
+                body: Box::new(replace(body, &inner)),
+            }
+        }
+        Expr::Call { func, args } => Expr::Call {
+            func: Box::new(replace(func, bindings)),
+            args: args.iter().map(|arg| replace(arg, bindings)).collect(),
+        },
+        Expr::BuiltinFn { name, args } => Expr::BuiltinFn {
+            name: *name,
+            args: args.iter().map(|arg| replace(arg, bindings)).collect(),
+        },
+        atom => atom.clone(),
+    }
+}
+
+/// The names a binder ends up with and the bindings inside its body. Rebound names are left
+/// alone, and a name used in a replacement gets a fresh one that the body also switches to
+fn enter_binder(
+    names: &[String],
+    body: &Expr,
+    bindings: &HashMap<String, Expr>,
+) -> (Vec<String>, HashMap<String, Expr>) {
+    let mut inner: HashMap<String, Expr> = bindings.clone();
+    for name in names {
+        inner.remove(name);
+    }
+    let mut in_replacements: Vec<String> = Vec::new();
+    inner
+        .values()
+        .for_each(|replacement| names_in(replacement, &mut in_replacements));
+    if names.iter().all(|name| !in_replacements.contains(name)) {
+        return (names.to_vec(), inner);
+    }
+    let mut used: Vec<String> = in_replacements.clone();
+    names_in(body, &mut used);
+    used.extend(names.iter().cloned());
+    let mut renamed: Vec<String> = Vec::with_capacity(names.len());
+    for name in names {
+        if !in_replacements.contains(name) {
+            renamed.push(name.clone());
+            continue;
+        }
+        let fresh: String = (1..)
+            .map(|n| format!("{}_{}", name, n))
+            .find(|candidate| !used.contains(candidate))
+            .unwrap_or_else(|| unreachable!("there are always unused names"));
+        used.push(fresh.clone());
+        inner.insert(
+            name.clone(),
+            Expr::Var {
+                name: fresh.clone(),
+            },
+        );
+        renamed.push(fresh);
+    }
+    return (renamed, inner);
+}
+
+/// Every name in `expr`, whether free, bound or called
+fn names_in(expr: &Expr, names: &mut Vec<String>) {
+    let mut add = |name: &String| {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    };
+    match expr {
+        Expr::Var { name } => add(name),
+        Expr::Let { name, value, body } => {
+            add(name);
+            names_in(value, names);
+            names_in(body, names);
+        }
+        Expr::Set { name, value } => {
+            add(name);
+            names_in(value, names);
+        }
+        Expr::Defn { name, params, body } => {
+            add(name);
+            params.iter().for_each(add);
+            names_in(body, names);
+        }
+        Expr::Lambda { params, body } => {
+            params.iter().for_each(add);
+            names_in(body, names);
+        }
+        Expr::Call { func, args } => {
+            names_in(func, names);
+            args.iter().for_each(|arg| names_in(arg, names));
+        }
+        Expr::BuiltinFn { args, .. } => args.iter().for_each(|arg| names_in(arg, names)),
+        _ => {}
+    }
+}
+
+/// Whether the derivative of `expr` could be anything but zero
+fn mentions(expr: &Expr, var: &str, env: &Env) -> bool {
+    match expr {
+        Expr::Var { name } => name == var || one_param_function(name, env).is_some(),
+        Expr::Let { value, body, .. } => mentions(value, var, env) || mentions(body, var, env),
+        Expr::Set { value, .. } => mentions(value, var, env),
+        Expr::Defn { body, .. } | Expr::Lambda { body, .. } => mentions(body, var, env),
+        // Calls are expanded when differentiated, so assume they depend on `var`
+        Expr::Call { .. } => true,
+        Expr::BuiltinFn { args, .. } => args.iter().any(|arg| mentions(arg, var, env)),
+        _ => false,
+    }
+}
+
+fn one_param_function<'a>(name: &str, env: &'a Env) -> Option<&'a Value> {
+    return env.get(name).filter(|value| match value {
+        Value::FnV { fn_v } => fn_v.params.len() == 1,
+        _ => false,
+    });
+}
+
+fn not_differentiable(what: &str) -> EvalError {
+    return EvalError::NotDifferentiable {
+        what: what.to_string(),
+    };
+}
+
+fn call(name: Builtin, args: Vec<Expr>) -> Expr {
+    return Expr::BuiltinFn { name, args };
+}
+
+fn int(i: i64) -> Expr {
+    return Expr::Integer { i };
+}
+
+fn var_expr(name: &str) -> Expr {
+    return Expr::Var {
+        name: name.to_string(),
+    };
+}
+
+fn negate(expr: Expr) -> Expr {
+    return call(Builtin::Subtract, vec![int(0), expr]);
+}
+
+fn square(expr: Expr) -> Expr {
+    return call(Builtin::Pow, vec![expr, int(2)]);
+}
+
+#[cfg(test)]
+mod diff_tests {
+    use super::*;
+    use crate::func_expr_util::{
+        evaluate_prefix_expression, evaluate_prefix_expression_in_env,
+        evaluate_prefix_expression_with_config, expr_types::EvalConfig, parse_expr::parse_expr,
+        simplify_expr::simplify,
+    };
+
+    fn derivative(source: &str) -> String {
+        let expr: Expr = parse_expr(source).unwrap();
+        let derivative: Expr = differentiate(&expr, "x", &Env::new(), AngleMode::Radians).unwrap();
+        return simplify(&derivative).to_string();
+    }
+
+    #[test]
+    fn rules() {
+        assert_eq!("0", derivative("add(y, 3)"));
+        assert_eq!("mult(3, pow(x, 2))", derivative("pow(x, 3)"));
+        assert_eq!("mult(2, x)", derivative("mult(x, x)"));
+        assert_eq!("mult(2, cos(pow(x, 2)), x)", derivative("sin(pow(x, 2))"));
+        assert_eq!("sub(0, sin(x))", derivative("cos(x)"));
+        assert_eq!("pow(e, x)", derivative("pow(e, x)"));
+        assert_eq!("mult(pow(2, x), ln(2))", derivative("pow(2, x)"));
+        assert_eq!("div(1, x)", derivative("ln(x)"));
+        assert_eq!("div(1, mult(2, sqrt(x)))", derivative("sqrt(x)"));
+        assert_eq!("div(1, y)", derivative("div(x, y)"));
+        assert_eq!("sub(0, div(1, pow(x, 2)))", derivative("div(1, x)"));
+        assert_eq!("mult(6, x)", derivative("let(y, pow(x, 2), mult(y, 3))"));
+        assert_eq!("x", derivative("div(pow(x, 2), 2)"));
+        assert_eq!("mult(6, x)", derivative("diff(pow(x, 3), x)"));
+        assert_eq!("mult(2, y)", derivative("diff(mult(x, pow(y, 2)), y)"));
+        assert_eq!("0", derivative("diff(pow(x, 3), x, 2)"));
+    }
+
+    #[test]
+    fn substitution() {
+        let substituted = |source: &str, name: &str, replacement: &str| -> String {
+            let replacement: Expr = parse_expr(replacement).unwrap();
+            let bindings: HashMap<&str, &Expr> = HashMap::from([(name, &replacement)]);
+            return substitute(&parse_expr(source).unwrap(), &bindings).to_string();
+        };
+        // A shadowing let only takes the replacement in its value
+        assert_eq!(
+            "add(3, let(x, add(3, 1), mult(x, 2)))",
+            substituted("add(x, let(x, add(x, 1), mult(x, 2)))", "x", "3")
+        );
+        assert_eq!("lambda((x), x)", substituted("lambda(x, x)", "x", "3"));
+        // Binders that would capture a name in the replacement are renamed
+        assert_eq!(
+            "lambda((y_1), add(y, y_1))",
+            substituted("lambda(y, add(x, y))", "x", "y")
+        );
+        assert_eq!(
+            "let(y_2, 1, add(mult(y, y_1), y_2))",
+            substituted("let(y, 1, add(x, y))", "x", "mult(y, y_1)")
+        );
+        assert_eq!(
+            "defn(f, (y_1), add(y, y_1))",
+            substituted("defn(f, (y), add(x, y))", "x", "y")
+        );
+        // Derivatives of session functions called with the variable of an inner lambda
+        let mut env = Env::new();
+        let config = EvalConfig::default();
+        evaluate_prefix_expression_in_env("defn(g, (a), let(x, 2, mult(a, x)))", &mut env);
+        let expr: Expr = parse_expr("g(pow(x, 2))").unwrap();
+        assert_eq!(
+            "mult(4, x)",
+            simplify(&differentiate(&expr, "x", &env, config.angle_mode).unwrap()).to_string()
+        );
+    }
+
+    #[test]
+    fn not_differentiable() {
+        let expr: Expr = parse_expr("round(x)").unwrap();
+        assert_eq!(
+            "diff: Cannot Differentiate round",
+            differentiate(&expr, "x", &Env::new(), AngleMode::Radians)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn derivatives() {
+        let mut env = Env::new();
+        assert_eq!(
+            "lambda((x), mult(3, pow(x, 2)))",
+            evaluate_prefix_expression_in_env("diff(pow(x, 3), x)", &mut env)
+        );
+        assert_eq!(
+            "12",
+            evaluate_prefix_expression_in_env("diff(pow(x, 3), x, 2)", &mut env)
+        );
+        assert_eq!(
+            "lambda((x), mult(6, x))",
+            evaluate_prefix_expression("diff(diff(pow(x, 3), x), x)")
+        );
+        assert_eq!(
+            "6",
+            evaluate_prefix_expression("diff(diff(pow(x, 3), x), x, 1)")
+        );
+        // Derivatives are functions, so they can be stored and called
+        evaluate_prefix_expression_in_env("defn(f, (t), mult(t, sin(t)))", &mut env);
+        evaluate_prefix_expression_in_env("set(df, diff(f, t))", &mut env);
+        assert_eq!(
+            "lambda((t), add(sin(t), mult(t, cos(t))))",
+            evaluate_prefix_expression_in_env("df", &mut env)
+        );
+        assert_eq!("0", evaluate_prefix_expression_in_env("df(0)", &mut env));
+        assert_eq!(
+            "diff: Arg 2 Must Be a Variable Name",
+            evaluate_prefix_expression("diff(x, 2)")
+        );
+        evaluate_prefix_expression_in_env("defn(g, (x), g(x))", &mut env);
+        assert_eq!(
+            "g: Exceeded the Call Depth Limit of 1000",
+            evaluate_prefix_expression_in_env("diff(g(x), x)", &mut env)
+        );
+
+        // In degrees d/dx sin(x) is cos(x) pi/180
+        let degrees = EvalConfig {
+            angle_mode: AngleMode::Degrees,
+            ..Default::default()
+        };
+        assert_eq!(
+            "true",
+            evaluate_prefix_expression_with_config(
+                "eq(diff(sin(x), x, 0), div(pi, 180))",
+                &mut Env::new(),
+                &degrees
+            )
+        );
+        assert_eq!(
+            "x -> 2 * x + 2",
+            crate::expression_util::calc_expr("diff(x^2 + 2x, x)")
+        );
+    }
+}
@@ -1,11 +1,66 @@
 #[derive(Clone, Debug, PartialEq)]
 pub enum Expr {
-    Integer { i: i64 },
-    Float { f: f64 },
+    Integer {
+        i: i64,
+    },
+    BigInteger {
+        b: BigInt,
+    },
+    Float {
+        f: f64,
+    },
+    /// Literals like `i` and `2.5i`
+    Imaginary {
+        im: f64,
+    },
+    Boolean {
+        b: bool,
+    },
 
-    BuiltinFn { name: Builtin, args: Vec<Expr> },
+    Var {
+        name: String,
+    },
+    /// Binds `name` only while evaluating `body`
+    Let {
+        name: String,
+        value: Box<Expr>,
+        body: Box<Expr>,
+    },
+    /// Rebinds the nearest `name` in scope, or defines it for the rest of the session
+    Set {
+        name: String,
+        value: Box<Expr>,
+    },
+    /// Defines a named function in the current scope
+    Defn {
+        name: String,
+        params: Vec<String>,
+        body: Box<Expr>,
+    },
+    Lambda {
+        params: Vec<String>,
+        body: Box<Expr>,
+    },
+    /// Calls a user defined function, builtins use `BuiltinFn`
+    Call {
+        func: Box<Expr>,
+        args: Vec<Expr>,
+    },
+
+    BuiltinFn {
+        name: Builtin,
+        args: Vec<Expr>,
+    },
+}
+
+/// Canonical prefix form in call syntax, `print_expr` also has s-expression and infix forms
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", print_prefix(self, Syntax::Call))
+    }
 }
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Builtin {
     // Basic Operations
@@ -37,6 +92,10 @@ pub enum Builtin {
     ArcSin,
     ArcCos,
     ArcTan,
+    /// Radians to degrees, whatever the angle mode
+    Degrees,
+    /// Degrees to radians, whatever the angle mode
+    Radians,
 
     // Hyperbolic
     Sinh,
@@ -62,71 +121,830 @@ pub enum Builtin {
     // RNG
     Rand,
     RandInt,
+
+    // Conversion
+    ToFloat,
+    Numerator,
+    Denominator,
+
+    // Comparison
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+
+    // Logic, only evaluating the arguments needed
+    And,
+    Or,
+    Not,
+    If,
+
+    // Lists
+    List,
+    Range,
+    Map,
+    Filter,
+    Reduce,
+    Sum,
+    Product,
+    Len,
+    /// The element at a 0-based index, `?` in infix
+    Get,
+
+    // Linear Algebra
+    Matrix,
+    Identity,
+    Transpose,
+    Determinant,
+    Inverse,
+    MatMul,
+    /// Of two lists of up to 3 real numbers, `#` in infix
+    Cross,
+    /// A linear system with 2 arguments, an equation in a variable with 3 to 5
+    Solve,
+    Rank,
+    Eigenvalues,
+
+    // Symbolic
+    /// Takes its first argument as an expression rather than evaluating it
+    Diff,
+    Simplify,
+
+    // Numerical
+    /// Takes its first argument as an expression rather than evaluating it
+    Integrate,
+    Root,
+}
+
+impl Builtin {
+    /// Identifier the builtin is written as in prefix expressions
+    pub fn name(&self) -> &'static str {
+        match self {
+            Builtin::Add => "add",
+            Builtin::Subtract => "sub",
+            Builtin::Multiply => "mult",
+            Builtin::Divide => "div",
+            Builtin::PercentOf => "percent_of",
+            Builtin::Modulus => "mod",
+            Builtin::Abs => "abs",
+            Builtin::GCD => "gcd",
+            Builtin::Choose => "choose",
+            Builtin::Permutation => "permutation",
+            Builtin::Factorial => "factorial",
+            Builtin::Round => "round",
+            Builtin::Truncate => "truncate",
+            Builtin::Fraction => "fraction",
+            Builtin::Sin => "sin",
+            Builtin::Cos => "cos",
+            Builtin::Tan => "tan",
+            Builtin::ArcSin => "arcsin",
+            Builtin::ArcCos => "arccos",
+            Builtin::ArcTan => "arctan",
+            Builtin::Degrees => "deg",
+            Builtin::Radians => "rad",
+            Builtin::Sinh => "sinh",
+            Builtin::Cosh => "cosh",
+            Builtin::Tanh => "tanh",
+            Builtin::ArcSinh => "arcsinh",
+            Builtin::ArcCosh => "arccosh",
+            Builtin::ArcTanh => "arctanh",
+            Builtin::Ln => "ln",
+            Builtin::Log10 => "log_10",
+            Builtin::Log2 => "log_2",
+            Builtin::Pow => "pow",
+            Builtin::Sqrt => "sqrt",
+            Builtin::Max => "max",
+            Builtin::Min => "min",
+            Builtin::Rand => "rand",
+            Builtin::RandInt => "randint",
+            Builtin::ToFloat => "to_float",
+            Builtin::Numerator => "numerator",
+            Builtin::Denominator => "denominator",
+            Builtin::Equal => "eq",
+            Builtin::NotEqual => "ne",
+            Builtin::Less => "lt",
+            Builtin::LessEqual => "le",
+            Builtin::Greater => "gt",
+            Builtin::GreaterEqual => "ge",
+            Builtin::And => "and",
+            Builtin::Or => "or",
+            Builtin::Not => "not",
+            Builtin::If => "if",
+            Builtin::List => "list",
+            Builtin::Range => "range",
+            Builtin::Map => "map",
+            Builtin::Filter => "filter",
+            Builtin::Reduce => "reduce",
+            Builtin::Sum => "sum",
+            Builtin::Product => "product",
+            Builtin::Len => "len",
+            Builtin::Get => "get",
+            Builtin::Matrix => "matrix",
+            Builtin::Identity => "identity",
+            Builtin::Transpose => "transpose",
+            Builtin::Determinant => "det",
+            Builtin::Inverse => "inverse",
+            Builtin::MatMul => "matmul",
+            Builtin::Cross => "cross",
+            Builtin::Solve => "solve",
+            Builtin::Rank => "rank",
+            Builtin::Eigenvalues => "eigenvalues",
+            Builtin::Diff => "diff",
+            Builtin::Simplify => "simplify",
+            Builtin::Integrate => "integrate",
+            Builtin::Root => "root",
+        }
+    }
 }
 
+/// `BigIntV` only holds integers outside the range of `IntV`,
+/// `RationalV` only holds fractions that are not whole numbers,
+/// `ComplexV` only holds numbers with a non-zero imaginary part,
+/// and `MatrixV` always has at least one row and one column
 #[derive(Clone, Debug, PartialEq)]
 pub enum Value {
     IntV { i_v: i64 },
+    BigIntV { b_v: BigInt },
+    RationalV { r_v: BigRational },
     FloatV { f_v: f64 },
-    Error { msg: String },
+    ComplexV { c_v: Complex64 },
+    BoolV { bool_v: bool },
+    ListV { l_v: Vec<Value> },
+    MatrixV { m_v: DMatrix<f64> },
+    FnV { fn_v: Box<Closure> },
+}
+
+/// A function value, `captured` holds the local scopes it was made in.
+/// Session variables are looked up when it is called, so later definitions are visible
+#[derive(Clone, Debug, PartialEq)]
+pub struct Closure {
+    /// Lets a named function call itself from inside a local scope
+    pub name: Option<String>,
+    pub params: Vec<String>,
+    pub body: Box<Expr>,
+    pub captured: Vec<Scope>,
+}
+
+impl Closure {
+    /// What errors and the result display call the function
+    pub fn display_name(&self) -> &str {
+        return self.name.as_deref().unwrap_or("lambda");
+    }
+
+    /// The `lambda` expression that would build this function
+    pub fn as_lambda(&self) -> Expr {
+        return Expr::Lambda {
+            params: self.params.clone(),
+            body: self.body.clone(),
+        };
+    }
 }
 
+impl Value {
+    /// Narrows back to `IntV` whenever the integer fits in an i64
+    pub fn from_big_int(b_v: BigInt) -> Value {
+        match b_v.to_i64() {
+            Some(i_v) => Value::IntV { i_v },
+            None => Value::BigIntV { b_v },
+        }
+    }
+
+    /// Narrows whole numbers back to `IntV` or `BigIntV`
+    pub fn from_rational(r_v: BigRational) -> Value {
+        if r_v.is_integer() {
+            return Value::from_big_int(r_v.to_integer());
+        }
+        return Value::RationalV { r_v };
+    }
+
+    /// Narrows numbers without an imaginary part back to `FloatV`
+    pub fn from_complex(c_v: Complex64) -> Value {
+        if c_v.im == 0.0 {
+            return Value::FloatV { f_v: c_v.re };
+        }
+        return Value::ComplexV { c_v };
+    }
+
+    pub fn is_number(&self) -> bool {
+        return matches!(
+            self,
+            Value::IntV { .. }
+                | Value::BigIntV { .. }
+                | Value::RationalV { .. }
+                | Value::FloatV { .. }
+                | Value::ComplexV { .. }
+        );
+    }
+
+    pub fn is_real(&self) -> bool {
+        return self.is_number() && !matches!(self, Value::ComplexV { .. });
+    }
+
+    /// NaN for complex numbers, which have no real equivalent, and for anything but a number
+    pub fn as_f64(&self) -> f64 {
+        match self {
+            Value::IntV { i_v } => *i_v as f64,
+            Value::BigIntV { b_v } => b_v.to_f64().unwrap_or(f64::NAN),
+            Value::RationalV { r_v } => r_v.to_f64().unwrap_or(f64::NAN),
+            Value::FloatV { f_v } => *f_v,
+            _ => f64::NAN,
+        }
+    }
+
+    /// An expression that evaluates to the value, rationals as a division and lists as `list`
+    pub fn as_expr(&self) -> Expr {
+        let builtin = |name: Builtin, args: Vec<Expr>| -> Expr {
+            return Expr::BuiltinFn { name, args };
+        };
+        match self {
+            Value::IntV { i_v } => Expr::Integer { i: *i_v },
+            Value::BigIntV { b_v } => Expr::BigInteger { b: b_v.clone() },
+            Value::RationalV { r_v } => builtin(
+                Builtin::Divide,
+                vec![
+                    Value::from_big_int(r_v.numer().clone()).as_expr(),
+                    Value::from_big_int(r_v.denom().clone()).as_expr(),
+                ],
+            ),
+            Value::FloatV { f_v } => Expr::Float { f: *f_v },
+            Value::ComplexV { c_v } if c_v.re == 0.0 => Expr::Imaginary { im: c_v.im },
+            Value::ComplexV { c_v } => builtin(
+                if c_v.im < 0.0 {
+                    Builtin::Subtract
+                } else {
+                    Builtin::Add
+                },
+                vec![
+                    Expr::Float { f: c_v.re },
+                    Expr::Imaginary { im: c_v.im.abs() },
+                ],
+            ),
+            Value::BoolV { bool_v } => Expr::Boolean { b: *bool_v },
+            Value::ListV { l_v } => {
+                builtin(Builtin::List, l_v.iter().map(Value::as_expr).collect())
+            }
+            Value::MatrixV { m_v } => builtin(
+                Builtin::Matrix,
+                m_v.row_iter()
+                    .map(|row| {
+                        builtin(
+                            Builtin::List,
+                            row.iter().map(|f| Expr::Float { f: *f }).collect(),
+                        )
+                    })
+                    .collect(),
+            ),
+            Value::FnV { fn_v } => match &fn_v.name {
+                Some(name) => Expr::Var { name: name.clone() },
+                None => fn_v.as_lambda(),
+            },
+        }
+    }
+
+    pub fn as_complex(&self) -> Complex64 {
+        match self {
+            Value::ComplexV { c_v } => *c_v,
+            real => Complex64::new(real.as_f64(), 0.0),
+        }
+    }
+
+    /// Exact for every variant, unlike comparing `as_f64` to 0
+    pub fn is_zero(&self) -> bool {
+        match self {
+            Value::IntV { i_v } => *i_v == 0,
+            Value::BigIntV { b_v } => b_v.is_zero(),
+            Value::RationalV { r_v } => r_v.is_zero(),
+            Value::FloatV { f_v } => *f_v == 0.0,
+            Value::ComplexV { c_v } => c_v.is_zero(),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::IntV { i_v } => write!(f, "{}", i_v),
+            Value::BigIntV { b_v } => write!(f, "{}", b_v),
+            Value::RationalV { r_v } => write!(f, "{}", r_v),
+            Value::FloatV { f_v } => write!(f, "{}", format_float(*f_v)),
+            Value::ComplexV { c_v } => {
+                if c_v.re != 0.0 {
+                    write!(f, "{}", format_float(c_v.re))?;
+                    // NaN has no sign to separate the parts, so it gets a +
+                    if c_v.im >= 0.0 || c_v.im.is_nan() {
+                        write!(f, "+")?;
+                    }
+                }
+                // Unit coefficients are left off, so i rather than 1i
+                match c_v.im {
+                    1.0 => write!(f, "i"),
+                    -1.0 => write!(f, "-i"),
+                    // NaN and inf are words, so the i is set apart rather than read as infi
+                    im if !im.is_finite() => write!(f, "{}*i", format_float(im)),
+                    im => write!(f, "{}i", format_float(im)),
+                }
+            }
+            Value::BoolV { bool_v } => write!(f, "{}", bool_v),
+            Value::ListV { l_v } => {
+                write!(f, "[")?;
+                for (index, item) in l_v.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            // One row per line with the columns lined up
+            Value::MatrixV { m_v } => {
+                // Adding 0 turns the -0 that elimination leaves behind into 0
+                let cells: Vec<String> = m_v.iter().map(|cell| format_float(cell + 0.0)).collect();
+                let widths: Vec<usize> = (0..m_v.ncols())
+                    .map(|col| {
+                        (0..m_v.nrows())
+                            .map(|row| cells[col * m_v.nrows() + row].len())
+                            .max()
+                            .unwrap_or(0)
+                    })
+                    .collect();
+                for row in 0..m_v.nrows() {
+                    if row > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "[")?;
+                    for (col, width) in widths.iter().enumerate() {
+                        if col > 0 {
+                            write!(f, "  ")?;
+                        }
+                        write!(
+                            f,
+                            "{:>width$}",
+                            cells[col * m_v.nrows() + row],
+                            width = width
+                        )?;
+                    }
+                    write!(f, "]")?;
+                }
+                Ok(())
+            }
+            // Anonymous functions, like the ones diff returns, show their whole body
+            Value::FnV { fn_v } => match fn_v.name {
+                Some(_) => write!(f, "{}({})", fn_v.display_name(), fn_v.params.join(", ")),
+                None => write!(f, "{}", fn_v.as_lambda()),
+            },
+        }
+    }
+}
+
+/// Plain decimals, except for magnitudes that would need long runs of zeros,
+/// like Planck's constant, which use scientific notation
+pub fn format_float(f_v: f64) -> String {
+    let magnitude: f64 = f_v.abs();
+    if magnitude != 0.0 && magnitude.is_finite() && !(1e-6..1e21).contains(&magnitude) {
+        return format!("{:e}", f_v);
+    }
+    return f_v.to_string();
+}
+
+#[cfg(feature = "gui")]
 impl From<Value> for SharedString {
     fn from(value: Value) -> Self {
-        match value {
-            Value::IntV { i_v } => i_v.to_string().into(),
-            Value::FloatV { f_v } => f_v.to_string().into(),
-            Value::Error { msg } => msg.into(),
-        }
+        return value.to_string().into();
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum ValuePair {
     IntInt { a: i64, b: i64 },
+    BigBig { a: BigInt, b: BigInt },
+    RatRat { a: BigRational, b: BigRational },
     FloatInt { a: f64, b: i64 },
     IntFloat { a: i64, b: f64 },
     FloatFloat { a: f64, b: f64 },
+    ComplexComplex { a: Complex64, b: Complex64 },
 }
 
+/// Mixed integer pairs widen to `BigBig`, integers paired with rationals widen to `RatRat`,
+/// anything but an `IntV` paired with a float becomes a float,
+/// and anything paired with a complex number becomes complex
 pub fn pair_up(a_v: Value, b_v: Value) -> ValuePair {
-    match a_v {
-        Value::IntV { i_v } => {
-            let a_iv: i64 = i_v;
-            match b_v {
-                Value::IntV { i_v } => ValuePair::IntInt { a: a_iv, b: i_v },
-                Value::FloatV { f_v } => ValuePair::IntFloat { a: a_iv, b: f_v },
-                _ => panic!(),
+    match (a_v, b_v) {
+        (a_v @ Value::ComplexV { .. }, b_v) | (a_v, b_v @ Value::ComplexV { .. }) => {
+            ValuePair::ComplexComplex {
+                a: a_v.as_complex(),
+                b: b_v.as_complex(),
             }
         }
-        Value::FloatV { f_v } => {
-            let a_fv: f64 = f_v;
-            match b_v {
-                Value::IntV { i_v } => ValuePair::FloatInt { a: a_fv, b: i_v },
-                Value::FloatV { f_v } => ValuePair::FloatFloat { a: a_fv, b: f_v },
-                _ => panic!(),
+        (Value::IntV { i_v: a }, Value::IntV { i_v: b }) => ValuePair::IntInt { a, b },
+        (Value::IntV { i_v: a }, Value::FloatV { f_v: b }) => ValuePair::IntFloat { a, b },
+        (Value::FloatV { f_v: a }, Value::IntV { i_v: b }) => ValuePair::FloatInt { a, b },
+        (Value::FloatV { f_v: a }, Value::FloatV { f_v: b }) => ValuePair::FloatFloat { a, b },
+        (Value::BigIntV { b_v: a }, Value::BigIntV { b_v: b }) => ValuePair::BigBig { a, b },
+        (Value::BigIntV { b_v: a }, Value::IntV { i_v: b }) => ValuePair::BigBig { a, b: b.into() },
+        (Value::IntV { i_v: a }, Value::BigIntV { b_v: b }) => ValuePair::BigBig { a: a.into(), b },
+        (Value::RationalV { r_v: a }, Value::RationalV { r_v: b }) => ValuePair::RatRat { a, b },
+        (Value::RationalV { r_v: a }, Value::IntV { i_v: b }) => ValuePair::RatRat {
+            a,
+            b: BigRational::from_integer(b.into()),
+        },
+        (Value::RationalV { r_v: a }, Value::BigIntV { b_v: b }) => ValuePair::RatRat {
+            a,
+            b: BigRational::from_integer(b),
+        },
+        (Value::IntV { i_v: a }, Value::RationalV { r_v: b }) => ValuePair::RatRat {
+            a: BigRational::from_integer(a.into()),
+            b,
+        },
+        (Value::BigIntV { b_v: a }, Value::RationalV { r_v: b }) => ValuePair::RatRat {
+            a: BigRational::from_integer(a),
+            b,
+        },
+        (a_v, b_v) => ValuePair::FloatFloat {
+            a: a_v.as_f64(),
+            b: b_v.as_f64(),
+        },
+    }
+}
+
+pub type Scope = HashMap<String, Value>;
+
+/// Variables visible while evaluating, the first scope lasts the whole session
+#[derive(Clone, Debug, PartialEq)]
+pub struct Env {
+    scopes: Vec<Scope>,
+    call_depth: usize,
+}
+
+impl Env {
+    pub fn new() -> Env {
+        return Env {
+            scopes: vec![HashMap::new()],
+            call_depth: 0,
+        };
+    }
+
+    /// Innermost binding of `name`
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        return self.scopes.iter().rev().find_map(|scope| scope.get(name));
+    }
+
+    /// Binds `name` in the innermost scope, shadowing any outer binding
+    pub fn define(&mut self, name: &str, value: Value) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), value);
+        }
+    }
+
+    /// Rebinds the innermost `name`, or defines it in the session scope when it is unbound
+    pub fn assign(&mut self, name: &str, value: Value) {
+        match self
+            .scopes
+            .iter_mut()
+            .rev()
+            .find(|scope| scope.contains_key(name))
+        {
+            Some(scope) => scope.insert(name.to_string(), value),
+            None => self.scopes[0].insert(name.to_string(), value),
+        };
+    }
+
+    pub fn push_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    /// The session scope is never popped
+    pub fn pop_scope(&mut self) -> Option<Scope> {
+        if self.scopes.len() > 1 {
+            return self.scopes.pop();
+        }
+        return None;
+    }
+
+    /// Every scope but the session scope, for closures to keep
+    pub fn capture(&self) -> Vec<Scope> {
+        return self.scopes[1..].to_vec();
+    }
+
+    /// Replaces every scope but the session scope, returning the replaced ones
+    pub fn swap_locals(&mut self, locals: Vec<Scope>) -> Vec<Scope> {
+        let replaced: Vec<Scope> = self.scopes.split_off(1);
+        self.scopes.extend(locals);
+        return replaced;
+    }
+
+    /// How many user function calls are in progress
+    pub fn call_depth(&self) -> usize {
+        return self.call_depth;
+    }
+
+    pub fn set_call_depth(&mut self, call_depth: usize) {
+        self.call_depth = call_depth;
+    }
+
+    /// Forgets every session variable
+    pub fn clear(&mut self) {
+        *self = Env::new();
+    }
+}
+
+impl Default for Env {
+    fn default() -> Self {
+        return Env::new();
+    }
+}
+
+/// What an integer result that does not fit in an i64 becomes
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum IntOverflow {
+    #[default]
+    PromoteToBigInt,
+    PromoteToFloat,
+}
+
+/// The unit trig builtins take angles in and inverse trig builtins give them back in
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum AngleMode {
+    #[default]
+    Radians,
+    Degrees,
+    Gradians,
+}
+
+impl AngleMode {
+    /// Accepts the full name or the usual calculator abbreviation, ignoring case
+    pub fn from_name(name: &str) -> Option<AngleMode> {
+        match name.trim().to_lowercase().as_str() {
+            "radians" | "rad" => Some(AngleMode::Radians),
+            "degrees" | "deg" => Some(AngleMode::Degrees),
+            "gradians" | "grad" => Some(AngleMode::Gradians),
+            _ => None,
+        }
+    }
+
+    /// The size of a whole turn in this unit
+    pub fn full_turn(&self) -> f64 {
+        match self {
+            AngleMode::Radians => std::f64::consts::TAU,
+            AngleMode::Degrees => 360.0,
+            AngleMode::Gradians => 400.0,
+        }
+    }
+
+    pub fn to_radians(&self, angle: f64) -> f64 {
+        match self {
+            AngleMode::Radians => angle,
+            _ => angle / self.full_turn() * std::f64::consts::TAU,
+        }
+    }
+
+    pub fn from_radians(&self, angle: f64) -> f64 {
+        match self {
+            AngleMode::Radians => angle,
+            _ => angle / std::f64::consts::TAU * self.full_turn(),
+        }
+    }
+}
+
+impl fmt::Display for AngleMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AngleMode::Radians => write!(f, "Radians"),
+            AngleMode::Degrees => write!(f, "Degrees"),
+            AngleMode::Gradians => write!(f, "Gradians"),
+        }
+    }
+}
+
+/// Settings that change how `interp_expr` evaluates
+#[derive(Clone, Debug, PartialEq)]
+pub struct EvalConfig {
+    pub int_overflow: IntOverflow,
+    pub angle_mode: AngleMode,
+    /// Deepest chain of user function calls before evaluation stops
+    pub max_call_depth: usize,
+    /// Error `integrate` aims for when not given one, absolute or relative to the value
+    pub integration_tolerance: f64,
+}
+
+impl Default for EvalConfig {
+    fn default() -> Self {
+        return EvalConfig {
+            int_overflow: IntOverflow::default(),
+            angle_mode: AngleMode::default(),
+            max_call_depth: 1000,
+            integration_tolerance: 1e-10,
+        };
+    }
+}
+
+/// How many arguments a builtin accepts
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ArgCount {
+    Exactly(usize),
+    Between(usize, usize),
+    AtLeast(usize),
+}
+
+impl fmt::Display for ArgCount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArgCount::Exactly(count) => write!(f, "{}", count),
+            ArgCount::Between(min, max) => write!(f, "{} to {}", min, max),
+            ArgCount::AtLeast(min) => write!(f, "at Least {}", min),
+        }
+    }
+}
+
+/// Reasons evaluating an `Expr` can fail, argument indices start at 0
+#[derive(Clone, Debug, PartialEq)]
+pub enum EvalError {
+    Arity {
+        builtin: Builtin,
+        expected: ArgCount,
+        found: usize,
+    },
+    TypeMismatch {
+        builtin: Builtin,
+        arg_index: usize,
+        expected: &'static str,
+    },
+    Domain {
+        builtin: Builtin,
+        arg_index: usize,
+        reason: &'static str,
+    },
+    Overflow {
+        builtin: Builtin,
+    },
+    DivisionByZero {
+        builtin: Builtin,
+        arg_index: usize,
+    },
+    UnboundVariable {
+        name: String,
+    },
+    NotCallable {
+        name: String,
+    },
+    CallArity {
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+    RecursionLimit {
+        name: String,
+        limit: usize,
+    },
+    NotDifferentiable {
+        what: String,
+    },
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvalError::Arity {
+                builtin,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{}: Expected {} Args, Found {}",
+                builtin.name(),
+                expected,
+                found
+            ),
+            EvalError::TypeMismatch {
+                builtin,
+                arg_index,
+                expected,
+            } => write!(
+                f,
+                "{}: Arg {} Must Be {}",
+                builtin.name(),
+                arg_index + 1,
+                expected
+            ),
+            EvalError::Domain {
+                builtin,
+                arg_index,
+                reason,
+            } => write!(f, "{}: Arg {} {}", builtin.name(), arg_index + 1, reason),
+            EvalError::Overflow { builtin } => write!(f, "{}: Integer Overflow", builtin.name()),
+            EvalError::DivisionByZero { builtin, arg_index } => write!(
+                f,
+                "{}: Arg {} Divides by Zero",
+                builtin.name(),
+                arg_index + 1
+            ),
+            EvalError::UnboundVariable { name } => write!(f, "{}: Variable Is Not Defined", name),
+            EvalError::NotCallable { name } => write!(f, "{}: Is Not a Function", name),
+            EvalError::CallArity {
+                name,
+                expected,
+                found,
+            } => write!(f, "{}: Expected {} Args, Found {}", name, expected, found),
+            EvalError::RecursionLimit { name, limit } => {
+                write!(f, "{}: Exceeded the Call Depth Limit of {}", name, limit)
+            }
+            EvalError::NotDifferentiable { what } => {
+                write!(f, "diff: Cannot Differentiate {}", what)
             }
         }
-        _ => panic!(),
+    }
+}
+impl Error for EvalError {}
+
+/// How expressions are written, `add(1, 2)`, `(add 1 2)` or `1 + 2`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Syntax {
+    #[default]
+    Call,
+    SExpr,
+    Infix,
+}
+
+/// Byte offsets into the source, `end` is exclusive
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Where a parse error happened, lines and columns start at 1 and count characters.
+/// The caret in `snippet` is placed by display width, so wide characters take two cells
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceLocation {
+    pub span: Span,
+    pub line: usize,
+    pub column: usize,
+    /// The line the error is on with a caret under the offending text
+    pub snippet: String,
+}
+
+impl SourceLocation {
+    pub fn new(source: &str, span: Span) -> SourceLocation {
+        let before: &str = &source[..span.start];
+        let line_start: usize = before.rfind('\n').map_or(0, |index| index + 1);
+        let line_text: &str = source[line_start..].lines().next().unwrap_or("");
+        let line_end: usize = line_start + line_text.len();
+        // Tabs are copied into the padding so the caret lines up however they are shown
+        let padding: String = source[line_start..span.start]
+            .chars()
+            .map(|c| match c {
+                '\t' => "\t".to_string(),
+                _ => " ".repeat(c.width().unwrap_or(0)),
+            })
+            .collect();
+        // Spans running past the end of the line are cut off there
+        let caret_end: usize = span.end.min(line_end).max(span.start);
+        let caret_len: usize = source[span.start..caret_end].width().max(1);
+        return SourceLocation {
+            span,
+            line: before.matches('\n').count() + 1,
+            column: source[line_start..span.start].chars().count() + 1,
+            snippet: format!("{}\n{}{}", line_text, padding, "^".repeat(caret_len)),
+        };
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ParseError {
     pub message: String,
+    /// None for errors that are not about one place in the source
+    pub location: Option<SourceLocation>,
 }
 
 impl ParseError {
     fn new(message: &str) -> ParseError {
         return ParseError {
             message: message.to_string(),
+            location: None,
+        };
+    }
+
+    /// An error pointing at `span` in `source`
+    pub fn at(message: &str, source: &str, span: Span) -> ParseError {
+        return ParseError {
+            message: message.to_string(),
+            location: Some(SourceLocation::new(source, span)),
         };
     }
 }
 
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.message)
+        match &self.location {
+            Some(location) => write!(
+                f,
+                "{} at Line {}, Column {}\n{}",
+                self.message, location.line, location.column, location.snippet
+            ),
+            None => write!(f, "{}", self.message),
+        }
     }
 }
 impl Error for ParseError {}
@@ -143,6 +961,16 @@ impl From<&str> for ParseError {
     }
 }
 
-use std::{error::Error, fmt};
+use std::{collections::HashMap, error::Error, fmt};
+
+use nalgebra::DMatrix;
+use num_bigint::BigInt;
+use num_complex::Complex64;
+use num_rational::BigRational;
+use num_traits::{ToPrimitive, Zero};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use super::print_expr::print_prefix;
 
+#[cfg(feature = "gui")]
 use slint::SharedString;
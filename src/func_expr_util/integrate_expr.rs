@@ -0,0 +1,304 @@
+/// Most pieces the interval is split into before `integrate` settles for its estimate
+const MAX_INTERVALS: usize = 1000;
+
+/// Kronrod nodes on [0, 1), the odd indices are also the Gauss nodes
+const KRONROD_NODES: [f64; 8] = [
+    0.991_455_371_120_812_6,
+    0.949_107_912_342_758_5,
+    0.864_864_423_359_769_1,
+    0.741_531_185_599_394_4,
+    0.586_087_235_467_691_1,
+    0.405_845_151_377_397_2,
+    0.207_784_955_007_898_5,
+    0.0,
+];
+const KRONROD_WEIGHTS: [f64; 8] = [
+    0.022_935_322_010_529_22,
+    0.063_092_092_629_978_55,
+    0.104_790_010_322_250_2,
+    0.140_653_259_715_525_9,
+    0.169_004_726_639_267_9,
+    0.190_350_578_064_785_4,
+    0.204_432_940_075_298_9,
+    0.209_482_141_084_727_8,
+];
+/// Weights of the 7 point Gauss rule, the last is for the center
+const GAUSS_WEIGHTS: [f64; 4] = [
+    0.129_484_966_168_869_7,
+    0.279_705_391_489_276_7,
+    0.381_830_050_505_118_9,
+    0.417_959_183_673_469_4,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quadrature {
+    pub value: f64,
+    /// Estimated absolute error of `value`
+    pub error: f64,
+}
+
+/// The integral of `f` from `a` to `b` by adaptive 15 point Gauss-Kronrod quadrature,
+/// splitting the piece with the largest error until the total error is within
+/// `tolerance`, absolute or relative to the value, whichever is looser.
+/// Infinite bounds are mapped onto finite ones first. When the tolerance cannot be met
+/// the best estimate comes back with its larger error
+pub fn integrate<E>(
+    f: &mut dyn FnMut(f64) -> Result<f64, E>,
+    a: f64,
+    b: f64,
+    tolerance: f64,
+) -> Result<Quadrature, E> {
+    if a == b {
+        return Ok(Quadrature {
+            value: 0.0,
+            error: 0.0,
+        });
+    } else if a > b {
+        let flipped: Quadrature = integrate(f, b, a, tolerance)?;
+        return Ok(Quadrature {
+            value: -flipped.value,
+            error: flipped.error,
+        });
+    }
+    match (a.is_finite(), b.is_finite()) {
+        (true, true) => adaptive(f, a, b, tolerance),
+        // x = a + t / (1 - t)
+        (true, false) => adaptive(
+            &mut |t: f64| -> Result<f64, E> {
+                let scale: f64 = 1.0 / (1.0 - t);
+                return Ok(f(a + t * scale)? * scale * scale);
+            },
+            0.0,
+            1.0,
+            tolerance,
+        ),
+        // x = b - (1 - t) / t
+        (false, true) => adaptive(
+            &mut |t: f64| -> Result<f64, E> {
+                return Ok(f(b - (1.0 - t) / t)? / (t * t));
+            },
+            0.0,
+            1.0,
+            tolerance,
+        ),
+        // x = t / (1 - t^2)
+        (false, false) => adaptive(
+            &mut |t: f64| -> Result<f64, E> {
+                let denominator: f64 = 1.0 - t * t;
+                return Ok(f(t / denominator)? * (1.0 + t * t) / (denominator * denominator));
+            },
+            -1.0,
+            1.0,
+            tolerance,
+        ),
+    }
+}
+
+/// A piece of the interval with its estimate
+struct Piece {
+    a: f64,
+    b: f64,
+    estimate: Quadrature,
+}
+
+fn adaptive<E>(
+    f: &mut dyn FnMut(f64) -> Result<f64, E>,
+    a: f64,
+    b: f64,
+    tolerance: f64,
+) -> Result<Quadrature, E> {
+    let mut pieces: Vec<Piece> = vec![Piece {
+        a,
+        b,
+        estimate: gauss_kronrod(f, a, b)?,
+    }];
+    loop {
+        let value: f64 = pieces.iter().map(|piece| piece.estimate.value).sum();
+        let error: f64 = pieces.iter().map(|piece| piece.estimate.error).sum();
+        if error <= tolerance.max(tolerance * value.abs()) || pieces.len() >= MAX_INTERVALS {
+            return Ok(Quadrature { value, error });
+        }
+        let worst: usize = pieces
+            .iter()
+            .enumerate()
+            .max_by(|(_, x), (_, y)| x.estimate.error.total_cmp(&y.estimate.error))
+            .map_or(0, |(index, _)| index);
+        let Piece { a, b, .. } = pieces.swap_remove(worst);
+        let middle: f64 = 0.5 * (a + b);
+        // Too narrow to split any further in floating point
+        if middle <= a || middle >= b {
+            return Ok(Quadrature { value, error });
+        }
+        pieces.push(Piece {
+            a,
+            b: middle,
+            estimate: gauss_kronrod(f, a, middle)?,
+        });
+        pieces.push(Piece {
+            a: middle,
+            b,
+            estimate: gauss_kronrod(f, middle, b)?,
+        });
+    }
+}
+
+/// One 15 point Kronrod estimate, with QUADPACK's error estimate from the embedded Gauss rule
+fn gauss_kronrod<E>(
+    f: &mut dyn FnMut(f64) -> Result<f64, E>,
+    a: f64,
+    b: f64,
+) -> Result<Quadrature, E> {
+    let center: f64 = 0.5 * (a + b);
+    let half_length: f64 = 0.5 * (b - a);
+    let f_center: f64 = f(center)?;
+    let mut gauss: f64 = f_center * GAUSS_WEIGHTS[3];
+    let mut kronrod: f64 = f_center * KRONROD_WEIGHTS[7];
+    let mut kronrod_abs: f64 = kronrod.abs();
+    let mut pairs: [(f64, f64); 7] = [(0.0, 0.0); 7];
+    for (index, pair) in pairs.iter_mut().enumerate() {
+        let offset: f64 = half_length * KRONROD_NODES[index];
+        let (left, right) = (f(center - offset)?, f(center + offset)?);
+        kronrod += KRONROD_WEIGHTS[index] * (left + right);
+        kronrod_abs += KRONROD_WEIGHTS[index] * (left.abs() + right.abs());
+        if index % 2 == 1 {
+            gauss += GAUSS_WEIGHTS[index / 2] * (left + right);
+        }
+        *pair = (left, right);
+    }
+    let mean: f64 = 0.5 * kronrod;
+    let mut spread: f64 = KRONROD_WEIGHTS[7] * (f_center - mean).abs();
+    for (index, (left, right)) in pairs.iter().enumerate() {
+        spread += KRONROD_WEIGHTS[index] * ((left - mean).abs() + (right - mean).abs());
+    }
+
+    let scale: f64 = half_length.abs();
+    let (spread, kronrod_abs) = (spread * scale, kronrod_abs * scale);
+    let mut error: f64 = ((kronrod - gauss) * half_length).abs();
+    if spread != 0.0 && error != 0.0 {
+        error = spread * (200.0 * error / spread).powf(1.5).min(1.0);
+    }
+    // Nothing is more accurate than rounding allows
+    error = error.max(50.0 * f64::EPSILON * kronrod_abs);
+    return Ok(Quadrature {
+        value: kronrod * half_length,
+        error,
+    });
+}
+
+#[cfg(test)]
+mod integrate_tests {
+    use super::*;
+    use crate::func_expr_util::{
+        evaluate_prefix_expression, evaluate_prefix_expression_in_env,
+        expr_types::{AngleMode, Env, EvalConfig, Expr, Value},
+        interp_expr::interp_expr_with_env,
+        parse_expr::parse_expr,
+    };
+
+    fn integral(f: fn(f64) -> f64, a: f64, b: f64) -> Quadrature {
+        return integrate::<()>(&mut |x| Ok(f(x)), a, b, 1e-10).unwrap();
+    }
+
+    #[test]
+    fn finite_intervals() {
+        let cube: Quadrature = integral(|x| x * x * x, 0.0, 2.0);
+        assert!((cube.value - 4.0).abs() < 1e-13);
+        assert!(cube.error < 1e-10);
+        assert!((integral(f64::sin, 0.0, std::f64::consts::PI).value - 2.0).abs() < 1e-12);
+        assert!((integral(|x| x * x, 1.0, 0.0).value + 1.0 / 3.0).abs() < 1e-13);
+        assert_eq!(0.0, integral(f64::exp, 3.0, 3.0).value);
+    }
+
+    #[test]
+    fn hard_integrands() {
+        // A square root singularity at 0
+        let sqrt: Quadrature = integral(|x| 1.0 / x.sqrt(), 0.0, 1.0);
+        assert!((sqrt.value - 2.0).abs() < 1e-8);
+        // Oscillating
+        let oscillating: Quadrature = integral(|x| (50.0 * x).cos(), 0.0, 1.0);
+        assert!((oscillating.value - 50f64.sin() / 50.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn infinite_intervals() {
+        let gaussian: Quadrature = integral(|x| (-x * x).exp(), f64::NEG_INFINITY, f64::INFINITY);
+        assert!((gaussian.value - std::f64::consts::PI.sqrt()).abs() < 1e-9);
+        let decay: Quadrature = integral(|x| (-x).exp(), 0.0, f64::INFINITY);
+        assert!((decay.value - 1.0).abs() < 1e-9);
+        let left: Quadrature = integral(f64::exp, f64::NEG_INFINITY, 0.0);
+        assert!((left.value - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn errors_stop_integration() {
+        let mut calls: usize = 0;
+        let result = integrate(
+            &mut |x: f64| {
+                calls += 1;
+                if x > 0.5 {
+                    return Err("too far");
+                }
+                return Ok(x);
+            },
+            0.0,
+            1.0,
+            1e-10,
+        );
+        assert_eq!(Err("too far"), result);
+        assert!(calls <= 15);
+    }
+
+    #[test]
+    fn integrals() {
+        let evaluated = |raw: &str, config: &EvalConfig| -> (f64, f64) {
+            let expr: Expr = parse_expr(raw).unwrap();
+            match interp_expr_with_env(&expr, &mut Env::new(), config).unwrap() {
+                Value::ListV { l_v } => return (l_v[0].as_f64(), l_v[1].as_f64()),
+                other => panic!("{} is not a list", other),
+            }
+        };
+        let config = EvalConfig::default();
+        let (value, error) = evaluated("integrate(pow(x, 2), x, 0, 1)", &config);
+        assert!((value - 1.0 / 3.0).abs() < 1e-14);
+        assert!(error <= 1e-10);
+        let (value, _) = evaluated("integrate(sin(t), t, pi, 0)", &config);
+        assert!((value + 2.0).abs() < 1e-12);
+        let (value, _) = evaluated("integrate(pow(e, mult(-1, x, x)), x, -inf, inf)", &config);
+        assert!((value - std::f64::consts::PI.sqrt()).abs() < 1e-9);
+        // A looser tolerance takes fewer steps and reports a larger error
+        let (value, error) = evaluated("integrate(div(1, sqrt(x)), x, 0, 1, 1e-3)", &config);
+        assert!((value - 2.0).abs() <= error && error > 1e-10);
+
+        // Session functions and variables can appear in the integrand
+        let mut env = Env::new();
+        evaluate_prefix_expression_in_env("defn(f, (x), mult(k, x))", &mut env);
+        evaluate_prefix_expression_in_env("set(k, 4)", &mut env);
+        assert!(
+            evaluate_prefix_expression_in_env("integrate(f(x), x, 0, 1)", &mut env)
+                .starts_with("[2, ")
+        );
+
+        let degrees = EvalConfig {
+            angle_mode: AngleMode::Degrees,
+            ..Default::default()
+        };
+        let (value, _) = evaluated("integrate(cos(x), x, 0, 90)", &degrees);
+        assert!((value - 180.0 / std::f64::consts::PI).abs() < 1e-9);
+        assert!(
+            crate::expression_util::calc_expr("integrate(x^2 + 1, x, 0, 3)").starts_with("[12, ")
+        );
+
+        assert_eq!(
+            "integrate: Arg 1 Is Not Finite on the Interval",
+            evaluate_prefix_expression("integrate(pow(sub(x, 0.5), -1.0), x, 0, 1)")
+        );
+        assert_eq!(
+            "integrate: Arg 5 Must Be Positive",
+            evaluate_prefix_expression("integrate(x, x, 0, 1, 0)")
+        );
+        assert_eq!(
+            "integrate: Arg 2 Must Be a Variable Name",
+            evaluate_prefix_expression("integrate(x, 1, 0, 1)")
+        );
+    }
+}
@@ -1,729 +1,3476 @@
+use std::{
+    cmp::Ordering,
+    f64::consts::{FRAC_1_SQRT_2, PI},
+};
+
+use nalgebra::{DMatrix, Schur, SymmetricEigen};
+use num_bigint::BigInt;
+use num_complex::Complex64;
+use num_integer::Integer as _;
+use num_rational::BigRational;
+use num_traits::{Signed, ToPrimitive, Zero};
 use rand::{random, thread_rng, Rng};
 
-use super::expr_types::*;
+use super::{
+    constants::constant_value,
+    diff_expr::differentiate,
+    expr_types::*,
+    integrate_expr::{integrate, Quadrature},
+    simplify_expr::{free_variables, simplify},
+    solve_expr::{brent, newton, polynomial_coefficients, polynomial_roots},
+};
+
+/// Largest big integer result, in bits, before an operation reports overflow
+const MAX_BIG_INT_BITS: u64 = 1 << 18;
+
+/// Stack left, in bytes, before evaluation moves onto a new segment
+const STACK_RED_ZONE: usize = 256 * 1024;
+const STACK_SEGMENT: usize = 4 * 1024 * 1024;
+
+/// Largest size `identity` will build
+const MAX_MATRIX_DIM: i64 = 1 << 10;
+/// Iterations the eigenvalue decompositions get before giving up
+const MAX_EIGEN_ITERATIONS: usize = 10_000;
+
+pub fn interp_expr(expr: &Expr) -> Result<Value, EvalError> {
+    return interp_expr_with_config(expr, &EvalConfig::default());
+}
+
+pub fn interp_expr_with_config(expr: &Expr, config: &EvalConfig) -> Result<Value, EvalError> {
+    return interp_expr_with_env(expr, &mut Env::new(), config);
+}
+
+/// Evaluates with the variables in `env`, which keeps anything defined by `set`
+pub fn interp_expr_with_env(
+    expr: &Expr,
+    env: &mut Env,
+    config: &EvalConfig,
+) -> Result<Value, EvalError> {
+    use Expr::*;
+    // Nested builtins nest several interpreter frames each, so deep expressions get a fresh
+    // stack segment rather than overflowing
+    return stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT, || match expr {
+        Integer { i } => Ok(Value::IntV { i_v: *i }),
+        BigInteger { b } => Ok(Value::from_big_int(b.clone())),
+        Float { f } => Ok(Value::FloatV { f_v: *f }),
+        Imaginary { im } => Ok(Value::from_complex(Complex64::new(0.0, *im))),
+        Boolean { b } => Ok(Value::BoolV { bool_v: *b }),
+        Var { name } => match env.get(name).cloned().or_else(|| constant_value(name)) {
+            Some(value) => Ok(value),
+            None => Err(EvalError::UnboundVariable { name: name.clone() }),
+        },
+        Let { name, value, body } => {
+            let value: Value = interp_expr_with_env(value, env, config)?;
+            env.push_scope();
+            env.define(name, value);
+            let result: Result<Value, EvalError> = interp_expr_with_env(body, env, config);
+            env.pop_scope();
+            result
+        }
+        Set { name, value } => {
+            let value: Value = interp_expr_with_env(value, env, config)?;
+            env.assign(name, value.clone());
+            Ok(value)
+        }
+        Defn { name, params, body } => {
+            let fn_v: Value = Value::FnV {
+                fn_v: Box::new(Closure {
+                    name: Some(name.clone()),
+                    params: params.clone(),
+                    body: body.clone(),
+                    captured: env.capture(),
+                }),
+            };
+            env.define(name, fn_v.clone());
+            Ok(fn_v)
+        }
+        Lambda { params, body } => Ok(Value::FnV {
+            fn_v: Box::new(Closure {
+                name: None,
+                params: params.clone(),
+                body: body.clone(),
+                captured: env.capture(),
+            }),
+        }),
+        Call { func, args } => match interp_expr_with_env(func, env, config)? {
+            Value::FnV { fn_v } => call_closure(&fn_v, args, env, config),
+            _ => Err(EvalError::NotCallable {
+                name: match &**func {
+                    Var { name } => name.clone(),
+                    _ => "lambda".to_string(),
+                },
+            }),
+        },
+        BuiltinFn { name, args } => interp_builtin(name, args, env, config),
+    });
+}
+
+fn interp_builtin(
+    name: &Builtin,
+    args: &[Expr],
+    env: &mut Env,
+    config: &EvalConfig,
+) -> Result<Value, EvalError> {
+    let mut rng: rand::prelude::ThreadRng = thread_rng();
+
+    match name {
+        Builtin::Sin | Builtin::Cos | Builtin::Tan => {
+            check_arity(*name, args, 1)?;
+            let angle: Value = number_arg(*name, args, 0, env, config)?;
+            trig(*name, angle, config.angle_mode)
+        }
+        Builtin::ArcSin | Builtin::ArcCos | Builtin::ArcTan => {
+            check_arity(*name, args, 1)?;
+            let x_v: Value = number_arg(*name, args, 0, env, config)?;
+            Ok(inverse_trig(*name, x_v, config.angle_mode))
+        }
+        Builtin::Degrees => do_single_arg(
+            *name,
+            f64::to_degrees,
+            |c_v| c_v.scale(180.0 / PI),
+            |_| true,
+            args,
+            env,
+            config,
+        ),
+        Builtin::Radians => do_single_arg(
+            *name,
+            f64::to_radians,
+            |c_v| c_v.scale(PI / 180.0),
+            |_| true,
+            args,
+            env,
+            config,
+        ),
+        Builtin::Ln => do_single_arg(
+            *name,
+            f64::ln,
+            Complex64::ln,
+            |x| x >= 0.0,
+            args,
+            env,
+            config,
+        ),
+        Builtin::Log10 => do_single_arg(
+            *name,
+            f64::log10,
+            Complex64::log10,
+            |x| x >= 0.0,
+            args,
+            env,
+            config,
+        ),
+        Builtin::Log2 => do_single_arg(
+            *name,
+            f64::log2,
+            Complex64::log2,
+            |x| x >= 0.0,
+            args,
+            env,
+            config,
+        ),
+        Builtin::Sinh => do_single_arg(
+            *name,
+            f64::sinh,
+            Complex64::sinh,
+            |_| true,
+            args,
+            env,
+            config,
+        ),
+        Builtin::Cosh => do_single_arg(
+            *name,
+            f64::cosh,
+            Complex64::cosh,
+            |_| true,
+            args,
+            env,
+            config,
+        ),
+        Builtin::Tanh => do_single_arg(
+            *name,
+            f64::tanh,
+            Complex64::tanh,
+            |_| true,
+            args,
+            env,
+            config,
+        ),
+        Builtin::ArcSinh => do_single_arg(
+            *name,
+            f64::asinh,
+            Complex64::asinh,
+            |_| true,
+            args,
+            env,
+            config,
+        ),
+        Builtin::ArcCosh => do_single_arg(
+            *name,
+            f64::acosh,
+            Complex64::acosh,
+            |x| x >= 1.0,
+            args,
+            env,
+            config,
+        ),
+        Builtin::ArcTanh => do_single_arg(
+            *name,
+            f64::atanh,
+            Complex64::atanh,
+            |x| x.abs() <= 1.0,
+            args,
+            env,
+            config,
+        ),
+        Builtin::Sqrt => do_single_arg(
+            *name,
+            f64::sqrt,
+            Complex64::sqrt,
+            |x| x >= 0.0,
+            args,
+            env,
+            config,
+        ),
+        Builtin::Pow => {
+            check_arity(*name, args, 2)?;
+            let a_v: Value = number_arg(*name, args, 0, env, config)?;
+            let b_v: Value = number_arg(*name, args, 1, env, config)?;
+            match pair_up(a_v, b_v) {
+                ValuePair::IntInt { a, b } => {
+                    if b < 0 {
+                        return rational_pow(
+                            *name,
+                            &BigRational::from_integer(a.into()),
+                            &b.into(),
+                        );
+                    }
+                    promote(
+                        *name,
+                        config,
+                        u32::try_from(b).ok().and_then(|b| a.checked_pow(b)),
+                        || big_pow(&BigInt::from(a), b as u64),
+                        || (a as f64).powf(b as f64),
+                    )
+                }
+                ValuePair::BigBig { a, b } => {
+                    rational_pow(*name, &BigRational::from_integer(a), &b)
+                }
+                ValuePair::RatRat { a, b } => {
+                    if b.is_integer() {
+                        rational_pow(*name, &a, &b.to_integer())
+                    } else {
+                        Ok(float_pow(
+                            a.to_f64().unwrap_or(f64::NAN),
+                            b.to_f64().unwrap_or(f64::NAN),
+                        ))
+                    }
+                }
+                ValuePair::FloatInt { a, b } => match i32::try_from(b) {
+                    Ok(b_pow) => Ok(Value::FloatV { f_v: a.powi(b_pow) }),
+                    Err(_) => Ok(Value::FloatV {
+                        f_v: a.powf(b as f64),
+                    }),
+                },
+                ValuePair::IntFloat { a, b } => Ok(float_pow(a as f64, b)),
+                ValuePair::FloatFloat { a, b } => Ok(float_pow(a, b)),
+                ValuePair::ComplexComplex { a, b } => {
+                    // Whole real powers multiply out, which avoids the rounding in powc
+                    if b.im == 0.0 && b.re.fract() == 0.0 && b.re.abs() <= i32::MAX as f64 {
+                        Ok(Value::from_complex(a.powi(b.re as i32)))
+                    } else {
+                        Ok(Value::from_complex(a.powc(b)))
+                    }
+                }
+            }
+        }
+        Builtin::Abs => {
+            check_arity(*name, args, 1)?;
+            match interp_expr_with_env(&args[0], env, config)? {
+                Value::IntV { i_v } => promote(
+                    *name,
+                    config,
+                    i_v.checked_abs(),
+                    || Some(BigInt::from(i_v).abs()),
+                    || (i_v as f64).abs(),
+                ),
+                Value::BigIntV { b_v } => Ok(Value::from_big_int(b_v.abs())),
+                Value::RationalV { r_v } => Ok(Value::from_rational(r_v.abs())),
+                Value::FloatV { f_v } => Ok(Value::FloatV { f_v: f_v.abs() }),
+                Value::ComplexV { c_v } => Ok(Value::FloatV { f_v: c_v.norm() }),
+                // The length of a list as a vector
+                Value::ListV { l_v } => {
+                    let mut norm_sqr: f64 = 0.0;
+                    for item in l_v {
+                        norm_sqr += expect_number(*name, 0, item)?.as_complex().norm_sqr();
+                    }
+                    Ok(Value::FloatV {
+                        f_v: norm_sqr.sqrt(),
+                    })
+                }
+                _ => Err(EvalError::TypeMismatch {
+                    builtin: *name,
+                    arg_index: 0,
+                    expected: "a Number or a List",
+                }),
+            }
+        }
+        Builtin::Choose | Builtin::Permutation => {
+            check_arity(*name, args, 2)?;
+            let n: BigInt = big_int_arg(*name, args, 0, env, config)?;
+            let k: BigInt = big_int_arg(*name, args, 1, env, config)?;
+            if n.is_negative() {
+                return Err(EvalError::Domain {
+                    builtin: *name,
+                    arg_index: 0,
+                    reason: "Must Not Be Negative",
+                });
+            }
+            if k.is_negative() || k > n {
+                return Err(EvalError::Domain {
+                    builtin: *name,
+                    arg_index: 1,
+                    reason: "Must Be Between 0 and Arg 1",
+                });
+            }
+            match (n.to_i64(), k.to_i64(), *name == Builtin::Choose) {
+                (Some(n), Some(k), true) => promote(
+                    *name,
+                    config,
+                    choose(n, k),
+                    || big_choose(&n.into(), &k.into()),
+                    || float_choose(n, k),
+                ),
+                (Some(n), Some(k), false) => promote(
+                    *name,
+                    config,
+                    permutation(n, k),
+                    || big_permutation(&n.into(), &k.into()),
+                    || float_permutation(n, k),
+                ),
+                (_, _, is_choose) => {
+                    let result: Option<BigInt> = if is_choose {
+                        big_choose(&n, &k)
+                    } else {
+                        big_permutation(&n, &k)
+                    };
+                    match result {
+                        Some(b_v) => Ok(Value::from_big_int(b_v)),
+                        None => Err(EvalError::Overflow { builtin: *name }),
+                    }
+                }
+            }
+        }
+        Builtin::Round => {
+            check_arity(*name, args, 1)?;
+            match real_arg(*name, args, 0, env, config)? {
+                Value::FloatV { f_v } => Ok(Value::FloatV { f_v: f_v.round() }),
+                Value::RationalV { r_v } => Ok(Value::from_rational(r_v.round())),
+                integer => Ok(integer),
+            }
+        }
+        Builtin::Truncate => {
+            check_arity(*name, args, 1)?;
+            match real_arg(*name, args, 0, env, config)? {
+                Value::FloatV { f_v } => Ok(Value::FloatV { f_v: f_v.trunc() }),
+                Value::RationalV { r_v } => Ok(Value::from_rational(r_v.trunc())),
+                integer => Ok(integer),
+            }
+        }
+        Builtin::Fraction => {
+            check_arity(*name, args, 1)?;
+            match real_arg(*name, args, 0, env, config)? {
+                Value::FloatV { f_v } => Ok(Value::FloatV { f_v: f_v.fract() }),
+                Value::RationalV { r_v } => Ok(Value::from_rational(r_v.fract())),
+                _ => Ok(Value::FloatV { f_v: 0.0 }),
+            }
+        }
+        Builtin::Max => do_binop(
+            *name,
+            args,
+            env,
+            config,
+            |l, r| Some(l.max(r)),
+            |l, r| l.max(r).clone(),
+            |l, r| l.max(r).clone(),
+            |l, r| l.max(r),
+        ),
+        Builtin::Min => do_binop(
+            *name,
+            args,
+            env,
+            config,
+            |l, r| Some(l.min(r)),
+            |l, r| l.min(r).clone(),
+            |l, r| l.min(r).clone(),
+            |l, r| l.min(r),
+        ),
+        Builtin::Rand => {
+            check_arity(*name, args, 0)?;
+            Ok(Value::FloatV { f_v: rng.gen() })
+        }
+        Builtin::RandInt => match args.len() {
+            0 => Ok(Value::IntV {
+                i_v: random::<i64>(),
+            }),
+            1 => {
+                let a: i64 = int_arg(*name, args, 0, env, config)?;
+                if a <= 0 {
+                    return Err(EvalError::Domain {
+                        builtin: *name,
+                        arg_index: 0,
+                        reason: "Must Be Positive",
+                    });
+                }
+                Ok(Value::IntV {
+                    i_v: rng.gen_range(0..a),
+                })
+            }
+            2 => {
+                let a: i64 = int_arg(*name, args, 0, env, config)?;
+                let b: i64 = int_arg(*name, args, 1, env, config)?;
+                if a >= b {
+                    return Err(EvalError::Domain {
+                        builtin: *name,
+                        arg_index: 1,
+                        reason: "Must Be Greater Than Arg 1",
+                    });
+                }
+                Ok(Value::IntV {
+                    i_v: rng.gen_range(a..b),
+                })
+            }
+            found => Err(EvalError::Arity {
+                builtin: *name,
+                expected: ArgCount::Between(0, 2),
+                found,
+            }),
+        },
+        Builtin::GCD => {
+            check_arity(*name, args, 2)?;
+            let a: BigInt = big_int_arg(*name, args, 0, env, config)?;
+            let b: BigInt = big_int_arg(*name, args, 1, env, config)?;
+            Ok(Value::from_big_int(a.gcd(&b)))
+        }
+        Builtin::PercentOf => {
+            check_arity(*name, args, 2)?;
+            let l_v: Value = real_arg(*name, args, 0, env, config)?;
+            let r_v: Value = real_arg(*name, args, 1, env, config)?;
+            Ok(Value::FloatV {
+                f_v: l_v.as_f64() * (r_v.as_f64() / 100.0),
+            })
+        }
+        Builtin::Add => do_binop(
+            *name,
+            args,
+            env,
+            config,
+            i64::checked_add,
+            |a, b| a + b,
+            |a, b| a + b,
+            |a, b| a + b,
+        ),
+        Builtin::Subtract => do_binop(
+            *name,
+            args,
+            env,
+            config,
+            i64::checked_sub,
+            |a, b| a - b,
+            |a, b| a - b,
+            |a, b| a - b,
+        ),
+        Builtin::Multiply => do_binop(
+            *name,
+            args,
+            env,
+            config,
+            i64::checked_mul,
+            |a, b| a * b,
+            |a, b| a * b,
+            |a, b| a * b,
+        ),
+        Builtin::Divide => {
+            check_arity(*name, args, 2)?;
+            let a_v: Value = interp_expr_with_env(&args[0], env, config)?;
+            let b_v: Value = number_arg(*name, args, 1, env, config)?;
+            if b_v.is_zero() {
+                return Err(EvalError::DivisionByZero {
+                    builtin: *name,
+                    arg_index: 1,
+                });
+            }
+            // Lists and matrices divide element by element
+            match a_v {
+                Value::ListV { l_v } => {
+                    let mut quotients: Vec<Value> = Vec::with_capacity(l_v.len());
+                    for item in l_v {
+                        quotients.push(divide(expect_number(*name, 0, item)?, b_v.clone()));
+                    }
+                    Ok(Value::ListV { l_v: quotients })
+                }
+                Value::MatrixV { m_v } => Ok(Value::MatrixV {
+                    m_v: m_v / expect_real(*name, 1, b_v)?.as_f64(),
+                }),
+                a_v => Ok(divide(expect_number(*name, 0, a_v)?, b_v)),
+            }
+        }
+        Builtin::Modulus => do_binop(
+            *name,
+            args,
+            env,
+            config,
+            i64::checked_rem,
+            |a, b| a % b,
+            |a, b| a % b,
+            |a, b| a % b,
+        ),
+        Builtin::ToFloat => {
+            check_arity(*name, args, 1)?;
+            let a_v: Value = real_arg(*name, args, 0, env, config)?;
+            Ok(Value::FloatV { f_v: a_v.as_f64() })
+        }
+        Builtin::Equal | Builtin::NotEqual => {
+            check_arity(*name, args, 2)?;
+            let a_v: Value = interp_expr_with_env(&args[0], env, config)?;
+            let b_v: Value = interp_expr_with_env(&args[1], env, config)?;
+            let equal: bool = values_equal(a_v, b_v);
+            Ok(Value::BoolV {
+                bool_v: equal == (*name == Builtin::Equal),
+            })
+        }
+        Builtin::Less | Builtin::LessEqual | Builtin::Greater | Builtin::GreaterEqual => {
+            check_arity(*name, args, 2)?;
+            let a_v: Value = real_arg(*name, args, 0, env, config)?;
+            let b_v: Value = real_arg(*name, args, 1, env, config)?;
+            let ordering: Option<Ordering> = match pair_up(a_v, b_v) {
+                ValuePair::IntInt { a, b } => Some(a.cmp(&b)),
+                ValuePair::BigBig { a, b } => Some(a.cmp(&b)),
+                ValuePair::RatRat { a, b } => Some(a.cmp(&b)),
+                ValuePair::FloatInt { a, b } => a.partial_cmp(&(b as f64)),
+                ValuePair::IntFloat { a, b } => (a as f64).partial_cmp(&b),
+                ValuePair::FloatFloat { a, b } => a.partial_cmp(&b),
+                ValuePair::ComplexComplex { a: _, b: _ } => None,
+            };
+            // NaN is unordered, so every comparison with it is false
+            let bool_v: bool = match (name, ordering) {
+                (_, None) => false,
+                (Builtin::Less, Some(ordering)) => ordering.is_lt(),
+                (Builtin::LessEqual, Some(ordering)) => ordering.is_le(),
+                (Builtin::Greater, Some(ordering)) => ordering.is_gt(),
+                (_, Some(ordering)) => ordering.is_ge(),
+            };
+            Ok(Value::BoolV { bool_v })
+        }
+        Builtin::And | Builtin::Or => {
+            check_arity(*name, args, 2)?;
+            // The second argument only matters when the first does not decide the result
+            let short_circuit: bool = *name == Builtin::Or;
+            if bool_arg(*name, args, 0, env, config)? == short_circuit {
+                return Ok(Value::BoolV {
+                    bool_v: short_circuit,
+                });
+            }
+            Ok(Value::BoolV {
+                bool_v: bool_arg(*name, args, 1, env, config)?,
+            })
+        }
+        Builtin::Not => {
+            check_arity(*name, args, 1)?;
+            Ok(Value::BoolV {
+                bool_v: !bool_arg(*name, args, 0, env, config)?,
+            })
+        }
+        Builtin::If => {
+            check_arity(*name, args, 3)?;
+            if bool_arg(*name, args, 0, env, config)? {
+                interp_expr_with_env(&args[1], env, config)
+            } else {
+                interp_expr_with_env(&args[2], env, config)
+            }
+        }
+        Builtin::List => {
+            let mut l_v: Vec<Value> = Vec::with_capacity(args.len());
+            for arg in args {
+                l_v.push(interp_expr_with_env(arg, env, config)?);
+            }
+            Ok(Value::ListV { l_v })
+        }
+        Builtin::Range => {
+            let (start, stop, step): (Value, Value, Value) = match args.len() {
+                1 => (
+                    Value::IntV { i_v: 0 },
+                    real_arg(*name, args, 0, env, config)?,
+                    Value::IntV { i_v: 1 },
+                ),
+                2 => (
+                    real_arg(*name, args, 0, env, config)?,
+                    real_arg(*name, args, 1, env, config)?,
+                    Value::IntV { i_v: 1 },
+                ),
+                3 => (
+                    real_arg(*name, args, 0, env, config)?,
+                    real_arg(*name, args, 1, env, config)?,
+                    real_arg(*name, args, 2, env, config)?,
+                ),
+                found => {
+                    return Err(EvalError::Arity {
+                        builtin: *name,
+                        expected: ArgCount::Between(1, 3),
+                        found,
+                    })
+                }
+            };
+            if step.is_zero() {
+                return Err(EvalError::Domain {
+                    builtin: *name,
+                    arg_index: 2,
+                    reason: "Must Not Be Zero",
+                });
+            }
+            range(*name, start, stop, step)
+        }
+        Builtin::Map => {
+            check_arity(*name, args, 2)?;
+            let closure: Box<Closure> = fn_arg(*name, args, 0, env, config)?;
+            let mut l_v: Vec<Value> = Vec::new();
+            for item in list_arg(*name, args, 1, env, config)? {
+                l_v.push(apply_closure(&closure, vec![item], env, config)?);
+            }
+            Ok(Value::ListV { l_v })
+        }
+        Builtin::Filter => {
+            check_arity(*name, args, 2)?;
+            let closure: Box<Closure> = fn_arg(*name, args, 0, env, config)?;
+            let mut l_v: Vec<Value> = Vec::new();
+            for item in list_arg(*name, args, 1, env, config)? {
+                match apply_closure(&closure, vec![item.clone()], env, config)? {
+                    Value::BoolV { bool_v: true } => l_v.push(item),
+                    Value::BoolV { bool_v: false } => {}
+                    _ => {
+                        return Err(EvalError::TypeMismatch {
+                            builtin: *name,
+                            arg_index: 0,
+                            expected: "a Function Returning a Boolean",
+                        })
+                    }
+                }
+            }
+            Ok(Value::ListV { l_v })
+        }
+        Builtin::Reduce => {
+            if args.len() < 2 || args.len() > 3 {
+                return Err(EvalError::Arity {
+                    builtin: *name,
+                    expected: ArgCount::Between(2, 3),
+                    found: args.len(),
+                });
+            }
+            let closure: Box<Closure> = fn_arg(*name, args, 0, env, config)?;
+            let mut items = list_arg(*name, args, 1, env, config)?.into_iter();
+            // Without a starting value the first element is used
+            let mut acc: Value = if args.len() == 3 {
+                interp_expr_with_env(&args[2], env, config)?
+            } else {
+                items.next().ok_or(EvalError::Domain {
+                    builtin: *name,
+                    arg_index: 1,
+                    reason: "Must Not Be Empty",
+                })?
+            };
+            for item in items {
+                acc = apply_closure(&closure, vec![acc, item], env, config)?;
+            }
+            Ok(acc)
+        }
+        Builtin::Sum | Builtin::Product => {
+            check_arity(*name, args, 1)?;
+            let items: Vec<Value> = list_arg(*name, args, 0, env, config)?;
+            if items.is_empty() {
+                let i_v: i64 = if *name == Builtin::Sum { 0 } else { 1 };
+                return Ok(Value::IntV { i_v });
+            }
+            let operands: Vec<(usize, Value)> = items.into_iter().map(|item| (0, item)).collect();
+            if *name == Builtin::Sum {
+                fold_numbers(
+                    *name,
+                    operands,
+                    config,
+                    i64::checked_add,
+                    |a, b| a + b,
+                    |a, b| a + b,
+                    |a, b| a + b,
+                )
+            } else {
+                fold_numbers(
+                    *name,
+                    operands,
+                    config,
+                    i64::checked_mul,
+                    |a, b| a * b,
+                    |a, b| a * b,
+                    |a, b| a * b,
+                )
+            }
+        }
+        Builtin::Len => {
+            check_arity(*name, args, 1)?;
+            let items: Vec<Value> = list_arg(*name, args, 0, env, config)?;
+            Ok(Value::IntV {
+                i_v: items.len() as i64,
+            })
+        }
+        Builtin::Get => {
+            check_arity(*name, args, 2)?;
+            let items: Vec<Value> = list_arg(*name, args, 0, env, config)?;
+            let index: i64 = int_arg(*name, args, 1, env, config)?;
+            if index < 0 {
+                return Err(EvalError::Domain {
+                    builtin: *name,
+                    arg_index: 1,
+                    reason: "Must Not Be Negative",
+                });
+            }
+            items
+                .into_iter()
+                .nth(index as usize)
+                .ok_or(EvalError::Domain {
+                    builtin: *name,
+                    arg_index: 1,
+                    reason: "Must Be Less Than the Length of Arg 1",
+                })
+        }
+        Builtin::Solve if args.len() > 2 => interp_numerical(*name, args, env, config),
+        Builtin::Matrix
+        | Builtin::Identity
+        | Builtin::Transpose
+        | Builtin::Determinant
+        | Builtin::Inverse
+        | Builtin::MatMul
+        | Builtin::Cross
+        | Builtin::Solve
+        | Builtin::Rank
+        | Builtin::Eigenvalues => interp_linear_algebra(*name, args, env, config),
+        Builtin::Diff | Builtin::Simplify => interp_symbolic(*name, args, env, config),
+        Builtin::Integrate | Builtin::Root => interp_numerical(*name, args, env, config),
+        Builtin::Numerator | Builtin::Denominator => {
+            check_arity(*name, args, 1)?;
+            let r_v: BigRational = match number_arg(*name, args, 0, env, config)? {
+                Value::IntV { i_v } => BigRational::from_integer(i_v.into()),
+                Value::BigIntV { b_v } => BigRational::from_integer(b_v),
+                Value::RationalV { r_v } => r_v,
+                _ => {
+                    return Err(EvalError::TypeMismatch {
+                        builtin: *name,
+                        arg_index: 0,
+                        expected: "an Integer or Rational",
+                    })
+                }
+            };
+            if *name == Builtin::Numerator {
+                Ok(Value::from_big_int(r_v.numer().clone()))
+            } else {
+                Ok(Value::from_big_int(r_v.denom().clone()))
+            }
+        }
+        Builtin::Factorial => {
+            check_arity(*name, args, 1)?;
+            let n: i64 = int_arg(*name, args, 0, env, config)?;
+            if n < 0 {
+                return Err(EvalError::Domain {
+                    builtin: *name,
+                    arg_index: 0,
+                    reason: "Must Not Be Negative",
+                });
+            }
+            promote(
+                *name,
+                config,
+                factorial(n),
+                || big_permutation(&n.into(), &n.into()),
+                || float_permutation(n, n),
+            )
+        }
+    }
+}
+
+/// Runs the body with the closure's captured scopes in place of the caller's
+fn call_closure(
+    closure: &Closure,
+    args: &[Expr],
+    env: &mut Env,
+    config: &EvalConfig,
+) -> Result<Value, EvalError> {
+    if args.len() != closure.params.len() {
+        return Err(EvalError::CallArity {
+            name: closure.display_name().to_string(),
+            expected: closure.params.len(),
+            found: args.len(),
+        });
+    }
+    let mut arg_values: Vec<Value> = Vec::with_capacity(args.len());
+    for arg in args {
+        arg_values.push(interp_expr_with_env(arg, env, config)?);
+    }
+    return apply_closure(closure, arg_values, env, config);
+}
+
+/// Calls a closure on arguments that are already evaluated
+fn apply_closure(
+    closure: &Closure,
+    arg_values: Vec<Value>,
+    env: &mut Env,
+    config: &EvalConfig,
+) -> Result<Value, EvalError> {
+    if arg_values.len() != closure.params.len() {
+        return Err(EvalError::CallArity {
+            name: closure.display_name().to_string(),
+            expected: closure.params.len(),
+            found: arg_values.len(),
+        });
+    }
+    if env.call_depth() >= config.max_call_depth {
+        return Err(EvalError::RecursionLimit {
+            name: closure.display_name().to_string(),
+            limit: config.max_call_depth,
+        });
+    }
+
+    let caller_locals: Vec<Scope> = env.swap_locals(closure.captured.clone());
+    env.push_scope();
+    if let Some(name) = &closure.name {
+        env.define(
+            name,
+            Value::FnV {
+                fn_v: Box::new(closure.clone()),
+            },
+        );
+    }
+    for (param, value) in closure.params.iter().zip(arg_values) {
+        env.define(param, value);
+    }
+    env.set_call_depth(env.call_depth() + 1);
+    // Each call can nest several large interpreter frames, so deep recursion gets a fresh stack segment
+    let result: Result<Value, EvalError> =
+        stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT, || {
+            interp_expr_with_env(&closure.body, env, config)
+        });
+    env.set_call_depth(env.call_depth() - 1);
+    env.swap_locals(caller_locals);
+    return result;
+}
+
+fn check_arity(builtin: Builtin, args: &[Expr], expected: usize) -> Result<(), EvalError> {
+    if args.len() != expected {
+        return Err(EvalError::Arity {
+            builtin,
+            expected: ArgCount::Exactly(expected),
+            found: args.len(),
+        });
+    }
+    return Ok(());
+}
+
+/// Evaluates an argument that has to be an integer small enough for an i64
+fn int_arg(
+    builtin: Builtin,
+    args: &[Expr],
+    arg_index: usize,
+    env: &mut Env,
+    config: &EvalConfig,
+) -> Result<i64, EvalError> {
+    match interp_expr_with_env(&args[arg_index], env, config)? {
+        Value::IntV { i_v } => Ok(i_v),
+        Value::BigIntV { b_v: _ } => Err(EvalError::Overflow { builtin }),
+        _ => Err(EvalError::TypeMismatch {
+            builtin,
+            arg_index,
+            expected: "an Integer",
+        }),
+    }
+}
+
+/// Evaluates an argument that has to be an integer of any size
+fn big_int_arg(
+    builtin: Builtin,
+    args: &[Expr],
+    arg_index: usize,
+    env: &mut Env,
+    config: &EvalConfig,
+) -> Result<BigInt, EvalError> {
+    match interp_expr_with_env(&args[arg_index], env, config)? {
+        Value::IntV { i_v } => Ok(i_v.into()),
+        Value::BigIntV { b_v } => Ok(b_v),
+        _ => Err(EvalError::TypeMismatch {
+            builtin,
+            arg_index,
+            expected: "an Integer",
+        }),
+    }
+}
+
+/// Evaluates an argument that has to be a number rather than a boolean, list or function
+fn number_arg(
+    builtin: Builtin,
+    args: &[Expr],
+    arg_index: usize,
+    env: &mut Env,
+    config: &EvalConfig,
+) -> Result<Value, EvalError> {
+    let value: Value = interp_expr_with_env(&args[arg_index], env, config)?;
+    return expect_number(builtin, arg_index, value);
+}
+
+fn expect_number(builtin: Builtin, arg_index: usize, value: Value) -> Result<Value, EvalError> {
+    if !value.is_number() {
+        return Err(EvalError::TypeMismatch {
+            builtin,
+            arg_index,
+            expected: "a Number",
+        });
+    }
+    return Ok(value);
+}
+
+fn expect_real(builtin: Builtin, arg_index: usize, value: Value) -> Result<Value, EvalError> {
+    match expect_number(builtin, arg_index, value)? {
+        Value::ComplexV { c_v: _ } => Err(EvalError::TypeMismatch {
+            builtin,
+            arg_index,
+            expected: "a Real Number",
+        }),
+        real => Ok(real),
+    }
+}
+
+fn list_arg(
+    builtin: Builtin,
+    args: &[Expr],
+    arg_index: usize,
+    env: &mut Env,
+    config: &EvalConfig,
+) -> Result<Vec<Value>, EvalError> {
+    match interp_expr_with_env(&args[arg_index], env, config)? {
+        Value::ListV { l_v } => Ok(l_v),
+        _ => Err(EvalError::TypeMismatch {
+            builtin,
+            arg_index,
+            expected: "a List",
+        }),
+    }
+}
+
+fn fn_arg(
+    builtin: Builtin,
+    args: &[Expr],
+    arg_index: usize,
+    env: &mut Env,
+    config: &EvalConfig,
+) -> Result<Box<Closure>, EvalError> {
+    match interp_expr_with_env(&args[arg_index], env, config)? {
+        Value::FnV { fn_v } => Ok(fn_v),
+        _ => Err(EvalError::TypeMismatch {
+            builtin,
+            arg_index,
+            expected: "a Function",
+        }),
+    }
+}
+
+fn bool_arg(
+    builtin: Builtin,
+    args: &[Expr],
+    arg_index: usize,
+    env: &mut Env,
+    config: &EvalConfig,
+) -> Result<bool, EvalError> {
+    match interp_expr_with_env(&args[arg_index], env, config)? {
+        Value::BoolV { bool_v } => Ok(bool_v),
+        _ => Err(EvalError::TypeMismatch {
+            builtin,
+            arg_index,
+            expected: "a Boolean",
+        }),
+    }
+}
+
+/// Evaluates an argument that has to be a real number
+fn real_arg(
+    builtin: Builtin,
+    args: &[Expr],
+    arg_index: usize,
+    env: &mut Env,
+    config: &EvalConfig,
+) -> Result<Value, EvalError> {
+    let value: Value = interp_expr_with_env(&args[arg_index], env, config)?;
+    return expect_real(builtin, arg_index, value);
+}
+
+/// Uses the i64 result when there is one, otherwise promotes as the config asks
+fn promote(
+    builtin: Builtin,
+    config: &EvalConfig,
+    checked: Option<i64>,
+    big: impl FnOnce() -> Option<BigInt>,
+    float: impl FnOnce() -> f64,
+) -> Result<Value, EvalError> {
+    if let Some(i_v) = checked {
+        return Ok(Value::IntV { i_v });
+    }
+    match config.int_overflow {
+        IntOverflow::PromoteToBigInt => match big() {
+            Some(b_v) => Ok(Value::from_big_int(b_v)),
+            None => Err(EvalError::Overflow { builtin }),
+        },
+        IntOverflow::PromoteToFloat => Ok(Value::FloatV { f_v: float() }),
+    }
+}
+
+/// `a_v / b_v` for numbers, with `b_v` not zero. Integers divide into exact fractions
+fn divide(a_v: Value, b_v: Value) -> Value {
+    match pair_up(a_v, b_v) {
+        ValuePair::IntInt { a, b } => Value::from_rational(BigRational::new(a.into(), b.into())),
+        ValuePair::BigBig { a, b } => Value::from_rational(BigRational::new(a, b)),
+        ValuePair::RatRat { a, b } => Value::from_rational(a / b),
+        ValuePair::FloatInt { a, b } => Value::FloatV { f_v: a / b as f64 },
+        ValuePair::IntFloat { a, b } => Value::FloatV { f_v: a as f64 / b },
+        ValuePair::FloatFloat { a, b } => Value::FloatV { f_v: a / b },
+        ValuePair::ComplexComplex { a, b } => Value::from_complex(a / b),
+    }
+}
+
+/// Add, mult, max and min take any number of arguments, or a single list of them
+#[allow(clippy::too_many_arguments)]
+fn do_binop(
+    builtin: Builtin,
+    args: &[Expr],
+    env: &mut Env,
+    config: &EvalConfig,
+    int_op: fn(i64, i64) -> Option<i64>,
+    big_op: fn(&BigInt, &BigInt) -> BigInt,
+    rat_op: fn(&BigRational, &BigRational) -> BigRational,
+    float_op: fn(f64, f64) -> f64,
+) -> Result<Value, EvalError> {
+    let operands: Vec<(usize, Value)> = match builtin {
+        Builtin::Add | Builtin::Multiply | Builtin::Max | Builtin::Min => {
+            variadic_operands(builtin, args, env, config)?
+        }
+        _ => {
+            check_arity(builtin, args, 2)?;
+            vec![
+                (0, interp_expr_with_env(&args[0], env, config)?),
+                (1, interp_expr_with_env(&args[1], env, config)?),
+            ]
+        }
+    };
+    let has_array =
+        |(_, value): &(usize, Value)| matches!(value, Value::ListV { .. } | Value::MatrixV { .. });
+    if operands.len() > 1
+        && matches!(
+            builtin,
+            Builtin::Add | Builtin::Subtract | Builtin::Multiply
+        )
+        && operands.iter().any(has_array)
+    {
+        let mut operands = operands.into_iter();
+        let (_, mut acc) = operands.next().expect("there are at least two operands");
+        for (arg_index, b_v) in operands {
+            acc = arithmetic(builtin, arg_index, acc, b_v, config)?;
+        }
+        return Ok(acc);
+    }
+    return fold_numbers(builtin, operands, config, int_op, big_op, rat_op, float_op);
+}
+
+/// Add, sub or mult of two values, `b_v` being argument `b_index`. Lists combine element by
+/// element and multiply as a dot product, matrices follow the usual rules with lists as columns
+fn arithmetic(
+    builtin: Builtin,
+    b_index: usize,
+    a_v: Value,
+    b_v: Value,
+    config: &EvalConfig,
+) -> Result<Value, EvalError> {
+    match (a_v, b_v) {
+        (a_v @ Value::MatrixV { .. }, b_v) | (a_v, b_v @ Value::MatrixV { .. }) => {
+            matrix_arithmetic(builtin, b_index, a_v, b_v)
+        }
+        (Value::ListV { l_v: a }, Value::ListV { l_v: b }) => {
+            if a.len() != b.len() {
+                return Err(EvalError::Domain {
+                    builtin,
+                    arg_index: b_index,
+                    reason: "Must Be as Long as Arg 1",
+                });
+            }
+            let mut items: Vec<Value> = Vec::with_capacity(a.len());
+            for (a_v, b_v) in a.into_iter().zip(b) {
+                items.push(arithmetic(builtin, b_index, a_v, b_v, config)?);
+            }
+            if builtin != Builtin::Multiply {
+                return Ok(Value::ListV { l_v: items });
+            } else if items.is_empty() {
+                return Ok(Value::IntV { i_v: 0 });
+            }
+            let products: Vec<(usize, Value)> =
+                items.into_iter().map(|item| (b_index, item)).collect();
+            fold_numbers(
+                Builtin::Add,
+                products,
+                config,
+                i64::checked_add,
+                |a, b| a + b,
+                |a, b| a + b,
+                |a, b| a + b,
+            )
+        }
+        (Value::ListV { l_v }, b_v) if builtin == Builtin::Multiply => {
+            let mut items: Vec<Value> = Vec::with_capacity(l_v.len());
+            for a_v in l_v {
+                items.push(arithmetic(builtin, b_index, a_v, b_v.clone(), config)?);
+            }
+            Ok(Value::ListV { l_v: items })
+        }
+        (a_v, Value::ListV { l_v }) if builtin == Builtin::Multiply => {
+            let mut items: Vec<Value> = Vec::with_capacity(l_v.len());
+            for b_v in l_v {
+                items.push(arithmetic(builtin, b_index, a_v.clone(), b_v, config)?);
+            }
+            Ok(Value::ListV { l_v: items })
+        }
+        (a_v, b_v) => {
+            let operands: Vec<(usize, Value)> =
+                vec![(b_index.saturating_sub(1), a_v), (b_index, b_v)];
+            match builtin {
+                Builtin::Add => fold_numbers(
+                    builtin,
+                    operands,
+                    config,
+                    i64::checked_add,
+                    |a, b| a + b,
+                    |a, b| a + b,
+                    |a, b| a + b,
+                ),
+                Builtin::Subtract => fold_numbers(
+                    builtin,
+                    operands,
+                    config,
+                    i64::checked_sub,
+                    |a, b| a - b,
+                    |a, b| a - b,
+                    |a, b| a - b,
+                ),
+                _ => fold_numbers(
+                    builtin,
+                    operands,
+                    config,
+                    i64::checked_mul,
+                    |a, b| a * b,
+                    |a, b| a * b,
+                    |a, b| a * b,
+                ),
+            }
+        }
+    }
+}
+
+/// `arithmetic` where one side is a matrix, real numbers scale it and lists are columns
+fn matrix_arithmetic(
+    builtin: Builtin,
+    b_index: usize,
+    a_v: Value,
+    b_v: Value,
+) -> Result<Value, EvalError> {
+    let as_list: bool = matches!(a_v, Value::ListV { .. }) || matches!(b_v, Value::ListV { .. });
+    let (a, a_is_scalar) = matrix_operand(builtin, b_index.saturating_sub(1), a_v)?;
+    let (b, b_is_scalar) = matrix_operand(builtin, b_index, b_v)?;
+    let m_v: DMatrix<f64> = match builtin {
+        Builtin::Multiply if a_is_scalar => b * a[(0, 0)],
+        Builtin::Multiply if b_is_scalar => a * b[(0, 0)],
+        Builtin::Multiply if a.ncols() == b.nrows() => a * b,
+        Builtin::Multiply => {
+            return Err(EvalError::Domain {
+                builtin,
+                arg_index: b_index,
+                reason: "Must Have as Many Rows as Arg 1 Has Columns",
+            })
+        }
+        _ if a_is_scalar || b_is_scalar || a.shape() != b.shape() => {
+            return Err(EvalError::Domain {
+                builtin,
+                arg_index: b_index,
+                reason: "Must Be the Same Size as Arg 1",
+            })
+        }
+        Builtin::Add => a + b,
+        _ => a - b,
+    };
+    let is_column: bool = m_v.ncols() == 1;
+    return Ok(column_result(m_v, as_list && is_column));
+}
+
+/// A matrix, a list as a column, or a real number as a 1 by 1 matrix flagged as a scalar
+fn matrix_operand(
+    builtin: Builtin,
+    arg_index: usize,
+    value: Value,
+) -> Result<(DMatrix<f64>, bool), EvalError> {
+    match value {
+        Value::MatrixV { m_v } => Ok((m_v, false)),
+        Value::ListV { l_v } if !l_v.is_empty() && l_v.iter().all(Value::is_real) => {
+            let column: Vec<f64> = l_v.iter().map(Value::as_f64).collect();
+            Ok((DMatrix::from_vec(column.len(), 1, column), false))
+        }
+        value if value.is_real() => Ok((DMatrix::from_element(1, 1, value.as_f64()), true)),
+        _ => Err(EvalError::TypeMismatch {
+            builtin,
+            arg_index,
+            expected: "a Real Number, a List of Them or a Matrix",
+        }),
+    }
+}
+
+/// Every argument with its index, a lone list argument gives its elements at index 0
+fn variadic_operands(
+    builtin: Builtin,
+    args: &[Expr],
+    env: &mut Env,
+    config: &EvalConfig,
+) -> Result<Vec<(usize, Value)>, EvalError> {
+    if args.is_empty() {
+        return Err(EvalError::Arity {
+            builtin,
+            expected: ArgCount::AtLeast(1),
+            found: 0,
+        });
+    }
+    if args.len() == 1 {
+        match interp_expr_with_env(&args[0], env, config)? {
+            Value::ListV { l_v } if l_v.is_empty() => {
+                return Err(EvalError::Domain {
+                    builtin,
+                    arg_index: 0,
+                    reason: "Must Not Be Empty",
+                })
+            }
+            Value::ListV { l_v } => return Ok(l_v.into_iter().map(|item| (0, item)).collect()),
+            value => return Ok(vec![(0, value)]),
+        }
+    }
+    let mut operands: Vec<(usize, Value)> = Vec::with_capacity(args.len());
+    for (arg_index, arg) in args.iter().enumerate() {
+        operands.push((arg_index, interp_expr_with_env(arg, env, config)?));
+    }
+    return Ok(operands);
+}
+
+/// Combines the operands left to right, which must not be empty
+fn fold_numbers(
+    builtin: Builtin,
+    operands: Vec<(usize, Value)>,
+    config: &EvalConfig,
+    int_op: fn(i64, i64) -> Option<i64>,
+    big_op: fn(&BigInt, &BigInt) -> BigInt,
+    rat_op: fn(&BigRational, &BigRational) -> BigRational,
+    float_op: fn(f64, f64) -> f64,
+) -> Result<Value, EvalError> {
+    // Only the field operations extend to complex numbers, max, min and mod need an ordering
+    let complex_op: Option<fn(Complex64, Complex64) -> Complex64> = match builtin {
+        Builtin::Add | Builtin::Sum => Some(|a, b| a + b),
+        Builtin::Subtract => Some(|a, b| a - b),
+        Builtin::Multiply | Builtin::Product => Some(|a, b| a * b),
+        _ => None,
+    };
+    let mut checked = operands.into_iter().map(|(arg_index, value)| {
+        if complex_op.is_some() {
+            expect_number(builtin, arg_index, value)
+        } else {
+            expect_real(builtin, arg_index, value)
+        }
+    });
+    let mut acc: Value = match checked.next() {
+        Some(first) => first?,
+        None => {
+            return Err(EvalError::Arity {
+                builtin,
+                expected: ArgCount::AtLeast(1),
+                found: 0,
+            })
+        }
+    };
+    for b_v in checked {
+        let b_v: Value = b_v?;
+        if builtin == Builtin::Modulus && b_v.is_zero() {
+            return Err(EvalError::DivisionByZero {
+                builtin,
+                arg_index: 1,
+            });
+        }
+        acc = match pair_up(acc, b_v) {
+            ValuePair::IntInt { a, b } => promote(
+                builtin,
+                config,
+                int_op(a, b),
+                || Some(big_op(&BigInt::from(a), &BigInt::from(b))),
+                || float_op(a as f64, b as f64),
+            )?,
+            ValuePair::BigBig { a, b } => Value::from_big_int(big_op(&a, &b)),
+            ValuePair::RatRat { a, b } => Value::from_rational(rat_op(&a, &b)),
+            ValuePair::FloatInt { a, b } => Value::FloatV {
+                f_v: float_op(a, b as f64),
+            },
+            ValuePair::IntFloat { a, b } => Value::FloatV {
+                f_v: float_op(a as f64, b),
+            },
+            ValuePair::FloatFloat { a, b } => Value::FloatV {
+                f_v: float_op(a, b),
+            },
+            ValuePair::ComplexComplex { a, b } => match complex_op {
+                Some(complex_op) => Value::from_complex(complex_op(a, b)),
+                None => unreachable!("complex arguments are rejected by expect_real"),
+            },
+        };
+    }
+    return Ok(acc);
+}
+
+/// Numbers compare by value across types, lists compare element by element
+fn values_equal(a_v: Value, b_v: Value) -> bool {
+    match (a_v, b_v) {
+        (Value::ListV { l_v: a }, Value::ListV { l_v: b }) => {
+            a.len() == b.len() && a.into_iter().zip(b).all(|(a, b)| values_equal(a, b))
+        }
+        (a_v, b_v) if a_v.is_number() && b_v.is_number() => match pair_up(a_v, b_v) {
+            ValuePair::IntInt { a, b } => a == b,
+            ValuePair::BigBig { a, b } => a == b,
+            ValuePair::RatRat { a, b } => a == b,
+            ValuePair::FloatInt { a, b } => a == b as f64,
+            ValuePair::IntFloat { a, b } => a as f64 == b,
+            ValuePair::FloatFloat { a, b } => a == b,
+            ValuePair::ComplexComplex { a, b } => a == b,
+        },
+        (a_v, b_v) => a_v == b_v,
+    }
+}
+
+/// Longest list `range` will build
+const MAX_RANGE_LEN: usize = 1 << 20;
+
+/// Integer ranges stay exact, anything else steps in floats
+fn range(builtin: Builtin, start: Value, stop: Value, step: Value) -> Result<Value, EvalError> {
+    let too_long = EvalError::Domain {
+        builtin,
+        arg_index: 1,
+        reason: "Makes a Range That Is Too Long",
+    };
+    if let (Value::IntV { i_v: start }, Value::IntV { i_v: stop }, Value::IntV { i_v: step }) =
+        (&start, &stop, &step)
+    {
+        let (start, stop, step) = (*start as i128, *stop as i128, *step as i128);
+        let count: i128 = ((stop - start + step - step.signum()) / step).max(0);
+        if count > MAX_RANGE_LEN as i128 {
+            return Err(too_long);
+        }
+        let l_v: Vec<Value> = (0..count)
+            .map(|i| Value::IntV {
+                i_v: (start + i * step) as i64,
+            })
+            .collect();
+        return Ok(Value::ListV { l_v });
+    }
+    let (start, stop, step) = (start.as_f64(), stop.as_f64(), step.as_f64());
+    let count: f64 = ((stop - start) / step).ceil().max(0.0);
+    if !count.is_finite() || count > MAX_RANGE_LEN as f64 {
+        return Err(too_long);
+    }
+    let l_v: Vec<Value> = (0..count as usize)
+        .map(|i| Value::FloatV {
+            f_v: start + i as f64 * step,
+        })
+        .collect();
+    return Ok(Value::ListV { l_v });
+}
+
+/// Kept out of `interp_builtin` so its stack frame stays small for deep recursion
+fn interp_symbolic(
+    builtin: Builtin,
+    args: &[Expr],
+    env: &mut Env,
+    config: &EvalConfig,
+) -> Result<Value, EvalError> {
+    match builtin {
+        // The derivative as a function of the variable, or its value at the optional point
+        Builtin::Diff => {
+            if !(2..=3).contains(&args.len()) {
+                return Err(EvalError::Arity {
+                    builtin,
+                    expected: ArgCount::Between(2, 3),
+                    found: args.len(),
+                });
+            }
+            let var: &str = var_name_arg(builtin, args, 1)?;
+            let derivative: Expr = differentiate(&args[0], var, env, config.angle_mode)?;
+            let closure: Closure = Closure {
+                name: None,
+                params: vec![var.to_string()],
+                body: Box::new(simplify(&derivative)),
+                captured: env.capture(),
+            };
+            match args.get(2) {
+                Some(point) => {
+                    let at: Value = interp_expr_with_env(point, env, config)?;
+                    apply_closure(&closure, vec![at], env, config)
+                }
+                None => Ok(Value::FnV {
+                    fn_v: Box::new(closure),
+                }),
+            }
+        }
+        // Functions come back with a simplified body. Expressions with variables that have no
+        // value, like add(x, x), come back as a function of those variables, mult(2, x) of x
+        // Unbound names make the expression a function of them, simplified without being run
+        Builtin::Simplify => {
+            check_arity(builtin, args, 1)?;
+            let unbound = |expr: &Expr, env: &Env| -> Vec<String> {
+                return free_variables(expr)
+                    .into_iter()
+                    .filter(|name| env.get(name).is_none() && constant_value(name).is_none())
+                    .collect();
+            };
+            let simplified: Expr = if unbound(&args[0], env).is_empty() {
+                args[0].clone()
+            } else {
+                simplify(&args[0])
+            };
+            let params: Vec<String> = unbound(&simplified, env);
+            if !params.is_empty() {
+                return Ok(Value::FnV {
+                    fn_v: Box::new(Closure {
+                        name: None,
+                        params,
+                        body: Box::new(simplified),
+                        captured: env.capture(),
+                    }),
+                });
+            }
+            match interp_expr_with_env(&simplified, env, config)? {
+                Value::FnV { fn_v } => Ok(Value::FnV {
+                    fn_v: Box::new(Closure {
+                        name: None,
+                        body: Box::new(simplify(&fn_v.body)),
+                        ..*fn_v
+                    }),
+                }),
+                value => Ok(value),
+            }
+        }
+        _ => unreachable!("{} is not a symbolic builtin", builtin.name()),
+    }
+}
+
+/// Kept out of `interp_builtin` so its stack frame stays small for deep recursion
+fn interp_numerical(
+    builtin: Builtin,
+    args: &[Expr],
+    env: &mut Env,
+    config: &EvalConfig,
+) -> Result<Value, EvalError> {
+    match builtin {
+        // The value and its estimated error, of the first argument as a function of the second
+        // from the third to the fourth, to within the optional tolerance
+        Builtin::Integrate => {
+            if !(4..=5).contains(&args.len()) {
+                return Err(EvalError::Arity {
+                    builtin,
+                    expected: ArgCount::Between(4, 5),
+                    found: args.len(),
+                });
+            }
+            let var: &str = var_name_arg(builtin, args, 1)?;
+            let a: f64 = real_arg(builtin, args, 2, env, config)?.as_f64();
+            let b: f64 = real_arg(builtin, args, 3, env, config)?.as_f64();
+            for (arg_index, bound) in [(2, a), (3, b)] {
+                if bound.is_nan() {
+                    return Err(EvalError::Domain {
+                        builtin,
+                        arg_index,
+                        reason: "Must Not Be NaN",
+                    });
+                }
+            }
+            let tolerance: f64 = match args.get(4) {
+                Some(_) => real_arg(builtin, args, 4, env, config)?.as_f64(),
+                None => config.integration_tolerance,
+            };
+            if tolerance.is_nan() || tolerance <= 0.0 {
+                return Err(EvalError::Domain {
+                    builtin,
+                    arg_index: 4,
+                    reason: "Must Be Positive",
+                });
+            }
+            let integrand: Closure = Closure {
+                name: None,
+                params: vec![var.to_string()],
+                body: Box::new(args[0].clone()),
+                captured: env.capture(),
+            };
+            let quadrature: Quadrature = integrate(
+                &mut |x: f64| -> Result<f64, EvalError> {
+                    let y: Value =
+                        apply_closure(&integrand, vec![Value::FloatV { f_v: x }], env, config)?;
+                    let y: f64 = expect_real(builtin, 0, y)?.as_f64();
+                    if !y.is_finite() {
+                        return Err(EvalError::Domain {
+                            builtin,
+                            arg_index: 0,
+                            reason: "Is Not Finite on the Interval",
+                        });
+                    }
+                    return Ok(y);
+                },
+                a,
+                b,
+                tolerance,
+            )?;
+            Ok(Value::ListV {
+                l_v: vec![
+                    Value::FloatV {
+                        f_v: quadrature.value,
+                    },
+                    Value::FloatV {
+                        f_v: quadrature.error,
+                    },
+                ],
+            })
+        }
+        // Where the first argument is 0 as a function of the second
+        Builtin::Root => {
+            if !(2..=4).contains(&args.len()) {
+                return Err(EvalError::Arity {
+                    builtin,
+                    expected: ArgCount::Between(2, 4),
+                    found: args.len(),
+                });
+            }
+            find_roots(builtin, &args[0], 1, args, env, config)
+        }
+        // Where the first argument equals the second as a function of the third
+        Builtin::Solve => {
+            if !(3..=5).contains(&args.len()) {
+                return Err(EvalError::Arity {
+                    builtin,
+                    expected: ArgCount::Between(3, 5),
+                    found: args.len(),
+                });
+            }
+            let difference: Expr = Expr::BuiltinFn {
+                name: Builtin::Subtract,
+                args: vec![args[0].clone(), args[1].clone()],
+            };
+            find_roots(builtin, &difference, 2, args, env, config)
+        }
+        _ => unreachable!("{} is not a numerical builtin", builtin.name()),
+    }
+}
+
+/// Solves `expr = 0` for the variable named at `var_index`. With nothing after it, `expr` has to be
+/// a polynomial and every root comes back in a list, real ones first. With one more argument
+/// Newton's method starts from it, with two Brent's method looks between them
+fn find_roots(
+    builtin: Builtin,
+    expr: &Expr,
+    var_index: usize,
+    args: &[Expr],
+    env: &mut Env,
+    config: &EvalConfig,
+) -> Result<Value, EvalError> {
+    let var: &str = var_name_arg(builtin, args, var_index)?;
+    let function: Closure = Closure {
+        name: None,
+        params: vec![var.to_string()],
+        body: Box::new(expr.clone()),
+        captured: env.capture(),
+    };
+    let at = |x: f64, env: &mut Env| -> Result<f64, EvalError> {
+        let y: Value = apply_closure(&function, vec![Value::FloatV { f_v: x }], env, config)?;
+        return Ok(expect_real(builtin, 0, y)?.as_f64());
+    };
+    match &args[var_index + 1..] {
+        [] => {
+            let coefficients: Option<Vec<Complex64>> =
+                polynomial_coefficients(expr, var, &mut |constant: &Expr| {
+                    let value: Value = interp_expr_with_env(constant, env, config)?;
+                    return Ok(expect_number(builtin, 0, value)?.as_complex());
+                })?;
+            let coefficients: Vec<Complex64> = coefficients.ok_or(EvalError::Domain {
+                builtin,
+                arg_index: 0,
+                reason: "Is Not a Polynomial, So Needs a Starting Guess",
+            })?;
+            if coefficients.iter().all(|c| c.is_zero()) {
+                return Err(EvalError::Domain {
+                    builtin,
+                    arg_index: 0,
+                    reason: "Holds for Every Value of the Variable",
+                });
+            }
+            let mut roots: Vec<Complex64> = polynomial_roots(&coefficients);
+            roots.sort_by(|a, b| {
+                (a.im != 0.0)
+                    .cmp(&(b.im != 0.0))
+                    .then(a.re.total_cmp(&b.re))
+                    .then(a.im.total_cmp(&b.im))
+            });
+            Ok(Value::ListV {
+                l_v: roots.into_iter().map(Value::from_complex).collect(),
+            })
+        }
+        [_] => {
+            let guess: f64 = real_arg(builtin, args, var_index + 1, env, config)?.as_f64();
+            // Symbolic when diff can manage it, otherwise a central difference
+            let derivative: Option<Closure> = match differentiate(expr, var, env, config.angle_mode)
+            {
+                Ok(derivative) => Some(Closure {
+                    body: Box::new(simplify(&derivative)),
+                    ..function.clone()
+                }),
+                Err(EvalError::NotDifferentiable { .. }) => None,
+                Err(e) => return Err(e),
+            };
+            let root: Option<f64> = newton(
+                &mut |x: f64| -> Result<(f64, f64), EvalError> {
+                    let slope: f64 = match &derivative {
+                        Some(derivative) => {
+                            let slope: Value = apply_closure(
+                                derivative,
+                                vec![Value::FloatV { f_v: x }],
+                                env,
+                                config,
+                            )?;
+                            expect_real(builtin, 0, slope)?.as_f64()
+                        }
+                        None => {
+                            let h: f64 = f64::EPSILON.cbrt() * x.abs().max(1.0);
+                            (at(x + h, env)? - at(x - h, env)?) / (2.0 * h)
+                        }
+                    };
+                    return Ok((at(x, env)?, slope));
+                },
+                guess,
+            )?;
+            match root {
+                Some(f_v) => Ok(Value::FloatV { f_v }),
+                None => Err(EvalError::Domain {
+                    builtin,
+                    arg_index: var_index + 1,
+                    reason: "Did Not Lead to a Root",
+                }),
+            }
+        }
+        _ => {
+            let a: f64 = real_arg(builtin, args, var_index + 1, env, config)?.as_f64();
+            let b: f64 = real_arg(builtin, args, var_index + 2, env, config)?.as_f64();
+            match brent(&mut |x: f64| at(x, env), a, b)? {
+                Some(f_v) => Ok(Value::FloatV { f_v }),
+                None => Err(EvalError::Domain {
+                    builtin,
+                    arg_index: var_index + 1,
+                    reason: "And the Next Arg Must Bracket a Sign Change",
+                }),
+            }
+        }
+    }
+}
+
+/// Builtins that take a variable name, like the `x` in diff(pow(x, 2), x)
+fn var_name_arg(builtin: Builtin, args: &[Expr], arg_index: usize) -> Result<&str, EvalError> {
+    match &args[arg_index] {
+        Expr::Var { name } => Ok(name),
+        _ => Err(EvalError::TypeMismatch {
+            builtin,
+            arg_index,
+            expected: "a Variable Name",
+        }),
+    }
+}
+
+/// Kept out of `interp_builtin` so its stack frame stays small for deep recursion
+fn interp_linear_algebra(
+    builtin: Builtin,
+    args: &[Expr],
+    env: &mut Env,
+    config: &EvalConfig,
+) -> Result<Value, EvalError> {
+    match builtin {
+        // Each argument is one row
+        Builtin::Matrix => {
+            if args.is_empty() {
+                return Err(EvalError::Arity {
+                    builtin,
+                    expected: ArgCount::AtLeast(1),
+                    found: 0,
+                });
+            }
+            let mut rows: Vec<Vec<f64>> = Vec::with_capacity(args.len());
+            for arg_index in 0..args.len() {
+                let row: Vec<f64> = real_list_arg(builtin, args, arg_index, env, config)?;
+                if row.is_empty() {
+                    return Err(EvalError::Domain {
+                        builtin,
+                        arg_index,
+                        reason: "Must Not Be Empty",
+                    });
+                }
+                if arg_index > 0 && row.len() != rows[0].len() {
+                    return Err(EvalError::Domain {
+                        builtin,
+                        arg_index,
+                        reason: "Must Be as Long as Arg 1",
+                    });
+                }
+                rows.push(row);
+            }
+            let m_v: DMatrix<f64> =
+                DMatrix::from_fn(rows.len(), rows[0].len(), |row, col| rows[row][col]);
+            Ok(Value::MatrixV { m_v })
+        }
+        Builtin::Identity => {
+            check_arity(builtin, args, 1)?;
+            let n: i64 = int_arg(builtin, args, 0, env, config)?;
+            if n < 1 {
+                return Err(EvalError::Domain {
+                    builtin,
+                    arg_index: 0,
+                    reason: "Must Be Positive",
+                });
+            }
+            if n > MAX_MATRIX_DIM {
+                return Err(EvalError::Domain {
+                    builtin,
+                    arg_index: 0,
+                    reason: "Makes a Matrix That Is Too Large",
+                });
+            }
+            Ok(Value::MatrixV {
+                m_v: DMatrix::identity(n as usize, n as usize),
+            })
+        }
+        Builtin::Transpose => {
+            check_arity(builtin, args, 1)?;
+            let m_v: DMatrix<f64> = matrix_arg(builtin, args, 0, env, config)?;
+            Ok(Value::MatrixV {
+                m_v: m_v.transpose(),
+            })
+        }
+        Builtin::Determinant => {
+            check_arity(builtin, args, 1)?;
+            let m_v: DMatrix<f64> = square_matrix_arg(builtin, args, 0, env, config)?;
+            Ok(Value::FloatV {
+                f_v: m_v.determinant(),
+            })
+        }
+        Builtin::Inverse => {
+            check_arity(builtin, args, 1)?;
+            let m_v: DMatrix<f64> = square_matrix_arg(builtin, args, 0, env, config)?;
+            match m_v.try_inverse() {
+                Some(m_v) => Ok(Value::MatrixV { m_v }),
+                None => Err(EvalError::Domain {
+                    builtin,
+                    arg_index: 0,
+                    reason: "Must Not Be Singular",
+                }),
+            }
+        }
+        Builtin::MatMul => {
+            check_arity(builtin, args, 2)?;
+            let a: DMatrix<f64> = matrix_arg(builtin, args, 0, env, config)?;
+            let (b, b_is_list) = column_arg(builtin, args, 1, env, config)?;
+            if a.ncols() != b.nrows() {
+                return Err(EvalError::Domain {
+                    builtin,
+                    arg_index: 1,
+                    reason: "Must Have as Many Rows as Arg 1 Has Columns",
+                });
+            }
+            Ok(column_result(a * b, b_is_list))
+        }
+        // Shorter lists are padded with zeros, as vectors in the plane
+        Builtin::Cross => {
+            check_arity(builtin, args, 2)?;
+            let mut a: Vec<Value> = list_arg(builtin, args, 0, env, config)?;
+            let mut b: Vec<Value> = list_arg(builtin, args, 1, env, config)?;
+            for (arg_index, items) in [(0, &a), (1, &b)] {
+                if items.len() > 3 || !items.iter().all(Value::is_real) {
+                    return Err(EvalError::TypeMismatch {
+                        builtin,
+                        arg_index,
+                        expected: "a List of up to 3 Real Numbers",
+                    });
+                }
+            }
+            if a.len() != b.len() {
+                return Err(EvalError::Domain {
+                    builtin,
+                    arg_index: 1,
+                    reason: "Must Be as Long as Arg 1",
+                });
+            }
+            a.resize(3, Value::IntV { i_v: 0 });
+            b.resize(3, Value::IntV { i_v: 0 });
+            let mut l_v: Vec<Value> = Vec::with_capacity(3);
+            for i in 0..3 {
+                let (j, k) = ((i + 1) % 3, (i + 2) % 3);
+                let ajbk: Value =
+                    arithmetic(Builtin::Multiply, 1, a[j].clone(), b[k].clone(), config)?;
+                let akbj: Value =
+                    arithmetic(Builtin::Multiply, 1, a[k].clone(), b[j].clone(), config)?;
+                l_v.push(arithmetic(Builtin::Subtract, 1, ajbk, akbj, config)?);
+            }
+            Ok(Value::ListV { l_v })
+        }
+        // Finds x in a x = b, where b is a matrix or a list standing for a column
+        Builtin::Solve => {
+            check_arity(builtin, args, 2)?;
+            let a: DMatrix<f64> = square_matrix_arg(builtin, args, 0, env, config)?;
+            let (b, b_is_list) = column_arg(builtin, args, 1, env, config)?;
+            if a.nrows() != b.nrows() {
+                return Err(EvalError::Domain {
+                    builtin,
+                    arg_index: 1,
+                    reason: "Must Have as Many Rows as Arg 1",
+                });
+            }
+            match a.lu().solve(&b) {
+                Some(x) => Ok(column_result(x, b_is_list)),
+                None => Err(EvalError::Domain {
+                    builtin,
+                    arg_index: 0,
+                    reason: "Must Not Be Singular",
+                }),
+            }
+        }
+        // Counts the singular values that are not rounding noise
+        Builtin::Rank => {
+            check_arity(builtin, args, 1)?;
+            let m_v: DMatrix<f64> = matrix_arg(builtin, args, 0, env, config)?;
+            let size: usize = m_v.nrows().max(m_v.ncols());
+            let singular_values = m_v.singular_values();
+            let tolerance: f64 = singular_values.max() * size as f64 * f64::EPSILON;
+            Ok(Value::IntV {
+                i_v: singular_values.iter().filter(|s| **s > tolerance).count() as i64,
+            })
+        }
+        // Largest real part first, complex pairs come out together
+        Builtin::Eigenvalues => {
+            check_arity(builtin, args, 1)?;
+            let m_v: DMatrix<f64> = square_matrix_arg(builtin, args, 0, env, config)?;
+            let eigenvalues: Option<Vec<Complex64>> = if m_v == m_v.transpose() {
+                SymmetricEigen::try_new(m_v, f64::EPSILON, MAX_EIGEN_ITERATIONS).map(|eigen| {
+                    eigen
+                        .eigenvalues
+                        .iter()
+                        .map(|re| Complex64::new(*re, 0.0))
+                        .collect()
+                })
+            } else {
+                Schur::try_new(m_v, f64::EPSILON, MAX_EIGEN_ITERATIONS)
+                    .map(|schur| schur.complex_eigenvalues().iter().copied().collect())
+            };
+            let mut eigenvalues: Vec<Complex64> = eigenvalues.ok_or(EvalError::Domain {
+                builtin,
+                arg_index: 0,
+                reason: "Has Eigenvalues That Did Not Converge",
+            })?;
+            eigenvalues.sort_by(|a, b| b.re.total_cmp(&a.re).then(b.im.total_cmp(&a.im)));
+            Ok(Value::ListV {
+                l_v: eigenvalues.into_iter().map(Value::from_complex).collect(),
+            })
+        }
+        _ => unreachable!("{} is not a linear algebra builtin", builtin.name()),
+    }
+}
+
+fn matrix_arg(
+    builtin: Builtin,
+    args: &[Expr],
+    arg_index: usize,
+    env: &mut Env,
+    config: &EvalConfig,
+) -> Result<DMatrix<f64>, EvalError> {
+    match interp_expr_with_env(&args[arg_index], env, config)? {
+        Value::MatrixV { m_v } => Ok(m_v),
+        _ => Err(EvalError::TypeMismatch {
+            builtin,
+            arg_index,
+            expected: "a Matrix",
+        }),
+    }
+}
+
+fn square_matrix_arg(
+    builtin: Builtin,
+    args: &[Expr],
+    arg_index: usize,
+    env: &mut Env,
+    config: &EvalConfig,
+) -> Result<DMatrix<f64>, EvalError> {
+    let m_v: DMatrix<f64> = matrix_arg(builtin, args, arg_index, env, config)?;
+    if !m_v.is_square() {
+        return Err(EvalError::Domain {
+            builtin,
+            arg_index,
+            reason: "Must Be a Square Matrix",
+        });
+    }
+    return Ok(m_v);
+}
+
+fn real_list_arg(
+    builtin: Builtin,
+    args: &[Expr],
+    arg_index: usize,
+    env: &mut Env,
+    config: &EvalConfig,
+) -> Result<Vec<f64>, EvalError> {
+    let not_real = EvalError::TypeMismatch {
+        builtin,
+        arg_index,
+        expected: "a List of Real Numbers",
+    };
+    let items: Vec<Value> = match interp_expr_with_env(&args[arg_index], env, config)? {
+        Value::ListV { l_v } => l_v,
+        _ => return Err(not_real),
+    };
+    if !items.iter().all(Value::is_real) {
+        return Err(not_real);
+    }
+    return Ok(items.iter().map(Value::as_f64).collect());
+}
+
+/// A list argument becomes a single column, the flag says to hand a list back
+fn column_arg(
+    builtin: Builtin,
+    args: &[Expr],
+    arg_index: usize,
+    env: &mut Env,
+    config: &EvalConfig,
+) -> Result<(DMatrix<f64>, bool), EvalError> {
+    match interp_expr_with_env(&args[arg_index], env, config)? {
+        Value::MatrixV { m_v } => Ok((m_v, false)),
+        Value::ListV { l_v } if !l_v.is_empty() && l_v.iter().all(Value::is_real) => {
+            let column: Vec<f64> = l_v.iter().map(Value::as_f64).collect();
+            Ok((DMatrix::from_vec(column.len(), 1, column), true))
+        }
+        _ => Err(EvalError::TypeMismatch {
+            builtin,
+            arg_index,
+            expected: "a Matrix or a List of Real Numbers",
+        }),
+    }
+}
+
+fn column_result(m_v: DMatrix<f64>, as_list: bool) -> Value {
+    if as_list {
+        return Value::ListV {
+            l_v: m_v.iter().map(|f_v| Value::FloatV { f_v: *f_v }).collect(),
+        };
+    }
+    return Value::MatrixV { m_v };
+}
+
+/// Real arguments outside `real_domain` give the principal complex result instead of NaN
+fn do_single_arg(
+    builtin: Builtin,
+    real_fn: fn(f64) -> f64,
+    complex_fn: fn(Complex64) -> Complex64,
+    real_domain: fn(f64) -> bool,
+    args: &[Expr],
+    env: &mut Env,
+    config: &EvalConfig,
+) -> Result<Value, EvalError> {
+    check_arity(builtin, args, 1)?;
+    let x_v: Value = number_arg(builtin, args, 0, env, config)?;
+    return Ok(apply_single_arg(real_fn, complex_fn, real_domain, x_v));
+}
+
+type RealFn = fn(f64) -> f64;
+type ComplexFn = fn(Complex64) -> Complex64;
+
+/// Reals outside `real_domain` go through `complex_fn`, like sqrt(-4) giving 2i
+fn apply_single_arg(
+    real_fn: RealFn,
+    complex_fn: ComplexFn,
+    real_domain: fn(f64) -> bool,
+    x_v: Value,
+) -> Value {
+    match x_v {
+        Value::ComplexV { c_v } => Value::from_complex(complex_fn(c_v)),
+        real => {
+            let f_v: f64 = real.as_f64();
+            if f_v.is_nan() || real_domain(f_v) {
+                return Value::FloatV { f_v: real_fn(f_v) };
+            }
+            return Value::from_complex(complex_fn(Complex64::new(f_v, 0.0)));
+        }
+    }
+}
+
+/// Angles in the first quadrant, in degrees, with their sines and cosines,
+/// which degree and gradian mode get exactly right
+const SPECIAL_ANGLES: [(f64, f64, f64); 5] = [
+    (0.0, 0.0, 1.0),
+    (30.0, 0.5, HALF_SQRT_3),
+    (45.0, FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+    (60.0, HALF_SQRT_3, 0.5),
+    (90.0, 1.0, 0.0),
+];
+const HALF_SQRT_3: f64 = 0.866_025_403_784_438_6;
+
+/// Sine, cosine or tangent of an angle in `angle_mode` units
+fn trig(builtin: Builtin, angle: Value, angle_mode: AngleMode) -> Result<Value, EvalError> {
+    let (real_fn, complex_fn): (RealFn, ComplexFn) = match builtin {
+        Builtin::Sin => (f64::sin, Complex64::sin),
+        Builtin::Cos => (f64::cos, Complex64::cos),
+        _ => (f64::tan, Complex64::tan),
+    };
+    match angle {
+        Value::ComplexV { c_v } => Ok(Value::from_complex(complex_fn(
+            c_v.scale(angle_mode.to_radians(1.0)),
+        ))),
+        real if angle_mode == AngleMode::Radians => Ok(Value::FloatV {
+            f_v: real_fn(real.as_f64()),
+        }),
+        real => {
+            let (sin, cos): (f64, f64) = sin_cos_in_mode(real.as_f64(), angle_mode);
+            // Exact right angles have no tangent, rather than an infinity of either sign
+            if builtin == Builtin::Tan && cos == 0.0 {
+                return Err(EvalError::Domain {
+                    builtin,
+                    arg_index: 0,
+                    reason: "Must Not Be an Odd Multiple of a Right Angle",
+                });
+            }
+            let f_v: f64 = match builtin {
+                Builtin::Sin => sin,
+                Builtin::Cos => cos,
+                _ => sin / cos,
+            };
+            // Adding 0 turns the -0 of sin(180) into 0
+            Ok(Value::FloatV { f_v: f_v + 0.0 })
+        }
+    }
+}
+
+/// Reduces the angle to a whole number of quarter turns plus a first quadrant angle,
+/// so multiples of 30 and 45 degrees come out exact, tan(45) is 1 rather than 0.9999999999999999
+fn sin_cos_in_mode(angle: f64, angle_mode: AngleMode) -> (f64, f64) {
+    let full_turn: f64 = angle_mode.full_turn();
+    let degrees: f64 = angle.rem_euclid(full_turn) * 360.0 / full_turn;
+    let quadrant: f64 = (degrees / 90.0).floor();
+    let reference: f64 = degrees - 90.0 * quadrant;
+    let (sin, cos): (f64, f64) = match SPECIAL_ANGLES.iter().find(|(a, _, _)| *a == reference) {
+        Some((_, sin, cos)) => (*sin, *cos),
+        None => (reference.to_radians().sin(), reference.to_radians().cos()),
+    };
+    // Rounding can leave a whole turn behind, which is quadrant 0 again
+    match quadrant as i64 % 4 {
+        0 => (sin, cos),
+        1 => (cos, -sin),
+        2 => (-sin, -cos),
+        _ => (-cos, sin),
+    }
+}
+
+/// Arcsine, arccosine or arctangent in `angle_mode` units
+fn inverse_trig(builtin: Builtin, x_v: Value, angle_mode: AngleMode) -> Value {
+    let (real_fn, complex_fn, real_domain): (RealFn, ComplexFn, fn(f64) -> bool) = match builtin {
+        Builtin::ArcSin => (f64::asin, Complex64::asin, |x| x.abs() <= 1.0),
+        Builtin::ArcCos => (f64::acos, Complex64::acos, |x| x.abs() <= 1.0),
+        _ => (f64::atan, Complex64::atan, |_| true),
+    };
+    let is_real: bool = x_v.is_real();
+    let x: f64 = x_v.as_f64();
+    let radians: Value = apply_single_arg(real_fn, complex_fn, real_domain, x_v);
+    if angle_mode == AngleMode::Radians {
+        return radians;
+    }
+    match radians {
+        Value::FloatV { f_v } if is_real => {
+            let special: Option<f64> = SPECIAL_ANGLES.iter().find_map(|(angle, sin, cos)| {
+                let (matches, degrees): (bool, f64) = match builtin {
+                    Builtin::ArcSin => (x.abs() == *sin, angle.copysign(x)),
+                    Builtin::ArcCos if x < 0.0 => (-x == *cos, 180.0 - angle),
+                    Builtin::ArcCos => (x == *cos, *angle),
+                    _ => (x.abs() == sin / cos, angle.copysign(x)),
+                };
+                return matches.then_some(degrees);
+            });
+            let f_v: f64 = match special {
+                Some(degrees) => degrees * angle_mode.full_turn() / 360.0,
+                None => angle_mode.from_radians(f_v),
+            };
+            Value::FloatV { f_v: f_v + 0.0 }
+        }
+        Value::ComplexV { c_v } => Value::from_complex(c_v.scale(angle_mode.from_radians(1.0))),
+        other => other,
+    }
+}
+
+/// Negative bases with fractional exponents have complex results
+fn float_pow(a: f64, b: f64) -> Value {
+    if a < 0.0 && b.fract() != 0.0 && b.is_finite() {
+        return Value::from_complex(Complex64::new(a, 0.0).powf(b));
+    }
+    return Value::FloatV { f_v: a.powf(b) };
+}
+
+/// None when the result does not fit in an i64
+fn factorial(i: i64) -> Option<i64> {
+    return (1..=i).try_fold(1, |acc: i64, i: i64| acc.checked_mul(i));
+}
+
+/// n! / (n - k)!, None when the result does not fit in an i64
+fn permutation(n: i64, k: i64) -> Option<i64> {
+    return (n - k + 1..=n).try_fold(1, |acc: i64, i: i64| acc.checked_mul(i));
+}
+
+/// n! / (k! (n - k)!) built up one factor at a time, so no factorial is ever computed
+fn choose(n: i64, k: i64) -> Option<i64> {
+    let k: i64 = k.min(n - k);
+    let mut acc: i128 = 1;
+    for i in 1..=k {
+        // acc is C(n - k + i - 1, i - 1) here, so the division is exact
+        acc = acc * (n - k + i) as i128 / i as i128;
+        if acc > i64::MAX as i128 {
+            return None;
+        }
+    }
+    return Some(acc as i64);
+}
+
+/// None when the result would be larger than MAX_BIG_INT_BITS
+fn big_pow(base: &BigInt, exp: u64) -> Option<BigInt> {
+    if base.magnitude().bits() <= 1 {
+        // 0, 1 and -1 never grow, only the parity of the exponent matters
+        let small_exp: u32 = if exp == 0 { 0 } else { 2 - (exp % 2) as u32 };
+        return Some(base.pow(small_exp));
+    }
+    if base.magnitude().bits().saturating_mul(exp) > MAX_BIG_INT_BITS {
+        return None;
+    }
+    return Some(base.pow(exp as u32));
+}
+
+/// Raises a fraction to an integer power, negative powers flip the fraction
+fn rational_pow(builtin: Builtin, base: &BigRational, exp: &BigInt) -> Result<Value, EvalError> {
+    if base.is_zero() && exp.is_negative() {
+        return Err(EvalError::DivisionByZero {
+            builtin,
+            arg_index: 0,
+        });
+    }
+    let magnitude: Option<u64> = exp.magnitude().to_u64();
+    let numer: Option<BigInt> = magnitude.and_then(|e| big_pow(base.numer(), e));
+    let denom: Option<BigInt> = magnitude.and_then(|e| big_pow(base.denom(), e));
+    match (numer, denom) {
+        (Some(numer), Some(denom)) if exp.is_negative() => {
+            Ok(Value::from_rational(BigRational::new(denom, numer)))
+        }
+        (Some(numer), Some(denom)) => Ok(Value::from_rational(BigRational::new(numer, denom))),
+        _ => Err(EvalError::Overflow { builtin }),
+    }
+}
+
+fn big_permutation(n: &BigInt, k: &BigInt) -> Option<BigInt> {
+    // Every factor but the last is at least 2, so the result has at least k - 1 bits
+    let k: u64 = k.to_u64().filter(|k| *k <= MAX_BIG_INT_BITS)?;
+    let n_f64: f64 = n.to_f64()?;
+    if (0..k).map(|i| (n_f64 - i as f64).log2()).sum::<f64>() > MAX_BIG_INT_BITS as f64 {
+        return None;
+    }
+    return Some((0..k).fold(BigInt::from(1), |acc, i| acc * (n - i)));
+}
+
+fn big_choose(n: &BigInt, k: &BigInt) -> Option<BigInt> {
+    // C(n, k) >= 2^k once k <= n / 2
+    let k: u64 = k
+        .min(&(n - k))
+        .to_u64()
+        .filter(|k| *k <= MAX_BIG_INT_BITS)?;
+    let n_minus_k: BigInt = n - k;
+    let n_minus_k_f64: f64 = n_minus_k.to_f64()?;
+    if (1..=k)
+        .map(|i| ((n_minus_k_f64 + i as f64) / i as f64).log2())
+        .sum::<f64>()
+        > MAX_BIG_INT_BITS as f64
+    {
+        return None;
+    }
+    let mut acc: BigInt = BigInt::from(1);
+    for i in 1..=k {
+        // acc is C(n - k + i - 1, i - 1) here, so the division is exact
+        acc = acc * (&n_minus_k + i) / i;
+    }
+    return Some(acc);
+}
+
+fn float_permutation(n: i64, k: i64) -> f64 {
+    let mut acc: f64 = 1.0;
+    for i in n - k + 1..=n {
+        acc *= i as f64;
+        if acc.is_infinite() {
+            break;
+        }
+    }
+    return acc;
+}
+
+fn float_choose(n: i64, k: i64) -> f64 {
+    let k: i64 = k.min(n - k);
+    let mut acc: f64 = 1.0;
+    for i in 1..=k {
+        acc = acc * (n - k + i) as f64 / i as f64;
+        if acc.is_infinite() {
+            break;
+        }
+    }
+    return acc;
+}
+
+#[cfg(test)]
+#[allow(clippy::unnecessary_cast)]
+mod interp_tests {
+    use super::*;
+    use crate::func_expr_util::{
+        evaluate_prefix_expression, evaluate_prefix_expression_in_env,
+        evaluate_prefix_expression_with_config,
+    };
+    use Expr::*;
+
+    #[test]
+    fn basic_arithmetic_builtins() {
+        assert_eq!(
+            Ok(Value::FloatV { f_v: 4.0 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Add,
+                args: vec![Float { f: 2.0 }, Float { f: 2.0 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::IntV { i_v: 4 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Add,
+                args: vec![Integer { i: 2 }, Integer { i: 2 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::FloatV { f_v: 0.0 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Subtract,
+                args: vec![Float { f: 2.0 }, Float { f: 2.0 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::IntV { i_v: 0 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Subtract,
+                args: vec![Integer { i: 2 }, Integer { i: 2 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::FloatV { f_v: 4.0 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Multiply,
+                args: vec![Float { f: 2.0 }, Float { f: 2.0 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::IntV { i_v: 4 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Multiply,
+                args: vec![Integer { i: 2 }, Integer { i: 2 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::FloatV { f_v: 1.0 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Divide,
+                args: vec![Float { f: 2.0 }, Float { f: 2.0 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::IntV { i_v: 1 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Divide,
+                args: vec![Integer { i: 2 }, Integer { i: 2 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::FloatV { f_v: 0.0 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Modulus,
+                args: vec![Float { f: 2.0 }, Float { f: 2.0 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::IntV { i_v: 0 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Modulus,
+                args: vec![Integer { i: 2 }, Integer { i: 2 }]
+            })
+        );
+    }
+
+    #[test]
+    fn floating_point_builtins() {
+        assert_eq!(
+            Ok(Value::IntV { i_v: 2 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Truncate,
+                args: vec![Integer { i: 2 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::FloatV { f_v: 2.0 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Truncate,
+                args: vec![Float { f: 2.1938 }]
+            }),
+        );
+
+        assert_eq!(
+            Ok(Value::IntV { i_v: 2 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Round,
+                args: vec![Integer { i: 2 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::FloatV { f_v: 2.0 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Round,
+                args: vec![Float { f: 2.1938 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::FloatV { f_v: 0.0 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Fraction,
+                args: vec![Integer { i: 2 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::FloatV {
+                f_v: 2.1938f64.fract()
+            }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Fraction,
+                args: vec![Float { f: 2.1938 }]
+            })
+        );
+    }
+
+    #[test]
+    fn trig_bulitins() {
+        assert_eq!(
+            Ok(Value::FloatV {
+                f_v: f64::sin(0.33) as f64
+            }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Sin,
+                args: vec![Float { f: 0.33 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::FloatV {
+                f_v: f64::cos(0.33) as f64
+            }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Cos,
+                args: vec![Float { f: 0.33 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::FloatV {
+                f_v: f64::tan(0.33) as f64
+            }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Tan,
+                args: vec![Float { f: 0.33 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::FloatV {
+                f_v: f64::asin(0.33) as f64
+            }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::ArcSin,
+                args: vec![Float { f: 0.33 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::FloatV {
+                f_v: f64::acos(0.33) as f64
+            }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::ArcCos,
+                args: vec![Float { f: 0.33 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::FloatV {
+                f_v: f64::atan(0.33) as f64
+            }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::ArcTan,
+                args: vec![Float { f: 0.33 }]
+            })
+        );
+    }
+
+    #[test]
+    fn hyperbolic_bulitins() {
+        use std::f64::consts::E;
+        assert_eq!(
+            Ok(Value::FloatV {
+                f_v: f64::sinh(1.0) as f64
+            }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Sinh,
+                args: vec![Float { f: 1.0 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::FloatV {
+                f_v: f64::cosh(1.0) as f64
+            }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Cosh,
+                args: vec![Float { f: 1.0 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::FloatV {
+                f_v: f64::tanh(1.0) as f64
+            }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Tanh,
+                args: vec![Float { f: 1.0 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::FloatV {
+                f_v: f64::asinh(E) as f64
+            }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::ArcSinh,
+                args: vec![Float { f: E }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::FloatV {
+                f_v: f64::acosh(E) as f64
+            }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::ArcCosh,
+                args: vec![Float { f: E }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::FloatV {
+                f_v: f64::atanh(1.0) as f64
+            }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::ArcTanh,
+                args: vec![Float { f: 1.0 }]
+            })
+        );
+    }
+
+    #[test]
+    fn factorial_builtins() {
+        assert_eq!(
+            Ok(Value::IntV { i_v: 1 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Factorial,
+                args: vec![Integer { i: 0 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::IntV { i_v: 120 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Factorial,
+                args: vec![Integer { i: 5 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::IntV { i_v: 60 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Permutation,
+                args: vec![Integer { i: 5 }, Integer { i: 3 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::IntV { i_v: 10 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Choose,
+                args: vec![Integer { i: 5 }, Integer { i: 3 }]
+            })
+        );
+    }
+
+    #[test]
+    fn log_bulitins() {
+        assert_eq!(
+            Ok(Value::FloatV {
+                f_v: f64::ln(0.33) as f64
+            }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Ln,
+                args: vec![Float { f: 0.33 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::FloatV {
+                f_v: f64::log2(0.33) as f64
+            }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Log2,
+                args: vec![Float { f: 0.33 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::FloatV {
+                f_v: f64::log10(0.33) as f64
+            }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Log10,
+                args: vec![Float { f: 0.33 }]
+            })
+        );
+    }
+
+    #[test]
+    fn pow_int_int() {
+        assert_eq!(
+            Ok(Value::IntV { i_v: 8 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Pow,
+                args: vec![Integer { i: 2 }, Integer { i: 3 }]
+            })
+        );
+    }
+
+    #[test]
+    fn pow_int_float() {
+        assert_eq!(
+            Ok(Value::FloatV { f_v: 8.0 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Pow,
+                args: vec![Integer { i: 2 }, Float { f: 3.0 }]
+            })
+        );
+    }
+
+    #[test]
+    fn pow_float_int() {
+        assert_eq!(
+            Ok(Value::FloatV { f_v: 8.0 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Pow,
+                args: vec![Float { f: 2.0 }, Integer { i: 3 }]
+            })
+        );
+    }
+
+    #[test]
+    fn pow_float_float() {
+        assert_eq!(
+            Ok(Value::FloatV { f_v: 8.0 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Pow,
+                args: vec![Float { f: 2.0 }, Float { f: 3.0 }]
+            })
+        );
+    }
+
+    #[test]
+    fn sqrt_int_int() {
+        assert_eq!(
+            Ok(Value::FloatV { f_v: 2.0 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Sqrt,
+                args: vec![Integer { i: 4 }]
+            })
+        );
+    }
 
-pub fn interp_expr(expr: &Expr) -> Value {
-    let mut rng: rand::prelude::ThreadRng = thread_rng();
+    #[test]
+    fn sqrt_float() {
+        assert_eq!(
+            Ok(Value::FloatV { f_v: 2.0 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Sqrt,
+                args: vec![Float { f: 4.0 }]
+            })
+        );
+    }
 
-    use Expr::*;
-    match expr {
-        Integer { i } => Value::IntV { i_v: *i },
-        Float { f } => Value::FloatV { f_v: *f },
-        BuiltinFn { name, args } => match name {
-            Builtin::Sin => do_single_arg_f64(f64::sin, &args),
-            Builtin::Cos => do_single_arg_f64(f64::cos, &args),
-            Builtin::Tan => do_single_arg_f64(f64::tan, &args),
-            Builtin::ArcSin => do_single_arg_f64(f64::asin, &args),
-            Builtin::ArcCos => do_single_arg_f64(f64::acos, &args),
-            Builtin::ArcTan => do_single_arg_f64(f64::atan, &args),
-            Builtin::Ln => do_single_arg_f64(f64::ln, &args),
-            Builtin::Log10 => do_single_arg_f64(f64::log10, &args),
-            Builtin::Log2 => do_single_arg_f64(f64::log2, &args),
-            Builtin::Sinh => do_single_arg_f64(f64::sinh, &args),
-            Builtin::Cosh => do_single_arg_f64(f64::cosh, &args),
-            Builtin::Tanh => do_single_arg_f64(f64::tanh, &args),
-            Builtin::ArcSinh => do_single_arg_f64(f64::asinh, &args),
-            Builtin::ArcCosh => do_single_arg_f64(f64::acosh, &args),
-            Builtin::ArcTanh => do_single_arg_f64(f64::atanh, &args),
-            Builtin::Pow => {
-                if args.len() != 2 {
-                    return Value::Error {
-                        msg: "Incorrect Number of Args".to_string(),
-                    };
-                }
-                let a_v: Value = interp_expr(&args[0]);
-                let b_v: Value = interp_expr(&args[1]);
-                match pair_up(a_v, b_v) {
-                    ValuePair::IntInt { a, b } => {
-                        if b < 0 {
-                            return Value::Error {
-                                msg: "Cannot Exponentiate by Negative Number".to_string(),
-                            };
-                        }
-                        Value::IntV {
-                            i_v: a.pow(b as u32),
-                        }
-                    }
-                    ValuePair::FloatInt { a, b } => match b.try_into() {
-                        Ok(b_pow) => return Value::FloatV { f_v: a.powi(b_pow) },
-                        Err(b) => Value::Error { msg: b.to_string() },
-                    },
-                    ValuePair::IntFloat { a, b } => Value::FloatV {
-                        f_v: (a as f64).powf(b),
-                    },
-                    ValuePair::FloatFloat { a, b } => Value::FloatV { f_v: a.powf(b) },
-                }
-            }
-            Builtin::Abs => {
-                if args.len() != 1 {
-                    return Value::Error {
-                        msg: "Incorrect Number of Args".to_string(),
-                    };
-                }
-                let a_v: Value = interp_expr(&args[0]);
-                match a_v {
-                    Value::IntV { i_v } => Value::IntV { i_v: i_v.abs() },
-                    Value::FloatV { f_v } => Value::FloatV { f_v: f_v.abs() },
-                    Value::Error { msg } => Value::Error { msg: msg },
-                }
-            }
-            Builtin::Choose => {
-                if args.len() != 2 {
-                    return Value::Error {
-                        msg: "Incorrect Number of Args".to_string(),
-                    };
-                }
-                let a_v: Value = interp_expr(&args[0]);
-                let b_v: Value = interp_expr(&args[1]);
-                match pair_up(a_v, b_v) {
-                    ValuePair::IntInt { a, b } => Value::IntV {
-                        i_v: factorial(a) / (factorial(b) * factorial(a - b)),
-                    },
-                    _ => Value::Error {
-                        msg: "Int Parameters Only".to_string(),
-                    },
-                }
-            }
-            Builtin::Permutation => {
-                let a_v: Value = interp_expr(&args[0]);
-                let b_v: Value = interp_expr(&args[1]);
-                match pair_up(a_v, b_v) {
-                    ValuePair::IntInt { a, b } => {
-                        if a >= 0 && b >= 0 && b <= u32::MAX as i64 {
-                            Value::IntV {
-                                i_v: factorial(a) / factorial(a - b),
-                            }
-                        } else {
-                            Value::Error {
-                                msg: " Positive Integer Parameters Only".to_string(),
-                            }
-                        }
-                    }
-                    _ => Value::Error {
-                        msg: "Int Parameters Only".to_string(),
-                    },
-                }
-            }
-            Builtin::Round => {
-                if args.len() != 1 {
-                    return Value::Error {
-                        msg: "Incorrect Number of Args".to_string(),
-                    };
-                }
-                let a_v: Value = interp_expr(&args[0]);
-                match a_v {
-                    Value::IntV { i_v } => Value::IntV { i_v: i_v },
-                    Value::FloatV { f_v } => Value::FloatV { f_v: f_v.round() },
-                    Value::Error { msg } => Value::Error { msg: msg },
-                }
-            }
-            Builtin::Truncate => {
-                if args.len() != 1 {
-                    return Value::Error {
-                        msg: "Incorrect Number of Args".to_string(),
-                    };
-                }
-                let a_v: Value = interp_expr(&args[0]);
-                match a_v {
-                    Value::IntV { i_v } => Value::IntV { i_v: i_v },
-                    Value::FloatV { f_v } => Value::FloatV { f_v: f_v.trunc() },
-                    Value::Error { msg } => Value::Error { msg: msg },
-                }
-            }
-            Builtin::Fraction => {
-                if args.len() != 1 {
-                    return Value::Error {
-                        msg: "Incorrect Number of Args".to_string(),
-                    };
-                }
-                let a_v: Value = interp_expr(&args[0]);
-                match a_v {
-                    Value::IntV { i_v: _ } => Value::FloatV { f_v: 0.0 },
-                    Value::FloatV { f_v } => Value::FloatV { f_v: f_v.fract() },
-                    Value::Error { msg } => Value::Error { msg: msg },
-                }
-            }
-            Builtin::Max => do_binop(&args, |l, r| l.max(r), |l, r| l.max(r)),
-            Builtin::Min => do_binop(&args, |l, r| l.min(r), |l, r| l.min(r)),
-            Builtin::Rand => {
-                if !args.is_empty() {
-                    return Value::Error {
-                        msg: "Incorrect Number of Args".to_string(),
-                    };
-                }
-                Value::FloatV { f_v: rng.gen() }
-            }
-            Builtin::RandInt => match args.len() {
-                0 => Value::IntV {
-                    i_v: random::<i64>(),
-                },
-                1 => {
-                    let a_v: Value = interp_expr(&args[0]);
-                    match a_v {
-                        Value::IntV { i_v } => Value::IntV {
-                            i_v: rng.gen_range(0..i_v),
-                        },
-                        Value::FloatV { f_v: _ } => Value::Error {
-                            msg: "Float in Int Parameter".to_string(),
-                        },
-                        Value::Error { msg } => Value::Error { msg: msg },
-                    }
-                }
-                2 => {
-                    let a_v: Value = interp_expr(&args[0]);
-                    let b_v: Value = interp_expr(&args[1]);
-                    match pair_up(a_v, b_v) {
-                        ValuePair::IntInt { a, b } => Value::IntV {
-                            i_v: rng.gen_range(a..b),
-                        },
-                        _ => Value::Error {
-                            msg: "Int Parameters Only".to_string(),
-                        },
-                    }
-                }
-                _ => Value::Error {
-                    msg: "Invalid Number of Args".to_string(),
-                },
-            },
-            Builtin::GCD => {
-                if args.len() != 2 {
-                    return Value::Error {
-                        msg: "Invalid Number of Args".to_string(),
-                    };
-                }
-                let l_v: Value = interp_expr(&args[0]);
-                let r_v: Value = interp_expr(&args[1]);
-                match pair_up(l_v, r_v) {
-                    ValuePair::IntInt { a, b } => Value::IntV { i_v: gcd(a, b) },
-                    _ => Value::Error {
-                        msg: "Integer Arguments Only".to_string(),
-                    },
-                }
-            }
-            Builtin::PercentOf => {
-                if args.len() != 2 {
-                    return Value::Error {
-                        msg: "Invalid Number of Args".to_string(),
-                    };
-                }
-                let l_v: Value = interp_expr(&args[0]);
-                let r_v: Value = interp_expr(&args[1]);
-                match pair_up(l_v, r_v) {
-                    ValuePair::IntInt { a, b } => Value::FloatV {
-                        f_v: a as f64 * (b as f64 / 100.0),
-                    },
-                    ValuePair::IntFloat { a, b } => Value::FloatV {
-                        f_v: a as f64 * (b as f64 / 100.0),
-                    },
-                    ValuePair::FloatInt { a, b } => Value::FloatV {
-                        f_v: a as f64 * (b as f64 / 100.0),
-                    },
-                    ValuePair::FloatFloat { a, b } => Value::FloatV {
-                        f_v: a as f64 * (b as f64 / 100.0),
+    #[test]
+    fn error_in_argument_does_not_panic() {
+        // add(sqrt(1), factorial(1.5))
+        assert_eq!(
+            Err(EvalError::TypeMismatch {
+                builtin: Builtin::Factorial,
+                arg_index: 0,
+                expected: "an Integer"
+            }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Add,
+                args: vec![
+                    BuiltinFn {
+                        name: Builtin::Sqrt,
+                        args: vec![Integer { i: 1 }]
                     },
-                }
-            }
-            Builtin::Add => do_binop(&args, |a, b| a + b, |a, b| a + b),
-            Builtin::Subtract => do_binop(&args, |a, b| a - b, |a, b| a - b),
-            Builtin::Multiply => do_binop(&args, |a, b| a * b, |a, b| a * b),
-            Builtin::Divide => do_binop(&args, |a, b| a / b, |a, b| a / b),
-            Builtin::Modulus => do_binop(&args, |a, b| a % b, |a, b| a % b),
-            Builtin::Factorial => {
-                if args.len() != 1 {
-                    return Value::Error {
-                        msg: "Invalid Number of Args".to_string(),
-                    };
-                } else {
-                    factorial_value(interp_expr(&args[0]))
-                }
-            }
-            Builtin::Sqrt => {
-                if args.len() != 1 {
-                    return Value::Error {
-                        msg: "Invalid Number of Args".to_string(),
-                    };
-                } else {
-                    match interp_expr(&args[0]) {
-                        Value::IntV { i_v } => Value::FloatV{ f_v: (i_v as f64).sqrt()},
-                        Value::FloatV { f_v } => Value::FloatV { f_v: f_v.sqrt()},
-                        Value::Error { msg } => Value::Error { msg: msg },
+                    BuiltinFn {
+                        name: Builtin::Factorial,
+                        args: vec![Float { f: 1.5 }]
                     }
-                }
-            },
-        },
+                ]
+            })
+        );
     }
-}
 
-fn do_binop(args: &Vec<Expr>, int_op: fn(i64, i64) -> i64, float_op: fn(f64, f64) -> f64) -> Value {
-    if args.len() != 2 {
-        return Value::Error {
-            msg: "Invalid Number of Args".to_string(),
-        };
-    }
-    let a_v: Value = interp_expr(&args[0]);
-    let b_v: Value = interp_expr(&args[1]);
-    match pair_up(a_v, b_v) {
-        ValuePair::IntInt { a, b } => Value::IntV { i_v: int_op(a, b) },
-        ValuePair::FloatInt { a, b } => Value::FloatV {
-            f_v: float_op(a, b as f64),
-        },
-        ValuePair::IntFloat { a, b } => Value::FloatV {
-            f_v: float_op(a as f64, b),
-        },
-        ValuePair::FloatFloat { a, b } => Value::FloatV {
-            f_v: float_op(a, b),
-        },
-    }
-}
+    #[test]
+    fn arity_errors() {
+        assert_eq!(
+            Err(EvalError::Arity {
+                builtin: Builtin::Permutation,
+                expected: ArgCount::Exactly(2),
+                found: 1
+            }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Permutation,
+                args: vec![Integer { i: 5 }]
+            })
+        );
 
-fn do_single_arg_f64(single_arg_fn: fn(f64) -> f64, args: &Vec<Expr>) -> Value {
-    if args.len() != 1 {
-        return Value::Error {
-            msg: format!("Incorrect Length {}", args.len()),
-        };
-    }
-    let a_v: Value = interp_expr(&args[0]);
-    match a_v {
-        Value::IntV { i_v } => Value::FloatV {
-            f_v: single_arg_fn(i_v as f64),
-        },
-        Value::FloatV { f_v } => Value::FloatV {
-            f_v: single_arg_fn(f_v),
-        },
-        Value::Error { msg } => Value::Error { msg: msg },
+        assert_eq!(
+            Err(EvalError::Arity {
+                builtin: Builtin::RandInt,
+                expected: ArgCount::Between(0, 2),
+                found: 3
+            }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::RandInt,
+                args: vec![Integer { i: 1 }, Integer { i: 2 }, Integer { i: 3 }]
+            })
+        );
     }
-}
 
-fn factorial_value(n_v: Value) -> Value {
-    match n_v {
-        Value::IntV { i_v } => {
-            if i_v < 0 {
-                Value::Error {
-                    msg: "Cannot Apply Factorial to Negative Integers".to_string(),
-                }
-            } else {
-                Value::IntV {
-                    i_v: factorial(i_v),
-                }
-            }
-        }
-        Value::FloatV { f_v: _ } => Value::Error {
-            msg: "Cannot Apply Factorial to f64".to_string(),
-        },
-        Value::Error { msg } => Value::Error { msg: msg },
+    #[test]
+    fn division_by_zero() {
+        assert_eq!(
+            Err(EvalError::DivisionByZero {
+                builtin: Builtin::Divide,
+                arg_index: 1
+            }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Divide,
+                args: vec![Integer { i: 1 }, Integer { i: 0 }]
+            })
+        );
+
+        assert_eq!(
+            Err(EvalError::DivisionByZero {
+                builtin: Builtin::Modulus,
+                arg_index: 1
+            }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Modulus,
+                args: vec![Float { f: 1.0 }, Float { f: 0.0 }]
+            })
+        );
     }
-}
 
-fn factorial(i: i64) -> i64 {
-    return (1..=i).fold(1, |acc: i64, i: i64| acc * i);
-}
+    #[test]
+    fn overflow_and_domain_errors() {
+        assert_eq!(
+            Err(EvalError::Overflow {
+                builtin: Builtin::Pow
+            }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Pow,
+                args: vec![Integer { i: 10 }, Integer { i: i64::MAX }]
+            })
+        );
+
+        assert_eq!(
+            Err(EvalError::Domain {
+                builtin: Builtin::Choose,
+                arg_index: 1,
+                reason: "Must Be Between 0 and Arg 1"
+            }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Choose,
+                args: vec![Integer { i: 3 }, Integer { i: 5 }]
+            })
+        );
 
-fn gcd(a: i64, b: i64) -> i64 {
-    if a == 0 {
-        return b;
+        assert_eq!(
+            Err(EvalError::Domain {
+                builtin: Builtin::RandInt,
+                arg_index: 0,
+                reason: "Must Be Positive"
+            }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::RandInt,
+                args: vec![Integer { i: 0 }]
+            })
+        );
     }
-    return gcd(b % a, a);
-}
 
-#[cfg(test)]
-mod interp_tests {
-    use super::*;
-    use Expr::*;
+    fn big(digits: &str) -> Value {
+        return Value::BigIntV {
+            b_v: digits.parse().unwrap(),
+        };
+    }
 
     #[test]
-    fn basic_arithmetic_builtins() {
+    fn overflow_promotes_to_big_int() {
         assert_eq!(
-            Value::FloatV { f_v: 4.0 },
+            Ok(big("15511210043330985984000000")),
             interp_expr(&BuiltinFn {
-                name: Builtin::Add,
-                args: vec![Float { f: 2.0 }, Float { f: 2.0 }]
+                name: Builtin::Factorial,
+                args: vec![Integer { i: 25 }]
             })
         );
 
         assert_eq!(
-            Value::IntV { i_v: 4 },
+            Ok(big("1000000000000000000000000000000")),
             interp_expr(&BuiltinFn {
-                name: Builtin::Add,
-                args: vec![Integer { i: 2 }, Integer { i: 2 }]
+                name: Builtin::Pow,
+                args: vec![Integer { i: 10 }, Integer { i: 30 }]
             })
         );
 
         assert_eq!(
-            Value::FloatV { f_v: 0.0 },
+            Ok(big("18446744073709551614")),
             interp_expr(&BuiltinFn {
-                name: Builtin::Subtract,
-                args: vec![Float { f: 2.0 }, Float { f: 2.0 }]
+                name: Builtin::Multiply,
+                args: vec![Integer { i: i64::MAX }, Integer { i: 2 }]
             })
         );
 
+        // Results that fit narrow back down
         assert_eq!(
-            Value::IntV { i_v: 0 },
+            Ok(Value::IntV { i_v: 1 }),
             interp_expr(&BuiltinFn {
                 name: Builtin::Subtract,
-                args: vec![Integer { i: 2 }, Integer { i: 2 }]
+                args: vec![
+                    BuiltinFn {
+                        name: Builtin::Add,
+                        args: vec![Integer { i: i64::MAX }, Integer { i: 1 }]
+                    },
+                    Integer { i: i64::MAX }
+                ]
             })
         );
+    }
+
+    #[test]
+    fn overflow_promotes_to_float() {
+        let config = EvalConfig {
+            int_overflow: IntOverflow::PromoteToFloat,
+            ..Default::default()
+        };
+        assert_eq!(
+            Ok(Value::FloatV { f_v: 1e30 }),
+            interp_expr_with_config(
+                &BuiltinFn {
+                    name: Builtin::Pow,
+                    args: vec![Integer { i: 10 }, Integer { i: 30 }]
+                },
+                &config
+            )
+        );
 
         assert_eq!(
-            Value::FloatV { f_v: 4.0 },
+            Ok(Value::FloatV {
+                f_v: (1..=25).fold(1.0, |acc, i| acc * i as f64)
+            }),
+            interp_expr_with_config(
+                &BuiltinFn {
+                    name: Builtin::Factorial,
+                    args: vec![Integer { i: 25 }]
+                },
+                &config
+            )
+        );
+    }
+
+    #[test]
+    fn choose_without_factorials() {
+        assert_eq!(
+            Ok(Value::IntV {
+                i_v: 118264581564861424
+            }),
             interp_expr(&BuiltinFn {
-                name: Builtin::Multiply,
-                args: vec![Float { f: 2.0 }, Float { f: 2.0 }]
+                name: Builtin::Choose,
+                args: vec![Integer { i: 60 }, Integer { i: 30 }]
             })
         );
 
         assert_eq!(
-            Value::IntV { i_v: 4 },
+            Ok(big("100891344545564193334812497256")),
             interp_expr(&BuiltinFn {
-                name: Builtin::Multiply,
-                args: vec![Integer { i: 2 }, Integer { i: 2 }]
+                name: Builtin::Choose,
+                args: vec![Integer { i: 100 }, Integer { i: 50 }]
             })
         );
 
         assert_eq!(
-            Value::FloatV { f_v: 1.0 },
+            Ok(Value::IntV { i_v: 9900 }),
             interp_expr(&BuiltinFn {
-                name: Builtin::Divide,
-                args: vec![Float { f: 2.0 }, Float { f: 2.0 }]
+                name: Builtin::Permutation,
+                args: vec![Integer { i: 100 }, Integer { i: 2 }]
             })
         );
+    }
 
+    #[test]
+    fn big_int_builtins() {
+        let a = BigInteger {
+            b: "100000000000000000000".parse().unwrap(),
+        };
         assert_eq!(
-            Value::IntV { i_v: 1 },
+            Ok(big("25000000000000000000")),
             interp_expr(&BuiltinFn {
                 name: Builtin::Divide,
-                args: vec![Integer { i: 2 }, Integer { i: 2 }]
+                args: vec![a.clone(), Integer { i: 4 }]
             })
         );
 
         assert_eq!(
-            Value::FloatV { f_v: 0.0 },
+            Ok(Value::IntV { i_v: 1 }),
             interp_expr(&BuiltinFn {
                 name: Builtin::Modulus,
-                args: vec![Float { f: 2.0 }, Float { f: 2.0 }]
+                args: vec![a.clone(), Integer { i: 3 }]
             })
         );
 
         assert_eq!(
-            Value::IntV { i_v: 0 },
+            Ok(Value::IntV { i_v: 15625 }),
             interp_expr(&BuiltinFn {
-                name: Builtin::Modulus,
-                args: vec![Integer { i: 2 }, Integer { i: 2 }]
+                name: Builtin::GCD,
+                args: vec![a.clone(), Integer { i: 46875 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(big("4999999999999999999950000000000000000000")),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Choose,
+                args: vec![a.clone(), Integer { i: 2 }]
+            })
+        );
+
+        assert_eq!(
+            Ok(Value::FloatV { f_v: 1e20 + 0.5 }),
+            interp_expr(&BuiltinFn {
+                name: Builtin::Add,
+                args: vec![a.clone(), Float { f: 0.5 }]
             })
         );
     }
 
     #[test]
-    fn floating_point_builtins() {
+    fn big_int_renders_exactly() {
         assert_eq!(
-            Value::IntV { i_v: 2 },
+            "30414093201713378043612608166064768844377641568960512000000000000",
             interp_expr(&BuiltinFn {
-                name: Builtin::Truncate,
-                args: vec![Integer { i: 2 }]
+                name: Builtin::Factorial,
+                args: vec![Integer { i: 50 }]
             })
+            .unwrap()
+            .to_string()
+        );
+    }
+
+    fn rational(numer: i64, denom: i64) -> Value {
+        return Value::RationalV {
+            r_v: BigRational::new(numer.into(), denom.into()),
+        };
+    }
+
+    fn div(a: Expr, b: Expr) -> Expr {
+        return BuiltinFn {
+            name: Builtin::Divide,
+            args: vec![a, b],
+        };
+    }
+
+    #[test]
+    fn integer_division_is_exact() {
+        assert_eq!(
+            Ok(rational(1, 3)),
+            interp_expr(&div(Integer { i: 1 }, Integer { i: 3 }))
         );
 
         assert_eq!(
-            Value::FloatV { f_v: 2.0 },
+            Ok(Value::IntV { i_v: 1 }),
             interp_expr(&BuiltinFn {
-                name: Builtin::Truncate,
-                args: vec![Float { f: 2.1938 }]
-            }),
+                name: Builtin::Add,
+                args: vec![
+                    div(Integer { i: 1 }, Integer { i: 3 }),
+                    div(Integer { i: 2 }, Integer { i: 3 })
+                ]
+            })
         );
 
         assert_eq!(
-            Value::IntV { i_v: 2 },
+            Ok(Value::FloatV { f_v: 0.5 }),
+            interp_expr(&div(Integer { i: 1 }, Float { f: 2.0 }))
+        );
+
+        assert_eq!(
+            "-5/6",
             interp_expr(&BuiltinFn {
-                name: Builtin::Round,
-                args: vec![Integer { i: 2 }]
+                name: Builtin::Subtract,
+                args: vec![div(Integer { i: 1 }, Integer { i: 6 }), Integer { i: 1 }]
             })
+            .unwrap()
+            .to_string()
         );
+    }
 
+    #[test]
+    fn rational_pow() {
         assert_eq!(
-            Value::FloatV { f_v: 2.0 },
+            Ok(rational(8, 27)),
             interp_expr(&BuiltinFn {
-                name: Builtin::Round,
-                args: vec![Float { f: 2.1938 }]
+                name: Builtin::Pow,
+                args: vec![div(Integer { i: 2 }, Integer { i: 3 }), Integer { i: 3 }]
             })
         );
 
         assert_eq!(
-            Value::FloatV { f_v: 0.0 },
+            Ok(rational(1, 8)),
             interp_expr(&BuiltinFn {
-                name: Builtin::Fraction,
-                args: vec![Integer { i: 2 }]
+                name: Builtin::Pow,
+                args: vec![Integer { i: 2 }, Integer { i: -3 }]
             })
         );
 
         assert_eq!(
-            Value::FloatV {
-                f_v: 2.1938f64.fract()
-            },
+            Err(EvalError::DivisionByZero {
+                builtin: Builtin::Pow,
+                arg_index: 0
+            }),
             interp_expr(&BuiltinFn {
-                name: Builtin::Fraction,
-                args: vec![Float { f: 2.1938 }]
+                name: Builtin::Pow,
+                args: vec![Integer { i: 0 }, Integer { i: -1 }]
             })
         );
     }
 
     #[test]
-    fn trig_bulitins() {
+    fn rational_conversion_builtins() {
+        let third = div(Integer { i: 2 }, Integer { i: 6 });
         assert_eq!(
-            Value::FloatV {
-                f_v: f64::sin(0.33) as f64
-            },
+            Ok(Value::FloatV { f_v: 1.0 / 3.0 }),
             interp_expr(&BuiltinFn {
-                name: Builtin::Sin,
-                args: vec![Float { f: 0.33 }]
+                name: Builtin::ToFloat,
+                args: vec![third.clone()]
             })
         );
 
         assert_eq!(
-            Value::FloatV {
-                f_v: f64::cos(0.33) as f64
-            },
+            Ok(Value::IntV { i_v: 1 }),
             interp_expr(&BuiltinFn {
-                name: Builtin::Cos,
-                args: vec![Float { f: 0.33 }]
+                name: Builtin::Numerator,
+                args: vec![third.clone()]
             })
         );
 
         assert_eq!(
-            Value::FloatV {
-                f_v: f64::tan(0.33) as f64
-            },
+            Ok(Value::IntV { i_v: 3 }),
             interp_expr(&BuiltinFn {
-                name: Builtin::Tan,
-                args: vec![Float { f: 0.33 }]
+                name: Builtin::Denominator,
+                args: vec![third.clone()]
             })
         );
 
         assert_eq!(
-            Value::FloatV {
-                f_v: f64::asin(0.33) as f64
-            },
+            Ok(Value::IntV { i_v: 0 }),
             interp_expr(&BuiltinFn {
-                name: Builtin::ArcSin,
-                args: vec![Float { f: 0.33 }]
+                name: Builtin::Round,
+                args: vec![third]
             })
         );
+    }
+
+    fn complex(re: f64, im: f64) -> Value {
+        return Value::ComplexV {
+            c_v: Complex64::new(re, im),
+        };
+    }
+
+    fn call(name: Builtin, args: Vec<Expr>) -> Expr {
+        return BuiltinFn { name, args };
+    }
+
+    #[test]
+    fn real_functions_give_principal_complex_results() {
+        assert_eq!(
+            Ok(complex(0.0, 2.0)),
+            interp_expr(&call(Builtin::Sqrt, vec![Integer { i: -4 }]))
+        );
 
         assert_eq!(
-            Value::FloatV {
-                f_v: f64::acos(0.33) as f64
-            },
-            interp_expr(&BuiltinFn {
-                name: Builtin::ArcCos,
-                args: vec![Float { f: 0.33 }]
-            })
+            Ok(complex(0.0, std::f64::consts::PI)),
+            interp_expr(&call(Builtin::Ln, vec![Integer { i: -1 }]))
         );
 
         assert_eq!(
-            Value::FloatV {
-                f_v: f64::atan(0.33) as f64
-            },
-            interp_expr(&BuiltinFn {
-                name: Builtin::ArcTan,
-                args: vec![Float { f: 0.33 }]
-            })
+            Ok(Value::from_complex(Complex64::new(2.0, 0.0).asin())),
+            interp_expr(&call(Builtin::ArcSin, vec![Integer { i: 2 }]))
+        );
+
+        assert_eq!(
+            Ok(Value::from_complex(Complex64::new(-8.0, 0.0).powf(0.5))),
+            interp_expr(&call(
+                Builtin::Pow,
+                vec![Integer { i: -8 }, Float { f: 0.5 }]
+            ))
         );
     }
 
     #[test]
-    fn hyperbolic_bulitins() {
-        use std::f64::consts::E;
+    fn complex_arithmetic() {
+        let i = Imaginary { im: 1.0 };
         assert_eq!(
-            Value::FloatV {
-                f_v: f64::sinh(1.0) as f64
-            },
-            interp_expr(&BuiltinFn {
-                name: Builtin::Sinh,
-                args: vec![Float { f: 1.0 }]
-            })
+            Ok(Value::FloatV { f_v: -1.0 }),
+            interp_expr(&call(Builtin::Multiply, vec![i.clone(), i.clone()]))
         );
 
         assert_eq!(
-            Value::FloatV {
-                f_v: f64::cosh(1.0) as f64
-            },
-            interp_expr(&BuiltinFn {
-                name: Builtin::Cosh,
-                args: vec![Float { f: 1.0 }]
-            })
+            Ok(complex(1.0, -1.0)),
+            interp_expr(&call(
+                Builtin::Divide,
+                vec![
+                    Integer { i: 2 },
+                    call(Builtin::Add, vec![Integer { i: 1 }, i.clone()])
+                ]
+            ))
         );
 
         assert_eq!(
-            Value::FloatV {
-                f_v: f64::tanh(1.0) as f64
-            },
-            interp_expr(&BuiltinFn {
-                name: Builtin::Tanh,
-                args: vec![Float { f: 1.0 }]
-            })
+            Ok(Value::FloatV { f_v: 5.0 }),
+            interp_expr(&call(
+                Builtin::Abs,
+                vec![call(
+                    Builtin::Add,
+                    vec![Integer { i: 3 }, Imaginary { im: 4.0 }]
+                )]
+            ))
         );
 
         assert_eq!(
-            Value::FloatV {
-                f_v: f64::asinh(E) as f64
-            },
-            interp_expr(&BuiltinFn {
-                name: Builtin::ArcSinh,
-                args: vec![Float { f: E }]
-            })
+            "1-2i",
+            interp_expr(&call(
+                Builtin::Subtract,
+                vec![Integer { i: 1 }, Imaginary { im: 2.0 }]
+            ))
+            .unwrap()
+            .to_string()
         );
 
         assert_eq!(
-            Value::FloatV {
-                f_v: f64::acosh(E) as f64
-            },
-            interp_expr(&BuiltinFn {
-                name: Builtin::ArcCosh,
-                args: vec![Float { f: E }]
-            })
+            Err(EvalError::TypeMismatch {
+                builtin: Builtin::Max,
+                arg_index: 1,
+                expected: "a Real Number"
+            }),
+            interp_expr(&call(Builtin::Max, vec![Integer { i: 1 }, i]))
         );
+    }
+
+    #[test]
+    fn deep_nesting_does_not_overflow() {
+        let mut expr: Expr = Integer { i: 0 };
+        for _ in 0..20_000 {
+            expr = call(Builtin::Add, vec![Integer { i: 1 }, expr]);
+        }
+        assert_eq!(Ok(Value::IntV { i_v: 20_000 }), interp_expr(&expr));
+        // Dropping is recursive too, so unwind the expression by hand
+        while let BuiltinFn { mut args, .. } = expr {
+            expr = args.pop().unwrap();
+        }
+    }
 
+    #[test]
+    fn non_finite_complex_display() {
+        let show = |expr: Expr| -> String {
+            return interp_expr(&expr).unwrap().to_string();
+        };
+        let i = || Imaginary { im: 1.0 };
         assert_eq!(
-            Value::FloatV {
-                f_v: f64::atanh(1.0) as f64
-            },
-            interp_expr(&BuiltinFn {
-                name: Builtin::ArcTanh,
-                args: vec![Float { f: 1.0 }]
-            })
+            "NaN+NaN*i",
+            show(call(Builtin::Multiply, vec![i(), Float { f: f64::NAN }]))
+        );
+        let infinite_i: Expr = call(Builtin::Multiply, vec![i(), Float { f: f64::INFINITY }]);
+        assert_eq!(
+            "NaN+inf*i",
+            show(call(
+                Builtin::Add,
+                vec![Integer { i: 1 }, infinite_i.clone()]
+            ))
+        );
+        assert_eq!(
+            "NaN-inf*i",
+            show(call(Builtin::Subtract, vec![Integer { i: 1 }, infinite_i]))
         );
     }
 
     #[test]
-    fn factorial_builtins() {
+    fn session_variables_persist() {
+        let mut env = Env::new();
         assert_eq!(
-            Value::IntV { i_v: 1 },
-            interp_expr(&BuiltinFn {
-                name: Builtin::Factorial,
-                args: vec![Integer { i: 0 }]
-            })
+            "5",
+            evaluate_prefix_expression_in_env("set(x, 5)", &mut env)
         );
-
         assert_eq!(
-            Value::IntV { i_v: 120 },
-            interp_expr(&BuiltinFn {
-                name: Builtin::Factorial,
-                args: vec![Integer { i: 5 }]
-            })
+            "10",
+            evaluate_prefix_expression_in_env("mult(x, 2)", &mut env)
+        );
+        assert_eq!(
+            "6",
+            evaluate_prefix_expression_in_env("set(x, add(x, 1))", &mut env)
         );
 
+        // let only binds inside its body
         assert_eq!(
-            Value::IntV { i_v: 60 },
-            interp_expr(&BuiltinFn {
-                name: Builtin::Permutation,
-                args: vec![Integer { i: 5 }, Integer { i: 3 }]
-            })
+            "3",
+            evaluate_prefix_expression_in_env("let(y, 2, add(y, 1))", &mut env)
+        );
+        assert_eq!(
+            "y: Variable Is Not Defined",
+            evaluate_prefix_expression_in_env("y", &mut env)
         );
 
+        env.clear();
         assert_eq!(
-            Value::IntV { i_v: 10 },
-            interp_expr(&BuiltinFn {
-                name: Builtin::Choose,
-                args: vec![Integer { i: 5 }, Integer { i: 3 }]
-            })
+            "x: Variable Is Not Defined",
+            evaluate_prefix_expression_in_env("x", &mut env)
         );
     }
 
     #[test]
-    fn log_bulitins() {
+    fn user_defined_functions() {
+        let mut env = Env::new();
+        evaluate_prefix_expression_in_env(
+            "defn(hyp, (a, b), sqrt(add(pow(a, 2), pow(b, 2))))",
+            &mut env,
+        );
         assert_eq!(
-            Value::FloatV {
-                f_v: f64::ln(0.33) as f64
-            },
-            interp_expr(&BuiltinFn {
-                name: Builtin::Ln,
-                args: vec![Float { f: 0.33 }]
-            })
+            "5",
+            evaluate_prefix_expression_in_env("hyp(3, 4)", &mut env)
         );
 
+        // Closures keep the local scope they were made in
+        evaluate_prefix_expression_in_env(
+            "set(triple, let(k, 3, lambda(x, mult(x, k))))",
+            &mut env,
+        );
         assert_eq!(
-            Value::FloatV {
-                f_v: f64::log2(0.33) as f64
-            },
-            interp_expr(&BuiltinFn {
-                name: Builtin::Log2,
-                args: vec![Float { f: 0.33 }]
-            })
+            "6",
+            evaluate_prefix_expression_in_env("triple(2)", &mut env)
         );
 
+        // Session functions are looked up when called, so they can be defined in any order
+        evaluate_prefix_expression_in_env("defn(outer, (x), inner(x))", &mut env);
+        evaluate_prefix_expression_in_env("defn(inner, (x), add(x, 1))", &mut env);
+        assert_eq!("2", evaluate_prefix_expression_in_env("outer(1)", &mut env));
+
         assert_eq!(
-            Value::FloatV {
-                f_v: f64::log10(0.33) as f64
-            },
-            interp_expr(&BuiltinFn {
-                name: Builtin::Log10,
-                args: vec![Float { f: 0.33 }]
-            })
+            "hyp: Expected 2 Args, Found 1",
+            evaluate_prefix_expression_in_env("hyp(3)", &mut env)
+        );
+        assert_eq!(
+            "sqrt: Arg 1 Must Be a Number",
+            evaluate_prefix_expression_in_env("sqrt(triple)", &mut env)
         );
     }
 
     #[test]
-    fn pow_int_int() {
+    fn recursion_is_limited() {
+        let mut env = Env::new();
+        evaluate_prefix_expression_in_env("defn(forever, (n), forever(add(n, 1)))", &mut env);
         assert_eq!(
-            Value::IntV { i_v: 8 },
-            interp_expr(&BuiltinFn {
-                name: Builtin::Pow,
-                args: vec![Integer { i: 2 }, Integer { i: 3 }]
-            })
+            "forever: Exceeded the Call Depth Limit of 1000",
+            evaluate_prefix_expression_in_env("forever(0)", &mut env)
         );
+        // The failed call does not leave the depth behind
+        assert_eq!(0, env.call_depth());
     }
 
     #[test]
-    fn pow_int_float() {
+    fn conditionals_and_comparisons() {
+        assert_eq!("true", evaluate_prefix_expression("lt(div(1, 3), 0.34)"));
+        assert_eq!("false", evaluate_prefix_expression("eq(div(1, 3), 0.3)"));
+        assert_eq!("true", evaluate_prefix_expression("ne(true, false)"));
+        assert_eq!("true", evaluate_prefix_expression("eq(mult(i, i), -1)"));
+
+        // The second argument is never evaluated, so its error does not surface
         assert_eq!(
-            Value::FloatV { f_v: 8.0 },
-            interp_expr(&BuiltinFn {
-                name: Builtin::Pow,
-                args: vec![Integer { i: 2 }, Float { f: 3.0 }]
-            })
+            "false",
+            evaluate_prefix_expression("and(false, factorial(-1))")
+        );
+        assert_eq!(
+            "true",
+            evaluate_prefix_expression("or(true, factorial(-1))")
+        );
+        assert_eq!(
+            "if: Arg 1 Must Be a Boolean",
+            evaluate_prefix_expression("if(1, 2, 3)")
+        );
+        assert_eq!(
+            "lt: Arg 2 Must Be a Real Number",
+            evaluate_prefix_expression("lt(1, i)")
+        );
+
+        let mut env = Env::new();
+        evaluate_prefix_expression_in_env(
+            "defn(fib, (n), if(le(n, 1), n, add(fib(sub(n, 1)), fib(sub(n, 2)))))",
+            &mut env,
         );
+        assert_eq!("55", evaluate_prefix_expression_in_env("fib(10)", &mut env));
     }
 
     #[test]
-    fn pow_float_int() {
+    fn lists_and_higher_order_functions() {
+        assert_eq!("[1, 2, 3]", evaluate_prefix_expression("list(1, 2, 3)"));
+        assert_eq!("[]", evaluate_prefix_expression("list()"));
+        assert_eq!("[0, 2, 4]", evaluate_prefix_expression("range(0, 6, 2)"));
+        assert_eq!("[3, 2, 1]", evaluate_prefix_expression("range(3, 0, -1)"));
+        assert_eq!("55", evaluate_prefix_expression("sum(range(1, 11))"));
+        assert_eq!("120", evaluate_prefix_expression("product(range(1, 6))"));
+        assert_eq!("0", evaluate_prefix_expression("sum(list())"));
+        assert_eq!("3", evaluate_prefix_expression("len(range(3))"));
         assert_eq!(
-            Value::FloatV { f_v: 8.0 },
-            interp_expr(&BuiltinFn {
-                name: Builtin::Pow,
-                args: vec![Float { f: 2.0 }, Integer { i: 3 }]
-            })
+            "[1, 4, 9]",
+            evaluate_prefix_expression("map(lambda(x, mult(x, x)), list(1, 2, 3))")
+        );
+        assert_eq!(
+            "[2, 4]",
+            evaluate_prefix_expression("filter(lambda(x, eq(mod(x, 2), 0)), range(1, 5))")
+        );
+        assert_eq!(
+            "24",
+            evaluate_prefix_expression("reduce(lambda((a, b), mult(a, b)), range(1, 5))")
+        );
+        assert_eq!(
+            "10",
+            evaluate_prefix_expression("reduce(lambda((a, b), add(a, b)), list(), 10)")
+        );
+        assert_eq!(
+            "true",
+            evaluate_prefix_expression("eq(list(1, 2), list(1, 2.0))")
+        );
+        assert_eq!(
+            "range: Arg 3 Must Not Be Zero",
+            evaluate_prefix_expression("range(0, 5, 0)")
         );
     }
 
     #[test]
-    fn pow_float_float() {
+    fn matrices() {
         assert_eq!(
-            Value::FloatV { f_v: 8.0 },
-            interp_expr(&BuiltinFn {
-                name: Builtin::Pow,
-                args: vec![Float { f: 2.0 }, Float { f: 3.0 }]
-            })
+            "[1  -2]\n[3  40]",
+            evaluate_prefix_expression("matrix(list(1, -2), list(3, 40))")
+        );
+        assert_eq!(
+            "[1  3]\n[2  4]",
+            evaluate_prefix_expression("transpose(matrix(list(1, 2), list(3, 4)))")
+        );
+        assert_eq!("[1  0]\n[0  1]", evaluate_prefix_expression("identity(2)"));
+        assert_eq!(
+            "-2",
+            evaluate_prefix_expression("round(det(matrix(list(1, 2), list(3, 4))))")
+        );
+        assert_eq!(
+            "[19  22]\n[43  50]",
+            evaluate_prefix_expression(
+                "matmul(matrix(list(1, 2), list(3, 4)), matrix(list(5, 6), list(7, 8)))"
+            )
+        );
+        assert_eq!(
+            "[5, 11]",
+            evaluate_prefix_expression("matmul(matrix(list(1, 2), list(3, 4)), list(1, 2))")
+        );
+        assert_eq!(
+            "[1, 3]",
+            evaluate_prefix_expression(
+                "map(lambda(x, round(x)), solve(matrix(list(2, 1), list(1, 3)), list(5, 10)))"
+            )
+        );
+        assert_eq!(
+            "[0.5     0]\n[  0  0.25]",
+            evaluate_prefix_expression("inverse(matrix(list(2, 0), list(0, 4)))")
+        );
+        assert_eq!(
+            "1",
+            evaluate_prefix_expression("rank(matrix(list(1, 2), list(2, 4)))")
+        );
+        assert_eq!(
+            "[3, 1]",
+            evaluate_prefix_expression("eigenvalues(matrix(list(2, 1), list(1, 2)))")
+        );
+        assert_eq!(
+            "[i, -i]",
+            evaluate_prefix_expression("eigenvalues(matrix(list(0, -1), list(1, 0)))")
+        );
+        assert_eq!(
+            "inverse: Arg 1 Must Not Be Singular",
+            evaluate_prefix_expression("inverse(matrix(list(1, 2), list(2, 4)))")
+        );
+        assert_eq!(
+            "det: Arg 1 Must Be a Square Matrix",
+            evaluate_prefix_expression("det(matrix(list(1, 2)))")
+        );
+        assert_eq!(
+            "matrix: Arg 2 Must Be as Long as Arg 1",
+            evaluate_prefix_expression("matrix(list(1, 2), list(3))")
         );
     }
 
     #[test]
-    fn sqrt_int_int() {
+    fn list_and_matrix_arithmetic() {
         assert_eq!(
-            Value::FloatV { f_v: 2.0 },
-            interp_expr(&BuiltinFn {
-                name: Builtin::Sqrt,
-                args: vec![Integer { i: 4 }]
-            })
+            "[4, 7/2]",
+            evaluate_prefix_expression("add(list(1, 2), list(3, div(3, 2)))")
+        );
+        assert_eq!(
+            "32",
+            evaluate_prefix_expression("mult(list(1, 2, 3), list(4, 5, 6))")
+        );
+        assert_eq!("[3, 6]", evaluate_prefix_expression("mult(3, list(1, 2))"));
+        assert_eq!(
+            "[5, 11]",
+            evaluate_prefix_expression("mult(matrix(list(1, 2), list(3, 4)), list(1, 2))")
+        );
+        assert_eq!(
+            "[0  2]\n[3  3]",
+            evaluate_prefix_expression("sub(matrix(list(1, 2), list(3, 4)), identity(2))")
+        );
+        assert_eq!(
+            "[-3, 6, -3]",
+            evaluate_prefix_expression("cross(list(1, 2, 3), list(4, 5, 6))")
+        );
+        assert_eq!("2", evaluate_prefix_expression("get(list(1, 2), 1)"));
+        assert_eq!(
+            "add: Arg 2 Must Be the Same Size as Arg 1",
+            evaluate_prefix_expression("add(identity(2), 1)")
+        );
+        assert_eq!(
+            "cross: Arg 2 Must Be a List of up to 3 Real Numbers",
+            evaluate_prefix_expression("cross(list(1, 2), list(1, i))")
+        );
+        assert_eq!(
+            "get: Arg 2 Must Not Be Negative",
+            evaluate_prefix_expression("get(list(1, 2), -1)")
         );
     }
 
     #[test]
-    fn sqrt_float() {
+    fn variadic_arithmetic() {
+        assert_eq!("6", evaluate_prefix_expression("add(1, 2, 3)"));
+        assert_eq!("24", evaluate_prefix_expression("mult(2, 3, 4)"));
+        assert_eq!("9", evaluate_prefix_expression("max(list(4, 9, 2))"));
+        assert_eq!("-1", evaluate_prefix_expression("min(3, -1, 2)"));
         assert_eq!(
-            Value::FloatV { f_v: 2.0 },
-            interp_expr(&BuiltinFn {
-                name: Builtin::Sqrt,
-                args: vec![Float { f: 4.0 }]
-            })
+            "max: Arg 1 Must Not Be Empty",
+            evaluate_prefix_expression("max(list())")
+        );
+        assert_eq!(
+            "add: Arg 2 Must Be a Number",
+            evaluate_prefix_expression("add(1, true, 3)")
+        );
+    }
+
+    #[test]
+    fn angle_modes() {
+        let degrees = EvalConfig {
+            angle_mode: AngleMode::Degrees,
+            ..Default::default()
+        };
+        let eval = |raw: &str| -> String {
+            return evaluate_prefix_expression_with_config(raw, &mut Env::new(), &degrees);
+        };
+        assert_eq!("0.5", eval("sin(30)"));
+        assert_eq!("0", eval("sin(180)"));
+        assert_eq!("-1", eval("cos(540)"));
+        assert_eq!("1", eval("tan(45)"));
+        assert_eq!("-1", eval("tan(-405)"));
+        for right_angle in ["tan(90)", "tan(270)", "tan(-90)", "tan(450)"] {
+            assert_eq!(
+                "tan: Arg 1 Must Not Be an Odd Multiple of a Right Angle",
+                eval(right_angle)
+            );
+        }
+        assert_eq!("30", eval("arcsin(0.5)"));
+        assert_eq!("135", eval("arccos(div(-1, sqrt(2)))"));
+        assert_eq!("-60", eval("arctan(sub(0, sqrt(3)))"));
+        assert_eq!(
+            "true",
+            eval("lt(abs(sub(sin(deg(1)), 0.8414709848078965)), 1e-15)")
+        );
+
+        let gradians = EvalConfig {
+            angle_mode: AngleMode::Gradians,
+            ..Default::default()
+        };
+        assert_eq!(
+            "50",
+            crate::expression_util::calc_expr_with_config("arctan(1)", &gradians)
+        );
+
+        // deg and rad convert the same way whatever the mode
+        assert_eq!("180", eval("deg(pi)"));
+        assert_eq!("true", eval("eq(rad(180), pi)"));
+        assert_eq!(
+            "0.7853981633974483",
+            crate::expression_util::calc_expr("arctan(1)")
         );
     }
 }
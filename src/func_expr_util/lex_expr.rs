@@ -0,0 +1,323 @@
+use num_bigint::BigInt;
+
+use super::expr_types::{ParseError, Span, Syntax};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenKind {
+    Integer(i64),
+    BigInteger(BigInt),
+    Float(f64),
+    /// Numbers followed by an i, like `2i`, the coefficient of the imaginary unit
+    Imaginary(f64),
+    /// Names of variables, functions, builtins and the `true`/`false`/`i` literals
+    Word(String),
+    /// Builtin aliases like `+` and `<=`, before an argument list or between operands in infix
+    Operator(&'static str),
+    LeftParen,
+    RightParen,
+    /// Lists in infix, like `[1, 2]`
+    LeftBracket,
+    RightBracket,
+    Comma,
+    End,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
+}
+
+/// Longest first, so `<=` is not read as `<` then `=`.
+/// `->` only separates the parameters and body of an infix function
+const OPERATORS: [&str; 15] = [
+    "==", "!=", "<=", ">=", "->", "<", ">", "+", "-", "*", "/", "%", "^", "?", "#",
+];
+
+/// Splits an expression into tokens, the last one is always `TokenKind::End`.
+/// Prefix syntaxes read `-1` as one number, infix reads it as minus then 1
+pub fn tokenize(source: &str, syntax: Syntax) -> Result<Vec<Token>, ParseError> {
+    let infix: bool = syntax == Syntax::Infix;
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut start: usize = 0;
+    while let Some(c) = source[start..].chars().next() {
+        let rest: &str = &source[start..];
+        let (kind, len): (TokenKind, usize) = if c.is_whitespace() {
+            start += c.len_utf8();
+            continue;
+        } else if c == '(' {
+            (TokenKind::LeftParen, 1)
+        } else if c == ')' {
+            (TokenKind::RightParen, 1)
+        } else if c == '[' {
+            (TokenKind::LeftBracket, 1)
+        } else if c == ']' {
+            (TokenKind::RightBracket, 1)
+        } else if c == ',' {
+            (TokenKind::Comma, 1)
+        } else if starts_number(rest, !infix) {
+            lex_number(source, start, infix)?
+        } else if is_word_start(c) {
+            let len: usize = word_len(rest);
+            (TokenKind::Word(rest[..len].to_string()), len)
+        } else if !infix && (c == '-' || c == '+') && rest[1..].starts_with(is_word_start) {
+            lex_signed_word(source, start)?
+        } else if let Some(operator) = OPERATORS
+            .iter()
+            .find(|op| rest.starts_with(**op) && (infix || **op != "->"))
+        {
+            (TokenKind::Operator(operator), operator.len())
+        } else {
+            return Err(ParseError::at(
+                &format!("Unexpected Character \"{}\"", c),
+                source,
+                Span {
+                    start,
+                    end: start + c.len_utf8(),
+                },
+            ));
+        };
+        tokens.push(Token {
+            kind,
+            span: Span {
+                start,
+                end: start + len,
+            },
+        });
+        start += len;
+    }
+    tokens.push(Token {
+        kind: TokenKind::End,
+        span: Span {
+            start: source.len(),
+            end: source.len(),
+        },
+    });
+    return Ok(tokens);
+}
+
+fn is_word_start(c: char) -> bool {
+    return c.is_alphabetic() || c == '_';
+}
+
+fn is_word_char(c: char) -> bool {
+    return c.is_alphanumeric() || c == '_';
+}
+
+fn word_len(text: &str) -> usize {
+    return text.find(|c: char| !is_word_char(c)).unwrap_or(text.len());
+}
+
+/// A digit, or a decimal point or allowed sign followed by one
+fn starts_number(text: &str, signed: bool) -> bool {
+    let unsigned: &str = match signed {
+        true => text.strip_prefix(['+', '-']).unwrap_or(text),
+        false => text,
+    };
+    let digits: &str = unsigned.strip_prefix('.').unwrap_or(unsigned);
+    return digits.starts_with(|c: char| c.is_ascii_digit());
+}
+
+/// Integers with the prefixes parse_int accepts, decimals with an optional exponent,
+/// and either followed by an i for imaginary numbers.
+/// Infix numbers can run straight into a name, as in `2x`
+fn lex_number(source: &str, start: usize, infix: bool) -> Result<(TokenKind, usize), ParseError> {
+    let rest: &str = &source[start..];
+    let bytes: &[u8] = rest.as_bytes();
+    let mut len: usize = if bytes[0] == b'+' || bytes[0] == b'-' {
+        1
+    } else {
+        0
+    };
+    let is_radix: bool = matches!(
+        rest.get(len..len + 2),
+        Some("0x" | "0X" | "0o" | "0O" | "0b" | "0B")
+    );
+    if is_radix {
+        len += 2 + word_len(&rest[len + 2..]);
+    } else {
+        len += digits_len(&rest[len..]);
+        if rest[len..].starts_with('.') {
+            len += 1 + digits_len(&rest[len + 1..]);
+        }
+        if let Some(exponent) = rest[len..].strip_prefix(['e', 'E']) {
+            let unsigned: &str = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+            if unsigned.starts_with(|c: char| c.is_ascii_digit()) {
+                len += 1 + (exponent.len() - unsigned.len()) + digits_len(unsigned);
+            }
+        }
+    }
+
+    let text: &str = &rest[..len];
+    let after: &str = &rest[len..];
+    let is_imaginary: bool =
+        !is_radix && after.starts_with('i') && !after[1..].starts_with(is_word_char);
+    let invalid = |len: usize| {
+        return ParseError::at(
+            &format!("Invalid Number \"{}\"", &rest[..len]),
+            source,
+            Span {
+                start,
+                end: start + len,
+            },
+        );
+    };
+    if is_imaginary {
+        return match text.replace('_', "").parse::<f64>() {
+            Ok(im) => Ok((TokenKind::Imaginary(im), len + 1)),
+            Err(_) => Err(invalid(len + 1)),
+        };
+    }
+    // Keeps things like 2x from reading as a number then a name
+    if !infix && after.starts_with(is_word_char) {
+        return Err(invalid(len + word_len(after)));
+    }
+    if let Ok(i) = parse_int::parse::<i64>(text) {
+        return Ok((TokenKind::Integer(i), len));
+    } else if let Some(b) = parse_big_int(text) {
+        return Ok((TokenKind::BigInteger(b), len));
+    } else if let Ok(f) = text.replace('_', "").parse::<f64>() {
+        return Ok((TokenKind::Float(f), len));
+    }
+    return Err(invalid(len));
+}
+
+fn digits_len(text: &str) -> usize {
+    return text
+        .find(|c: char| !(c.is_ascii_digit() || c == '_'))
+        .unwrap_or(text.len());
+}
+
+/// `-i`, `+i` and the signed special floats like `-inf`
+fn lex_signed_word(source: &str, start: usize) -> Result<(TokenKind, usize), ParseError> {
+    let rest: &str = &source[start..];
+    let len: usize = 1 + word_len(&rest[1..]);
+    let sign: f64 = if rest.starts_with('-') { -1.0 } else { 1.0 };
+    let word: &str = &rest[1..len];
+    if word == "i" {
+        return Ok((TokenKind::Imaginary(sign), len));
+    } else if let Some(f) = special_float(word) {
+        return Ok((TokenKind::Float(sign * f), len));
+    }
+    return Err(ParseError::at(
+        &format!("Expected a Number After \"{}\"", &rest[..1]),
+        source,
+        Span {
+            start,
+            end: start + len,
+        },
+    ));
+}
+
+/// The words that are floats, `NaN` as well as `nan` since that is how answers show it.
+/// Other spellings Rust would parse, like `infinity`, are names
+pub fn special_float(word: &str) -> Option<f64> {
+    match word {
+        "inf" => Some(f64::INFINITY),
+        "nan" | "NaN" => Some(f64::NAN),
+        _ => None,
+    }
+}
+
+/// Integer literals too large for an i64, with the same prefixes parse_int accepts
+fn parse_big_int(int_str: &str) -> Option<BigInt> {
+    let (negative, unsigned) = match int_str.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, int_str.strip_prefix('+').unwrap_or(int_str)),
+    };
+    let (radix, digits) = match unsigned.get(..2) {
+        Some("0x") | Some("0X") => (16, &unsigned[2..]),
+        Some("0o") | Some("0O") => (8, &unsigned[2..]),
+        Some("0b") | Some("0B") => (2, &unsigned[2..]),
+        _ => (10, unsigned),
+    };
+    let digits: String = digits.chars().filter(|c| *c != '_').collect();
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+    let magnitude: BigInt = BigInt::parse_bytes(digits.as_bytes(), radix)?;
+    return Some(if negative { -magnitude } else { magnitude });
+}
+
+#[cfg(test)]
+mod lex_tests {
+    use super::*;
+    use TokenKind::*;
+
+    fn kinds(source: &str) -> Vec<TokenKind> {
+        return tokenize(source, Syntax::Call)
+            .unwrap()
+            .into_iter()
+            .map(|token| token.kind)
+            .collect();
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(vec![Integer(-12), End], kinds("-12"));
+        assert_eq!(vec![Integer(255), End], kinds("0xFF"));
+        assert_eq!(vec![Float(0.5), End], kinds(".5"));
+        assert_eq!(vec![Float(1.5e3), End], kinds("1.5e3"));
+        assert_eq!(vec![Imaginary(2.5), End], kinds("2.5i"));
+        assert_eq!(vec![Imaginary(-1.0), End], kinds("-i"));
+        assert_eq!(vec![Float(f64::NEG_INFINITY), End], kinds("-inf"));
+        assert!(tokenize("-Infinity", Syntax::Call).is_err());
+        assert!(tokenize("2x", Syntax::Call).is_err());
+    }
+
+    #[test]
+    fn spans_are_byte_offsets() {
+        let tokens: Vec<Token> = tokenize("π(é, 1)", Syntax::Call).unwrap();
+        assert_eq!(Word("π".to_string()), tokens[0].kind);
+        assert_eq!(Span { start: 0, end: 2 }, tokens[0].span);
+        assert_eq!(Word("é".to_string()), tokens[2].kind);
+        assert_eq!(Span { start: 3, end: 5 }, tokens[2].span);
+        assert_eq!(Span { start: 9, end: 9 }, tokens[6].span);
+    }
+
+    #[test]
+    fn operators() {
+        assert_eq!(
+            vec![
+                Operator("<="),
+                LeftParen,
+                Integer(1),
+                Comma,
+                Integer(2),
+                RightParen,
+                End
+            ],
+            kinds("<= (1, 2)")
+        );
+        assert_eq!(vec![Operator("-"), LeftParen, End], kinds("-("));
+    }
+
+    #[test]
+    fn infix_numbers() {
+        let kinds = |source: &str| -> Vec<TokenKind> {
+            return tokenize(source, Syntax::Infix)
+                .unwrap()
+                .into_iter()
+                .map(|token| token.kind)
+                .collect();
+        };
+        assert_eq!(
+            vec![Integer(3), Operator("-"), Integer(1), End],
+            kinds("3-1")
+        );
+        assert_eq!(
+            vec![
+                Integer(2),
+                Word("x".to_string()),
+                Operator("^"),
+                Imaginary(2.0),
+                End
+            ],
+            kinds("2x^2i")
+        );
+        assert_eq!(
+            vec![LeftBracket, Integer(1), RightBracket, End],
+            kinds("[1]")
+        );
+    }
+}
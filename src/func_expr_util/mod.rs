@@ -1,16 +1,67 @@
+pub mod constants;
+pub mod diff_expr;
 pub mod expr_types;
+pub mod integrate_expr;
 pub mod interp_expr;
+pub mod lex_expr;
 pub mod parse_expr;
+pub mod print_expr;
+pub mod simplify_expr;
+pub mod solve_expr;
+pub mod trace_expr;
 
-use interp_expr::interp_expr;
+use expr_types::{Env, EvalConfig, Expr, Syntax};
+use interp_expr::interp_expr_with_env;
 use parse_expr::parse_expr;
-use slint::SharedString;
+use print_expr::print_infix;
+use trace_expr::trace_expr;
 
-pub fn evaluate_prefix_expression(raw_string: &str) -> SharedString {
+pub fn evaluate_prefix_expression(raw_string: &str) -> String {
+    return evaluate_prefix_expression_in_env(raw_string, &mut Env::new());
+}
+
+/// Like `evaluate_prefix_expression`, but variables from `set` stay in `env` for later calls
+pub fn evaluate_prefix_expression_in_env(raw_string: &str, env: &mut Env) -> String {
+    return evaluate_prefix_expression_with_config(raw_string, env, &EvalConfig::default());
+}
+
+/// Like `evaluate_prefix_expression_in_env`, with settings such as the angle mode
+pub fn evaluate_prefix_expression_with_config(
+    raw_string: &str,
+    env: &mut Env,
+    config: &EvalConfig,
+) -> String {
+    match try_evaluate_prefix_expression(raw_string, env, config) {
+        Ok(answer) | Err(answer) => answer,
+    }
+}
+
+/// Like `evaluate_prefix_expression_with_config`, keeping error messages apart from answers
+pub fn try_evaluate_prefix_expression(
+    raw_string: &str,
+    env: &mut Env,
+    config: &EvalConfig,
+) -> Result<String, String> {
+    let expr: Expr = parse_expr(raw_string).map_err(|e| e.to_string())?;
+    match interp_expr_with_env(&expr, env, config) {
+        Ok(value) => Ok(value.to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Each step of evaluating the prefix expression on its own line, in a copy of `env`
+/// so showing the steps never changes session variables
+pub fn trace_prefix_expression(raw_string: &str, env: &Env, config: &EvalConfig) -> String {
     match parse_expr(raw_string) {
-        Ok(expr) => interp_expr(&expr).into(),
-        Err(e) => e.message.into(),
+        Ok(expr) => trace_expr(&expr, &mut env.clone(), config, Syntax::Call).to_string(),
+        Err(e) => e.to_string(),
     }
 }
 
-pub mod func_expr_util {}
+/// The prefix expression written in infix notation, or why it could not be parsed
+pub fn prefix_as_infix(raw_string: &str) -> String {
+    match parse_expr(raw_string) {
+        Ok(expr) => print_infix(&expr),
+        Err(e) => e.to_string(),
+    }
+}
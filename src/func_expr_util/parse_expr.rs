@@ -1,24 +1,538 @@
-use super::expr_types::*;
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
 
-/**
- *
- */
+use super::{
+    expr_types::*,
+    lex_expr::{special_float, tokenize, Token, TokenKind},
+};
+
+/// Parses either syntax, an expression starting with `(` is read as an s-expression
 pub fn parse_expr(expr_str: &str) -> Result<Expr, ParseError> {
-    if let Ok(int) = parse_int::parse::<i64>(expr_str.trim()) {
-        return Ok(Expr::Integer { i: int });
-    } else if let Ok(float) = expr_str.trim().parse::<f64>() {
-        return Ok(Expr::Float { f: float });
-    } else if let Ok(func) = parse_fn(expr_str) {
-        return Ok(func);
-    } else {
-        return Err(format!(
-            "Expression String Does not match any pattern in grammar {}",
-            expr_str
-        )
-        .into());
+    let tokens: Vec<Token> = tokenize(expr_str, Syntax::Call)?;
+    let syntax: Syntax = match tokens[0].kind {
+        TokenKind::LeftParen => Syntax::SExpr,
+        _ => Syntax::Call,
+    };
+    return parse_tokens(expr_str, tokens, syntax);
+}
+
+pub fn parse_expr_with_syntax(expr_str: &str, syntax: Syntax) -> Result<Expr, ParseError> {
+    return parse_tokens(expr_str, tokenize(expr_str, syntax)?, syntax);
+}
+
+fn parse_tokens(source: &str, tokens: Vec<Token>, syntax: Syntax) -> Result<Expr, ParseError> {
+    let mut parser: Parser = Parser {
+        source,
+        tokens,
+        pos: 0,
+        syntax,
+        depth: 0,
+    };
+    let expr: Expr = parser.expr()?;
+    let trailing: Token = parser.next();
+    if trailing.kind != TokenKind::End {
+        return Err(parser.error(
+            &format!(
+                "Unexpected {} After the Expression",
+                parser.describe(&trailing)
+            ),
+            &trailing,
+        ));
+    }
+    return Ok(expr);
+}
+
+/// How deep expressions may nest before parsing stops, well short of overflowing the stack
+const MAX_NESTING: usize = 128;
+
+/// Recursive descent over the tokens, `pos` is the next one to read
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+    syntax: Syntax,
+    /// Expressions currently being parsed inside one another
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> &TokenKind {
+        return &self.tokens[self.pos].kind;
+    }
+
+    /// Keeps returning the `End` token once the input runs out
+    fn next(&mut self) -> Token {
+        let token: Token = self.tokens[self.pos].clone();
+        if token.kind != TokenKind::End {
+            self.pos += 1;
+        }
+        return token;
+    }
+
+    fn describe(&self, token: &Token) -> String {
+        match token.kind {
+            TokenKind::End => "the End of the Expression".to_string(),
+            _ => format!("\"{}\"", &self.source[token.span.start..token.span.end]),
+        }
+    }
+
+    fn error(&self, message: &str, token: &Token) -> ParseError {
+        return ParseError::at(message, self.source, token.span);
+    }
+
+    fn expect(&mut self, kind: TokenKind, expected: &str) -> Result<(), ParseError> {
+        let token: Token = self.next();
+        if token.kind != kind {
+            return Err(self.error(
+                &format!("Expected {}, Found {}", expected, self.describe(&token)),
+                &token,
+            ));
+        }
+        return Ok(());
+    }
+
+    /// Commas between arguments, except in s-expressions where whitespace is enough
+    fn separator(&mut self) -> Result<(), ParseError> {
+        match self.syntax {
+            Syntax::Call | Syntax::Infix => self.expect(TokenKind::Comma, "\",\""),
+            Syntax::SExpr => Ok(()),
+        }
+    }
+
+    /// Runs `parse` one level deeper, failing at the next token once nesting gets too deep
+    fn nested<T>(
+        &mut self,
+        parse: fn(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        if self.depth >= MAX_NESTING {
+            let token: Token = self.tokens[self.pos].clone();
+            return Err(self.error("Nesting Too Deep", &token));
+        }
+        self.depth += 1;
+        let result: Result<T, ParseError> = parse(self);
+        self.depth -= 1;
+        return result;
+    }
+
+    fn expr(&mut self) -> Result<Expr, ParseError> {
+        return self.nested(Parser::unnested_expr);
+    }
+
+    fn unnested_expr(&mut self) -> Result<Expr, ParseError> {
+        if self.syntax == Syntax::Infix {
+            return self.function();
+        }
+        let token: Token = self.next();
+        if let Some(literal) = literal_to_expr(&token.kind) {
+            return Ok(literal);
+        }
+        let is_call: bool = self.syntax == Syntax::Call && *self.peek() == TokenKind::LeftParen;
+        match token.kind {
+            TokenKind::Word(ref word) if is_call => {
+                self.next();
+                self.application(word)
+            }
+            TokenKind::Word(word) => Ok(word_to_expr(word)),
+            TokenKind::Operator(operator) if is_call => {
+                self.next();
+                self.application(operator)
+            }
+            TokenKind::LeftParen if self.syntax == Syntax::SExpr => {
+                let head: Token = self.next();
+                match head.kind {
+                    TokenKind::Word(ref name) => self.application(name),
+                    TokenKind::Operator(operator) => self.application(operator),
+                    _ => Err(self.error(
+                        &format!("Expected a Function Name, Found {}", self.describe(&head)),
+                        &head,
+                    )),
+                }
+            }
+            TokenKind::Operator(operator) => Err(self.error(
+                &format!("\"{}\" Can Only Be Used as a Function Name", operator),
+                &token,
+            )),
+            _ => Err(self.error(
+                &format!("Expected an Expression, Found {}", self.describe(&token)),
+                &token,
+            )),
+        }
+    }
+
+    /// Everything after the function name through the closing `)`.
+    /// Parameter lists and names are not expressions, so the special forms read their own
+    fn application(&mut self, name: &str) -> Result<Expr, ParseError> {
+        match name {
+            "defn" => {
+                let name: String = self.name("defn: First Arg Must Be a Function Name")?;
+                self.separator()?;
+                let params: Vec<String> = self.params()?;
+                self.separator()?;
+                let body: Expr = self.expr()?;
+                self.expect(TokenKind::RightParen, "\")\"")?;
+                return Ok(Expr::Defn {
+                    name,
+                    params,
+                    body: Box::new(body),
+                });
+            }
+            "lambda" => {
+                let params: Vec<String> = self.params()?;
+                self.separator()?;
+                let body: Expr = self.expr()?;
+                self.expect(TokenKind::RightParen, "\")\"")?;
+                return Ok(Expr::Lambda {
+                    params,
+                    body: Box::new(body),
+                });
+            }
+            "let" => {
+                let name: String = self.name("let: First Arg Must Be a Variable Name")?;
+                self.separator()?;
+                let value: Expr = self.expr()?;
+                self.separator()?;
+                let body: Expr = self.expr()?;
+                self.expect(TokenKind::RightParen, "\")\"")?;
+                return Ok(Expr::Let {
+                    name,
+                    value: Box::new(value),
+                    body: Box::new(body),
+                });
+            }
+            "set" => {
+                let name: String = self.name("set: First Arg Must Be a Variable Name")?;
+                self.separator()?;
+                let value: Expr = self.expr()?;
+                self.expect(TokenKind::RightParen, "\")\"")?;
+                return Ok(Expr::Set {
+                    name,
+                    value: Box::new(value),
+                });
+            }
+            _ => {
+                let args: Vec<Expr> = self.list(Parser::expr, TokenKind::RightParen)?;
+                return Ok(builtin_or_call(name, args));
+            }
+        }
+    }
+
+    /// Items through the `close` token, with the opening one already read
+    fn list<T>(
+        &mut self,
+        item: fn(&mut Self) -> Result<T, ParseError>,
+        close: TokenKind,
+    ) -> Result<Vec<T>, ParseError> {
+        let mut items: Vec<T> = Vec::new();
+        if *self.peek() == close {
+            self.next();
+            return Ok(items);
+        }
+        loop {
+            items.push(item(self)?);
+            if self.syntax == Syntax::SExpr {
+                if *self.peek() == close {
+                    self.next();
+                    return Ok(items);
+                }
+                continue;
+            }
+            let token: Token = self.next();
+            if token.kind == close {
+                return Ok(items);
+            } else if token.kind != TokenKind::Comma {
+                let close_text: &str = if close == TokenKind::RightBracket {
+                    "]"
+                } else {
+                    ")"
+                };
+                return Err(self.error(
+                    &format!(
+                        "Expected \",\" or \"{}\", Found {}",
+                        close_text,
+                        self.describe(&token)
+                    ),
+                    &token,
+                ));
+            }
+        }
+    }
+
+    /// A word to bind, literal words like `nan` and `true` would never read as the binding
+    fn name(&mut self, message: &str) -> Result<String, ParseError> {
+        let token: Token = self.next();
+        match token.kind {
+            TokenKind::Word(word) if matches!(word_to_expr(word.clone()), Expr::Var { .. }) => {
+                Ok(word)
+            }
+            _ => Err(self.error(message, &token)),
+        }
+    }
+
+    fn param(&mut self) -> Result<String, ParseError> {
+        return self.name("Expected a Parameter Name");
+    }
+
+    /// `(x, y)` or `(x y)`, `()`, or a single name without parentheses
+    fn params(&mut self) -> Result<Vec<String>, ParseError> {
+        if *self.peek() != TokenKind::LeftParen {
+            return Ok(vec![self.param()?]);
+        }
+        self.next();
+        return self.list(Parser::param, TokenKind::RightParen);
+    }
+
+    /// Takes the next token when it is one of `operators`
+    fn infix_operator(&mut self, operators: &[&str]) -> Option<Builtin> {
+        match self.peek() {
+            TokenKind::Operator(operator) if operators.contains(operator) => {
+                let builtin: Option<Builtin> = identifier_to_builtin(operator).ok();
+                self.next();
+                return builtin;
+            }
+            _ => return None,
+        }
+    }
+
+    /// Infix grammar, loosest first: functions, comparisons, sums, products, unary minus, powers.
+    /// A function is `x -> x^2` or `(x, y) -> x * y`, and its body reaches as far as it can
+    fn function(&mut self) -> Result<Expr, ParseError> {
+        let params: Vec<String> = match self.arrow_params_len() {
+            Some(1) => vec![self.param()?],
+            Some(_) => self.params()?,
+            None => return self.comparison(),
+        };
+        self.next();
+        return Ok(Expr::Lambda {
+            params,
+            body: Box::new(self.nested(Parser::function)?),
+        });
+    }
+
+    /// How many tokens the parameters take when a `->` follows them, looking ahead without
+    /// reading anything
+    fn arrow_params_len(&self) -> Option<usize> {
+        let len: usize = match self.peek() {
+            TokenKind::Word(_) => 1,
+            TokenKind::LeftParen => {
+                let names: usize = self.tokens[self.pos + 1..]
+                    .iter()
+                    .take_while(|token| matches!(token.kind, TokenKind::Word(_) | TokenKind::Comma))
+                    .count();
+                if self.tokens[self.pos + 1 + names].kind != TokenKind::RightParen {
+                    return None;
+                }
+                names + 2
+            }
+            _ => return None,
+        };
+        match self.tokens[self.pos + len].kind {
+            TokenKind::Operator("->") => Some(len),
+            _ => None,
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Expr, ParseError> {
+        const COMPARISONS: [&str; 6] = ["==", "!=", "<", "<=", ">", ">="];
+        let left: Expr = self.sum()?;
+        let builtin: Builtin = match self.infix_operator(&COMPARISONS) {
+            Some(builtin) => builtin,
+            None => return Ok(left),
+        };
+        let right: Expr = self.sum()?;
+        if matches!(self.peek(), TokenKind::Operator(operator) if COMPARISONS.contains(operator)) {
+            let token: Token = self.next();
+            return Err(self.error("Comparisons Do Not Chain, Use and(...)", &token));
+        }
+        return Ok(binary(builtin, left, right));
+    }
+
+    fn sum(&mut self) -> Result<Expr, ParseError> {
+        let mut left: Expr = self.product()?;
+        while let Some(builtin) = self.infix_operator(&["+", "-"]) {
+            left = binary(builtin, left, self.product()?);
+        }
+        return Ok(left);
+    }
+
+    /// A name or `(` straight after an operand multiplies, as in `2x` and `3(x + 1)`,
+    /// but two names in a row are an error rather than `x * y`
+    fn product(&mut self) -> Result<Expr, ParseError> {
+        let mut left: Expr = self.unary()?;
+        loop {
+            if let Some(builtin) = self.infix_operator(&["*", "/", "%", "#"]) {
+                left = binary(builtin, left, self.unary()?);
+            } else if matches!(self.peek(), TokenKind::Word(_) | TokenKind::LeftParen) {
+                let previous: Token = self.tokens[self.pos - 1].clone();
+                if let (TokenKind::Word(_), TokenKind::Word(_)) = (&previous.kind, self.peek()) {
+                    let token: Token = self.next();
+                    return Err(self.error(
+                        &format!(
+                            "Expected an Operator Between {} and {}",
+                            self.describe(&previous),
+                            self.describe(&token)
+                        ),
+                        &token,
+                    ));
+                }
+                left = binary(Builtin::Multiply, left, self.power()?);
+            } else {
+                return Ok(left);
+            }
+        }
+    }
+
+    /// Binds looser than `^`, so `-2^2` is -4
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        if self.infix_operator(&["+"]).is_some() {
+            return self.nested(Parser::unary);
+        } else if self.infix_operator(&["-"]).is_none() {
+            return self.power();
+        }
+        // Negative numbers stay literals rather than 0 - x
+        match self.nested(Parser::unary)? {
+            Expr::Integer { i } => match i.checked_neg() {
+                Some(i) => Ok(Expr::Integer { i }),
+                None => Ok(Expr::BigInteger {
+                    b: -BigInt::from(i),
+                }),
+            },
+            Expr::BigInteger { b } => match (-&b).to_i64() {
+                Some(i) => Ok(Expr::Integer { i }),
+                None => Ok(Expr::BigInteger { b: -b }),
+            },
+            Expr::Float { f } => Ok(Expr::Float { f: -f }),
+            Expr::Imaginary { im } => Ok(Expr::Imaginary { im: -im }),
+            operand => Ok(binary(Builtin::Subtract, Expr::Integer { i: 0 }, operand)),
+        }
+    }
+
+    /// Right associative, and the exponent can have its own minus, as in `2^-1`
+    fn power(&mut self) -> Result<Expr, ParseError> {
+        let base: Expr = self.element()?;
+        if self.infix_operator(&["^"]).is_some() {
+            return Ok(binary(Builtin::Pow, base, self.nested(Parser::unary)?));
+        }
+        return Ok(base);
+    }
+
+    /// Indexing binds tightest, `[1, 2, 3]?0` is the first element
+    fn element(&mut self) -> Result<Expr, ParseError> {
+        let mut list: Expr = self.primary()?;
+        while self.infix_operator(&["?"]).is_some() {
+            list = binary(Builtin::Get, list, self.primary()?);
+        }
+        return Ok(list);
+    }
+
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        let token: Token = self.next();
+        if let Some(literal) = literal_to_expr(&token.kind) {
+            return Ok(literal);
+        }
+        match token.kind {
+            // The integral and derivative at a point, as the infix calculator has always had them
+            TokenKind::Word(ref word) if *self.peek() == TokenKind::LeftParen => {
+                self.next();
+                match word.as_str() {
+                    "I" => Ok(binary(
+                        Builtin::Get,
+                        Expr::BuiltinFn {
+                            name: Builtin::Integrate,
+                            args: self.list(Parser::expr, TokenKind::RightParen)?,
+                        },
+                        Expr::Integer { i: 0 },
+                    )),
+                    "D" => Ok(Expr::BuiltinFn {
+                        name: Builtin::Diff,
+                        args: self.list(Parser::expr, TokenKind::RightParen)?,
+                    }),
+                    _ => self.application(word),
+                }
+            }
+            TokenKind::Word(word) => Ok(word_to_expr(word)),
+            TokenKind::LeftParen => {
+                let inner: Expr = self.nested(Parser::function)?;
+                self.expect(TokenKind::RightParen, "\")\"")?;
+                Ok(inner)
+            }
+            TokenKind::LeftBracket => {
+                let items: Vec<Expr> = self.list(Parser::expr, TokenKind::RightBracket)?;
+                let is_list = |item: &Expr| {
+                    matches!(
+                        item,
+                        Expr::BuiltinFn {
+                            name: Builtin::List,
+                            ..
+                        }
+                    )
+                };
+                if items.is_empty() || !items.iter().all(is_list) {
+                    return Ok(Expr::BuiltinFn {
+                        name: Builtin::List,
+                        args: items,
+                    });
+                }
+                // A list of lists is a matrix with the inner lists as its columns
+                Ok(Expr::BuiltinFn {
+                    name: Builtin::Transpose,
+                    args: vec![Expr::BuiltinFn {
+                        name: Builtin::Matrix,
+                        args: items,
+                    }],
+                })
+            }
+            _ => Err(self.error(
+                &format!("Expected an Expression, Found {}", self.describe(&token)),
+                &token,
+            )),
+        }
     }
 }
 
+fn literal_to_expr(kind: &TokenKind) -> Option<Expr> {
+    match kind {
+        TokenKind::Integer(i) => Some(Expr::Integer { i: *i }),
+        TokenKind::BigInteger(b) => Some(Expr::BigInteger { b: b.clone() }),
+        TokenKind::Float(f) => Some(Expr::Float { f: *f }),
+        TokenKind::Imaginary(im) => Some(Expr::Imaginary { im: *im }),
+        _ => None,
+    }
+}
+
+fn binary(name: Builtin, left: Expr, right: Expr) -> Expr {
+    return Expr::BuiltinFn {
+        name,
+        args: vec![left, right],
+    };
+}
+
+fn builtin_or_call(name: &str, args: Vec<Expr>) -> Expr {
+    if let Ok(builtin) = identifier_to_builtin(name) {
+        return Expr::BuiltinFn {
+            name: builtin,
+            args,
+        };
+    }
+    return Expr::Call {
+        func: Box::new(Expr::Var {
+            name: name.to_string(),
+        }),
+        args,
+    };
+}
+
+/// Words that are literals, `inf`, `nan` and `NaN` included, and otherwise variables
+fn word_to_expr(word: String) -> Expr {
+    if let Ok(b) = word.parse::<bool>() {
+        return Expr::Boolean { b };
+    } else if word == "i" {
+        return Expr::Imaginary { im: 1.0 };
+    } else if let Some(f) = special_float(&word) {
+        return Expr::Float { f };
+    }
+    return Expr::Var { name: word };
+}
+
 fn identifier_to_builtin(identifier: &str) -> Result<Builtin, ParseError> {
     match identifier {
         "abs" => Ok(Builtin::Abs),
@@ -33,8 +547,10 @@ fn identifier_to_builtin(identifier: &str) -> Result<Builtin, ParseError> {
         "cos" => Ok(Builtin::Cos),
         "tan" => Ok(Builtin::Tan),
         "arcsin" => Ok(Builtin::ArcSin),
-        "arcos" => Ok(Builtin::ArcCos),
+        "arccos" | "arcos" => Ok(Builtin::ArcCos),
         "arctan" => Ok(Builtin::ArcTan),
+        "deg" => Ok(Builtin::Degrees),
+        "rad" => Ok(Builtin::Radians),
         "sinh" => Ok(Builtin::Sinh),
         "cosh" => Ok(Builtin::Cosh),
         "tanh" => Ok(Builtin::Tanh),
@@ -44,7 +560,7 @@ fn identifier_to_builtin(identifier: &str) -> Result<Builtin, ParseError> {
         "ln" => Ok(Builtin::Ln),
         "log_10" => Ok(Builtin::Log10),
         "log_2" => Ok(Builtin::Log2),
-        "pow" => Ok(Builtin::Pow),
+        "pow" | "^" => Ok(Builtin::Pow),
         "max" => Ok(Builtin::Max),
         "min" => Ok(Builtin::Min),
         "rand" => Ok(Builtin::Rand),
@@ -56,82 +572,46 @@ fn identifier_to_builtin(identifier: &str) -> Result<Builtin, ParseError> {
         "div" | "/" => Ok(Builtin::Divide),
         "mod" | "%" => Ok(Builtin::Modulus),
         "sqrt" => Ok(Builtin::Sqrt),
+        "to_float" => Ok(Builtin::ToFloat),
+        "numerator" => Ok(Builtin::Numerator),
+        "denominator" => Ok(Builtin::Denominator),
+        "eq" | "==" => Ok(Builtin::Equal),
+        "ne" | "!=" => Ok(Builtin::NotEqual),
+        "lt" | "<" => Ok(Builtin::Less),
+        "le" | "<=" => Ok(Builtin::LessEqual),
+        "gt" | ">" => Ok(Builtin::Greater),
+        "ge" | ">=" => Ok(Builtin::GreaterEqual),
+        "and" => Ok(Builtin::And),
+        "or" => Ok(Builtin::Or),
+        "not" => Ok(Builtin::Not),
+        "if" => Ok(Builtin::If),
+        "list" => Ok(Builtin::List),
+        "range" => Ok(Builtin::Range),
+        "map" => Ok(Builtin::Map),
+        "filter" => Ok(Builtin::Filter),
+        "reduce" => Ok(Builtin::Reduce),
+        "sum" => Ok(Builtin::Sum),
+        "product" => Ok(Builtin::Product),
+        "len" => Ok(Builtin::Len),
+        "get" | "?" => Ok(Builtin::Get),
+        "matrix" => Ok(Builtin::Matrix),
+        "diff" => Ok(Builtin::Diff),
+        "simplify" => Ok(Builtin::Simplify),
+        "integrate" => Ok(Builtin::Integrate),
+        "root" => Ok(Builtin::Root),
+        "identity" => Ok(Builtin::Identity),
+        "transpose" => Ok(Builtin::Transpose),
+        "det" => Ok(Builtin::Determinant),
+        "inverse" => Ok(Builtin::Inverse),
+        "matmul" => Ok(Builtin::MatMul),
+        "cross" | "#" => Ok(Builtin::Cross),
+        "solve" => Ok(Builtin::Solve),
+        "rank" => Ok(Builtin::Rank),
+        "eigenvalues" => Ok(Builtin::Eigenvalues),
         _ => Err("No Matching Builtin Function".into()),
     }
 }
 
-fn args_to_vec(args: &str) -> Result<Vec<Expr>, ParseError> {
-    let mut arg_vec: Vec<Expr> = Vec::new();
-    let mut delim_vec: Vec<char> = Vec::new();
-    let mut current_arg: String = String::new();
-    for (i, c) in args.chars().enumerate() {
-        if i == 0 {
-            // First Character
-            continue;
-        } else if i == args.len() - 1 {
-            // Last Character
-            if delim_vec.is_empty() {
-                if c != ')' {
-                    return Err("Function Parameters are not closed".into());
-                } else if current_arg.is_empty() {
-                    continue;
-                } else {
-                    let expr: Expr = parse_expr(&current_arg)?;
-                    arg_vec.push(expr);
-                    current_arg.clear();
-                }
-            } else {
-                return Err("Delimiter Mismatch".into());
-            }
-        } else {
-            match c {
-                '(' => {
-                    delim_vec.push(c);
-                    current_arg.push(c);
-                }
-                ')' => {
-                    if delim_vec.is_empty() {
-                        return Err("Delimiter Mismatch".into());
-                    } else {
-                        delim_vec.pop();
-                        current_arg.push(c);
-                    }
-                }
-                ',' => {
-                    if delim_vec.len() == 0 {
-                        let expr: Expr = parse_expr(&current_arg)?;
-                        arg_vec.push(expr);
-                        current_arg.clear();
-                    } else {
-                        current_arg.push(c);
-                    }
-                }
-                ' ' | '\n' | '\t' => {
-                    continue;
-                }
-                _ => {
-                    current_arg.push(c);
-                }
-            }
-        }
-    }
-
-    return Ok(arg_vec);
-}
-
-fn parse_fn(in_string: &str) -> Result<Expr, ParseError> {
-    if let Some(pos) = in_string.find('(') {
-        return match in_string.split_at(pos) {
-            (identifier, args) => Ok(Expr::BuiltinFn {
-                name: identifier_to_builtin(identifier)?,
-                args: args_to_vec(args)?,
-            }),
-        };
-    } else {
-        return Err("No Matching Builtin Function".into());
-    }
-}
-
 #[cfg(test)]
 mod parse_tests {
     use super::*;
@@ -208,6 +688,39 @@ mod parse_tests {
         assert_eq!(Ok(Float { f: 1.0 }), parse_expr("1.0"));
     }
 
+    #[test]
+    fn special_float_parsing() {
+        assert_eq!(Ok(Float { f: f64::INFINITY }), parse_expr("inf"));
+        assert!(matches!(parse_expr("NaN"), Ok(Float { f }) if f.is_nan()));
+        // Only the spellings above, so other ones can be bound
+        for name in ["Inf", "infinity", "NAN"] {
+            assert_eq!(
+                Ok(Var {
+                    name: name.to_string()
+                }),
+                parse_expr(name)
+            );
+        }
+        assert_eq!(
+            Ok(Var {
+                name: "Inf".to_string()
+            }),
+            parse_expr("let(Inf, 3, Inf)").map(|expr| match expr {
+                Let { body, .. } => *body,
+                other => other,
+            })
+        );
+        // Literal words can't be bound
+        for source in [
+            "let(nan, 3, nan)",
+            "set(inf, 1)",
+            "lambda((x, true), x)",
+            "defn(i, (), 1)",
+        ] {
+            assert!(parse_expr(source).is_err());
+        }
+    }
+
     #[test]
     fn negate_int_parsing() {
         assert_eq!(Ok(Integer { i: -1 }), parse_expr("-1"));
@@ -217,4 +730,258 @@ mod parse_tests {
     fn negate_float_parsing() {
         assert_eq!(Ok(Float { f: -1.0 }), parse_expr("-1.0"));
     }
+
+    #[test]
+    fn imaginary_parsing() {
+        assert_eq!(Ok(Imaginary { im: 1.0 }), parse_expr("i"));
+        assert_eq!(Ok(Imaginary { im: -1.0 }), parse_expr("-i"));
+        assert_eq!(Ok(Imaginary { im: 2.5 }), parse_expr("2.5i"));
+        assert_eq!(
+            Ok(Var {
+                name: "pi".to_string()
+            }),
+            parse_expr("pi")
+        );
+    }
+
+    #[test]
+    fn binding_forms() {
+        assert_eq!(
+            Ok(Let {
+                name: "x".to_string(),
+                value: Box::new(Integer { i: 2 }),
+                body: Box::new(BuiltinFn {
+                    name: Builtin::Multiply,
+                    args: vec![
+                        Var {
+                            name: "x".to_string()
+                        },
+                        Integer { i: 3 }
+                    ]
+                })
+            }),
+            parse_expr("let(x, 2, mult(x, 3))")
+        );
+        assert_eq!(
+            Ok(Set {
+                name: "rate_2".to_string(),
+                value: Box::new(Float { f: 0.5 })
+            }),
+            parse_expr("set(rate_2, 0.5)")
+        );
+        assert!(parse_expr("set(2, 0.5)").is_err());
+    }
+
+    #[test]
+    fn function_forms() {
+        let body = BuiltinFn {
+            name: Builtin::Add,
+            args: vec![
+                Var {
+                    name: "a".to_string(),
+                },
+                Var {
+                    name: "b".to_string(),
+                },
+            ],
+        };
+        assert_eq!(
+            Ok(Defn {
+                name: "plus".to_string(),
+                params: vec!["a".to_string(), "b".to_string()],
+                body: Box::new(body.clone())
+            }),
+            parse_expr("defn(plus, (a, b), add(a, b))")
+        );
+        assert_eq!(
+            Ok(Lambda {
+                params: vec![],
+                body: Box::new(Integer { i: 1 })
+            }),
+            parse_expr("lambda((), 1)")
+        );
+        assert_eq!(
+            Ok(Call {
+                func: Box::new(Var {
+                    name: "plus".to_string()
+                }),
+                args: vec![Integer { i: 1 }, Integer { i: 2 }]
+            }),
+            parse_expr("plus(1, 2)")
+        );
+        assert!(parse_expr("lambda((a, 2), a)").is_err());
+    }
+
+    #[test]
+    fn bool_parsing() {
+        assert_eq!(Ok(Boolean { b: true }), parse_expr("true"));
+        assert_eq!(
+            Ok(BuiltinFn {
+                name: Builtin::Not,
+                args: vec![Boolean { b: false }]
+            }),
+            parse_expr("not(false)")
+        );
+    }
+
+    #[test]
+    fn whitespace_and_unicode() {
+        assert_eq!(
+            Ok(BuiltinFn {
+                name: Builtin::Add,
+                args: vec![Integer { i: 1 }, Integer { i: 2 }]
+            }),
+            parse_expr("add (\n  1,\n  2\n)")
+        );
+        assert_eq!(
+            Ok(Call {
+                func: Box::new(Var {
+                    name: "fü".to_string()
+                }),
+                args: vec![Var {
+                    name: "π".to_string()
+                }]
+            }),
+            parse_expr("fü(π)")
+        );
+    }
+
+    #[test]
+    fn error_locations() {
+        let error: ParseError = parse_expr("add(1, 2").unwrap_err();
+        let location: SourceLocation = error.location.clone().unwrap();
+        assert_eq!((1, 9), (location.line, location.column));
+        assert_eq!(
+            "Expected \",\" or \")\", Found the End of the Expression at Line 1, Column 9\n\
+             add(1, 2\n        ^",
+            error.to_string()
+        );
+
+        let location: SourceLocation = parse_expr("mult(2,\n  é€)").unwrap_err().location.unwrap();
+        assert_eq!((2, 4), (location.line, location.column));
+        assert_eq!("  é€)\n   ^", location.snippet);
+
+        // Wide characters take two cells, so the caret still lines up under the ","
+        let location: SourceLocation = parse_expr_with_syntax("日本 + ,", Syntax::Infix)
+            .unwrap_err()
+            .location
+            .unwrap();
+        assert_eq!((1, 6), (location.line, location.column));
+        assert_eq!("日本 + ,\n       ^", location.snippet);
+
+        let error: ParseError = parse_expr("add(1, 2) 3").unwrap_err();
+        assert_eq!("Unexpected \"3\" After the Expression", error.message);
+        assert!(parse_expr("").is_err());
+        assert!(parse_expr("add(1,, 2)").is_err());
+        assert!(parse_expr("2x").is_err());
+        assert!(parse_expr("+").is_err());
+    }
+
+    #[test]
+    fn nesting_limit() {
+        let nested = |depth: usize| format!("{}1{}", "abs(".repeat(depth), ")".repeat(depth));
+        assert!(parse_expr(&nested(MAX_NESTING - 1)).is_ok());
+        let error: ParseError = parse_expr(&nested(100_000)).unwrap_err();
+        assert_eq!("Nesting Too Deep", error.message);
+        assert_eq!(MAX_NESTING * 4 + 1, error.location.unwrap().column);
+
+        let parens = |depth: usize| format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(parse_expr_with_syntax(&parens(MAX_NESTING - 1), Syntax::Infix).is_ok());
+        let error: ParseError =
+            parse_expr_with_syntax(&parens(100_000), Syntax::Infix).unwrap_err();
+        assert_eq!("Nesting Too Deep", error.message);
+        assert_eq!(
+            "Nesting Too Deep",
+            parse_expr_with_syntax(&"-".repeat(100_000), Syntax::Infix)
+                .unwrap_err()
+                .message
+        );
+        assert_eq!(
+            "Nesting Too Deep",
+            parse_expr(&format!(
+                "{}1{}",
+                "(abs ".repeat(100_000),
+                ")".repeat(100_000)
+            ))
+            .unwrap_err()
+            .message
+        );
+    }
+
+    #[test]
+    fn s_expressions() {
+        assert_eq!(
+            parse_expr("add(1, mult(2, 3))"),
+            parse_expr("(+ 1 (* 2 3))")
+        );
+        assert_eq!(
+            parse_expr("defn(hyp, (a, b), sqrt(add(pow(a, 2), pow(b, 2))))"),
+            parse_expr("(defn hyp (a b)\n  (sqrt (add (pow a 2) (pow b 2))))")
+        );
+        assert_eq!(
+            parse_expr("let(x, 2, map(lambda(y, f(x, y)), list()))"),
+            parse_expr("(let x 2 (map (lambda y (f x y)) (list)))")
+        );
+        assert_eq!(
+            Ok(Integer { i: 1 }),
+            parse_expr_with_syntax("1", Syntax::SExpr)
+        );
+        assert!(parse_expr("(add 1, 2)").is_err());
+        assert!(parse_expr("(1 2)").is_err());
+        assert!(parse_expr("(add 1 2").is_err());
+        assert!(parse_expr_with_syntax("add(1, 2)", Syntax::SExpr).is_err());
+    }
+
+    #[test]
+    fn infix() {
+        assert_eq!(
+            parse_expr("sub(add(1, mult(2, x)), pow(y, -1))"),
+            parse_expr_with_syntax("1 + 2x - y^-1", Syntax::Infix)
+        );
+        assert_eq!(
+            parse_expr("let(r, 2, mult(mult(pi, 3), pow(r, 2)))"),
+            parse_expr_with_syntax("let(r, 2, pi * 3r^2)", Syntax::Infix)
+        );
+        assert!(parse_expr_with_syntax("let(r, 2, pi r^2)", Syntax::Infix).is_err());
+        assert_eq!(
+            "Comparisons Do Not Chain, Use and(...)",
+            parse_expr_with_syntax("1 < 2 < 3", Syntax::Infix)
+                .unwrap_err()
+                .message
+        );
+        assert!(parse_expr_with_syntax("1 +", Syntax::Infix).is_err());
+        assert!(parse_expr_with_syntax("(1 + 2", Syntax::Infix).is_err());
+        assert!(parse_expr_with_syntax("[1, 2", Syntax::Infix).is_err());
+        assert_eq!(
+            parse_expr("lambda((x, y), add(x, mult(2, y)))"),
+            parse_expr_with_syntax("(x, y) -> x + 2y", Syntax::Infix)
+        );
+        assert_eq!(
+            parse_expr("map(lambda(x, lambda(y, x)), list())"),
+            parse_expr_with_syntax("map(x -> y -> x, [])", Syntax::Infix)
+        );
+        assert_eq!(
+            parse_expr("mult(lambda((), 1), 2)"),
+            parse_expr_with_syntax("(() -> 1) * 2", Syntax::Infix)
+        );
+        assert!(parse_expr_with_syntax("2 * x -> x", Syntax::Infix).is_err());
+        assert!(parse_expr_with_syntax("true -> 1", Syntax::Infix).is_err());
+        assert!(parse_expr("x -> 1").is_err());
+    }
+
+    #[test]
+    fn big_int_parsing() {
+        assert_eq!(
+            Ok(BigInteger {
+                b: "123456789012345678901234567890".parse().unwrap()
+            }),
+            parse_expr("123456789012345678901234567890")
+        );
+        assert_eq!(
+            Ok(BigInteger {
+                b: "-18446744073709551616".parse().unwrap()
+            }),
+            parse_expr("-0x1_0000_0000_0000_0000")
+        );
+    }
 }
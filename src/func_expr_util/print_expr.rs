@@ -0,0 +1,377 @@
+use num_bigint::Sign;
+
+use super::expr_types::*;
+
+/// Lines longer than this are broken up with one argument per line
+const LINE_WIDTH: usize = 60;
+
+/// Writes an `Expr` back out, `parse_expr` reads either prefix syntax back to the same `Expr`.
+/// Infix stays on one line
+pub fn pretty_print(expr: &Expr, syntax: Syntax) -> String {
+    if syntax == Syntax::Infix {
+        return print_infix(expr);
+    }
+    return render(expr, syntax, 0);
+}
+
+/// The whole expression on one line, what `Display` for `Expr` writes in call syntax
+pub fn print_prefix(expr: &Expr, syntax: Syntax) -> String {
+    if syntax == Syntax::Infix {
+        return print_infix(expr);
+    }
+    return flat(expr, syntax);
+}
+
+/// Conventional infix form with only the parentheses the precedence rules need.
+/// Operators without a symbol keep the function call form, like `sin(x + 1)`,
+/// and functions are written with an arrow, like `x -> x^2`
+pub fn print_infix(expr: &Expr) -> String {
+    if let Expr::Lambda { params, body } = expr {
+        return match params.as_slice() {
+            [param] => format!("{} -> {}", param, print_infix(body)),
+            _ => format!("({}) -> {}", params.join(", "), print_infix(body)),
+        };
+    }
+    let (symbol, precedence, args) = match infix_operator(expr) {
+        Some(operator) => operator,
+        None => {
+            return match parts(expr, Syntax::Call) {
+                Some((head, pieces)) => {
+                    let pieces: Vec<String> = pieces
+                        .iter()
+                        .map(|piece| match piece {
+                            Piece::Text(text) => text.clone(),
+                            Piece::Child(child) => print_infix(child),
+                        })
+                        .collect();
+                    format!("{}({})", head, pieces.join(", "))
+                }
+                None => infix_atom(expr),
+            };
+        }
+    };
+    let operands: Vec<String> = args
+        .iter()
+        .enumerate()
+        .map(|(index, arg)| {
+            let arg_precedence: u8 = infix_precedence(arg);
+            let needs_parens: bool = match precedence {
+                // Right associative, so only the base needs them for another power
+                POWER if index == 0 => arg_precedence <= POWER,
+                POWER => arg_precedence < POWER,
+                // Comparisons do not chain
+                COMPARISON => arg_precedence <= COMPARISON,
+                _ if index == 0 => arg_precedence < precedence,
+                _ => {
+                    arg_precedence < precedence
+                        || (arg_precedence == precedence && !regroups(expr, arg))
+                }
+            };
+            if needs_parens {
+                format!("({})", print_infix(arg))
+            } else {
+                print_infix(arg)
+            }
+        })
+        .collect();
+    return operands.join(&format!(" {} ", symbol));
+}
+
+const FUNCTION: u8 = 0;
+const COMPARISON: u8 = 1;
+const SUM: u8 = 2;
+const PRODUCT: u8 = 3;
+const NEGATION: u8 = 4;
+const POWER: u8 = 5;
+const INDEX: u8 = 6;
+const ATOM: u8 = 7;
+
+/// The symbol and precedence of builtins written between their arguments
+fn infix_operator(expr: &Expr) -> Option<(&'static str, u8, &[Expr])> {
+    let (name, args) = match expr {
+        Expr::BuiltinFn { name, args } => (name, args),
+        _ => return None,
+    };
+    let (symbol, precedence): (&str, u8) = match (name, args.len()) {
+        // A single argument is a list to add up, which has no infix form
+        (Builtin::Add, 2..) => ("+", SUM),
+        (Builtin::Multiply, 2..) => ("*", PRODUCT),
+        (Builtin::Subtract, 2) => ("-", SUM),
+        (Builtin::Divide, 2) => ("/", PRODUCT),
+        (Builtin::Modulus, 2) => ("%", PRODUCT),
+        (Builtin::Cross, 2) => ("#", PRODUCT),
+        (Builtin::Pow, 2) => ("^", POWER),
+        (Builtin::Get, 2) => ("?", INDEX),
+        (Builtin::Equal, 2) => ("==", COMPARISON),
+        (Builtin::NotEqual, 2) => ("!=", COMPARISON),
+        (Builtin::Less, 2) => ("<", COMPARISON),
+        (Builtin::LessEqual, 2) => ("<=", COMPARISON),
+        (Builtin::Greater, 2) => (">", COMPARISON),
+        (Builtin::GreaterEqual, 2) => (">=", COMPARISON),
+        _ => return None,
+    };
+    return Some((symbol, precedence, args));
+}
+
+/// Negative literals bind like a unary minus, and `2.5i` like a product
+fn infix_precedence(expr: &Expr) -> u8 {
+    match expr {
+        Expr::Integer { i } if *i < 0 => NEGATION,
+        Expr::BigInteger { b } if b.sign() == Sign::Minus => NEGATION,
+        Expr::Float { f } if f.is_sign_negative() && !f.is_nan() => NEGATION,
+        Expr::Imaginary { im } if im.is_sign_negative() => NEGATION,
+        Expr::Imaginary { im } if *im != 1.0 => PRODUCT,
+        Expr::Lambda { .. } => FUNCTION,
+        _ => match infix_operator(expr) {
+            Some((_, precedence, _)) => precedence,
+            None => ATOM,
+        },
+    }
+}
+
+/// Whether `parent` with `child` as a right operand means the same without parentheses,
+/// as in a + (b - c) and a * (b / c)
+fn regroups(parent: &Expr, child: &Expr) -> bool {
+    let (parent, child) = match (parent, child) {
+        (Expr::BuiltinFn { name: parent, .. }, Expr::BuiltinFn { name: child, .. }) => {
+            (parent, child)
+        }
+        _ => return false,
+    };
+    return matches!(
+        (parent, child),
+        (Builtin::Add, Builtin::Add | Builtin::Subtract)
+            | (Builtin::Multiply, Builtin::Multiply | Builtin::Divide)
+    );
+}
+
+/// Numbers are written the usual way, so 2.0 shows as 2
+fn infix_atom(expr: &Expr) -> String {
+    match expr {
+        Expr::Float { f } => f.to_string(),
+        Expr::Imaginary { im } if im.abs() != 1.0 => format!("{}i", im),
+        atom_expr => atom(atom_expr),
+    }
+}
+
+/// Pieces of an application after its name, names and parameter lists are already text
+enum Piece<'a> {
+    Text(String),
+    Child(&'a Expr),
+}
+
+fn render(expr: &Expr, syntax: Syntax, indent: usize) -> String {
+    let flat: String = flat(expr, syntax);
+    if indent + flat.chars().count() <= LINE_WIDTH {
+        return flat;
+    }
+    let (head, pieces) = match parts(expr, syntax) {
+        Some(parts) => parts,
+        None => return flat,
+    };
+    if pieces.is_empty() {
+        return flat;
+    }
+    let inner_indent: String = " ".repeat(indent + 2);
+    let render_piece = |piece: &Piece| match piece {
+        Piece::Text(text) => text.clone(),
+        Piece::Child(child) => render(child, syntax, indent + 2),
+    };
+    match syntax {
+        Syntax::Call | Syntax::Infix => {
+            let lines: Vec<String> = pieces
+                .iter()
+                .map(|piece| format!("{}{}", inner_indent, render_piece(piece)))
+                .collect();
+            format!("{}(\n{}\n{})", head, lines.join(",\n"), " ".repeat(indent))
+        }
+        // Names and parameter lists stay on the first line, like `(defn f (x)`
+        Syntax::SExpr => {
+            let text_len: usize = pieces
+                .iter()
+                .take_while(|piece| matches!(piece, Piece::Text(_)))
+                .count();
+            let mut first_line: Vec<String> = vec![head];
+            first_line.extend(pieces[..text_len].iter().map(render_piece));
+            let lines: Vec<String> = pieces[text_len..]
+                .iter()
+                .map(|piece| format!("{}{}", inner_indent, render_piece(piece)))
+                .collect();
+            format!("({}\n{})", first_line.join(" "), lines.join("\n"))
+        }
+    }
+}
+
+/// The whole expression on one line
+fn flat(expr: &Expr, syntax: Syntax) -> String {
+    let (head, pieces) = match parts(expr, syntax) {
+        Some(parts) => parts,
+        None => return atom(expr),
+    };
+    let pieces: Vec<String> = pieces
+        .iter()
+        .map(|piece| match piece {
+            Piece::Text(text) => text.clone(),
+            Piece::Child(child) => flat(child, syntax),
+        })
+        .collect();
+    match syntax {
+        Syntax::Call | Syntax::Infix => format!("{}({})", head, pieces.join(", ")),
+        Syntax::SExpr if pieces.is_empty() => format!("({})", head),
+        Syntax::SExpr => format!("({} {})", head, pieces.join(" ")),
+    }
+}
+
+/// Literals and variables, which look the same in both syntaxes
+fn atom(expr: &Expr) -> String {
+    match expr {
+        Expr::Integer { i } => i.to_string(),
+        Expr::BigInteger { b } => b.to_string(),
+        // Debug keeps the decimal point, so 1.0 does not come back as an integer
+        Expr::Float { f } => format!("{:?}", f),
+        Expr::Imaginary { im } => match im {
+            1.0 => "i".to_string(),
+            -1.0 => "-i".to_string(),
+            im => format!("{:?}i", im),
+        },
+        Expr::Boolean { b } => b.to_string(),
+        Expr::Var { name } => name.clone(),
+        _ => unreachable!("applications are printed by flat"),
+    }
+}
+
+/// The name and arguments of anything that is not an atom
+fn parts(expr: &Expr, syntax: Syntax) -> Option<(String, Vec<Piece<'_>>)> {
+    let params = |params: &[String]| -> Piece {
+        let separator: &str = if syntax == Syntax::Call { ", " } else { " " };
+        return Piece::Text(format!("({})", params.join(separator)));
+    };
+    match expr {
+        Expr::Let { name, value, body } => Some((
+            "let".to_string(),
+            vec![
+                Piece::Text(name.clone()),
+                Piece::Child(value),
+                Piece::Child(body),
+            ],
+        )),
+        Expr::Set { name, value } => Some((
+            "set".to_string(),
+            vec![Piece::Text(name.clone()), Piece::Child(value)],
+        )),
+        Expr::Defn {
+            name,
+            params: fn_params,
+            body,
+        } => Some((
+            "defn".to_string(),
+            vec![
+                Piece::Text(name.clone()),
+                params(fn_params),
+                Piece::Child(body),
+            ],
+        )),
+        Expr::Lambda {
+            params: fn_params,
+            body,
+        } => Some((
+            "lambda".to_string(),
+            vec![params(fn_params), Piece::Child(body)],
+        )),
+        Expr::Call { func, args } => Some((flat(func, syntax), children(args))),
+        Expr::BuiltinFn { name, args } => Some((name.name().to_string(), children(args))),
+        _ => None,
+    }
+}
+
+fn children(args: &[Expr]) -> Vec<Piece<'_>> {
+    return args.iter().map(Piece::Child).collect();
+}
+
+#[cfg(test)]
+mod print_tests {
+    use super::*;
+    use crate::func_expr_util::parse_expr::{parse_expr, parse_expr_with_syntax};
+
+    const EXPRESSIONS: [&str; 6] = [
+        "add(1, mult(2.0, 3))",
+        "sub(-i, 2.5i, 123456789012345678901234567890)",
+        "defn(hyp, (a, b), sqrt(add(pow(a, 2), pow(b, 2))))",
+        "let(x, true, if(not(x), lambda((), -inf), lambda(y, y)))",
+        "set(total, reduce(lambda((a, b), add(a, b)), map(lambda(x, mult(x, x)), range(1, 100)), 0))",
+        "f(g(h(1)), rand())",
+    ];
+
+    #[test]
+    fn round_trips_in_both_syntaxes() {
+        for source in EXPRESSIONS {
+            let expr: Expr = parse_expr(source).unwrap();
+            for syntax in [Syntax::Call, Syntax::SExpr] {
+                let printed: String = pretty_print(&expr, syntax);
+                assert_eq!(
+                    Ok(expr.clone()),
+                    parse_expr_with_syntax(&printed, syntax),
+                    "{}",
+                    printed
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn infix() {
+        let infix = |source: &str| print_infix(&parse_expr(source).unwrap());
+        assert_eq!("1 + 2 * 3", infix("(+ 1 (* 2 3))"));
+        assert_eq!("(1 + 2) * 3", infix("(* (+ 1 2) 3)"));
+        assert_eq!("a - (b - c)", infix("(- a (- b c))"));
+        assert_eq!("a - b - c", infix("(- (- a b) c)"));
+        assert_eq!("a + b - c + d", infix("(+ a (- b c) d)"));
+        assert_eq!("a * b % c", infix("(% (* a b) c)"));
+        assert_eq!("a * (b % c)", infix("(* a (% b c))"));
+        assert_eq!("a ^ b ^ c", infix("(pow a (pow b c))"));
+        assert_eq!("(a ^ b) ^ c", infix("(pow (pow a b) c)"));
+        assert_eq!("(-3) ^ 2", infix("(pow -3 2)"));
+        assert_eq!("2 * -3.5", infix("(* 2.0 -3.5)"));
+        assert_eq!("(2.5i) ^ 2", infix("(pow 2.5i 2)"));
+        assert_eq!("(a < b) == true", infix("(== (< a b) true)"));
+        assert_eq!("sin(x + 1) / add(xs)", infix("(/ (sin (+ x 1)) (add xs))"));
+        assert_eq!(
+            "defn(f, (x), if(x > 0, x, 0 - x))",
+            infix("(defn f (x) (if (> x 0) x (- 0 x)))")
+        );
+        assert_eq!("x -> y -> x * y", infix("lambda(x, lambda(y, mult(x, y)))"));
+        assert_eq!("() -> 1", infix("lambda((), 1)"));
+        assert_eq!(
+            "map(x -> x + 1, xs) + (y -> y)",
+            infix("add(map(lambda(x, add(x, 1)), xs), lambda(y, y))")
+        );
+        for source in [
+            "lambda((a, b), add(a, b))",
+            "map(lambda(x, pow(x, 2)), list(1, 2))",
+            "add(lambda(x, x), 1)",
+        ] {
+            let expr: Expr = parse_expr(source).unwrap();
+            assert_eq!(
+                Ok(expr.clone()),
+                parse_expr_with_syntax(&print_infix(&expr), Syntax::Infix)
+            );
+        }
+    }
+
+    #[test]
+    fn layout() {
+        let expr: Expr = parse_expr("(+ 1 (* 2.5 x))").unwrap();
+        assert_eq!("add(1, mult(2.5, x))", pretty_print(&expr, Syntax::Call));
+        assert_eq!("(add 1 (mult 2.5 x))", pretty_print(&expr, Syntax::SExpr));
+
+        let expr: Expr = parse_expr(EXPRESSIONS[4]).unwrap();
+        assert_eq!(
+            "(set total\n  (reduce\n    (lambda (a b) (add a b))\n    (map (lambda (x) (mult x x)) (range 1 100))\n    0))",
+            pretty_print(&expr, Syntax::SExpr)
+        );
+        assert_eq!(
+            "set(\n  total,\n  reduce(\n    lambda((a, b), add(a, b)),\n    map(lambda((x), mult(x, x)), range(1, 100)),\n    0\n  )\n)",
+            pretty_print(&expr, Syntax::Call)
+        );
+    }
+}
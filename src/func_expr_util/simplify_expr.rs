@@ -0,0 +1,1044 @@
+use num_traits::Signed;
+
+use super::{
+    constants::constant_value,
+    expr_types::{Builtin, Expr, Value},
+    interp_expr::interp_expr,
+};
+
+/// Passes before `simplify` settles for what it has, each one rewrites bottom up
+const MAX_PASSES: usize = 16;
+
+/// Folds arithmetic on number literals, removes identities like `x*1` and `x+0`,
+/// collects like terms and powers of the same base, and applies trig identities
+/// like sin(x)^2 + cos(x)^2 = 1. Factors that may be lists or matrices keep their
+/// order in products. Like most computer algebra, it assumes denominators are not zero,
+/// so x/x becomes 1
+pub fn simplify(expr: &Expr) -> Expr {
+    let mut current: Expr = simplify_once(expr);
+    for _ in 1..MAX_PASSES {
+        let next: Expr = simplify_once(&current);
+        if next == current {
+            break;
+        }
+        current = next;
+    }
+    return current;
+}
+
+/// Names used but not bound inside `expr`, in the order they first appear.
+/// The names of called functions are left out, they are not values to fill in
+pub fn free_variables(expr: &Expr) -> Vec<String> {
+    let mut free: Vec<String> = Vec::new();
+    collect_free(expr, &mut Vec::new(), &mut free);
+    return free;
+}
+
+fn collect_free(expr: &Expr, bound: &mut Vec<String>, free: &mut Vec<String>) {
+    match expr {
+        Expr::Var { name } if !bound.contains(name) && !free.contains(name) => {
+            free.push(name.clone());
+        }
+        Expr::Let { name, value, body } => {
+            collect_free(value, bound, free);
+            bound.push(name.clone());
+            collect_free(body, bound, free);
+            bound.pop();
+        }
+        Expr::Set { value, .. } => collect_free(value, bound, free),
+        Expr::Defn { name, params, body } => {
+            let depth: usize = bound.len();
+            bound.push(name.clone());
+            bound.extend(params.iter().cloned());
+            collect_free(body, bound, free);
+            bound.truncate(depth);
+        }
+        Expr::Lambda { params, body } => {
+            let depth: usize = bound.len();
+            bound.extend(params.iter().cloned());
+            collect_free(body, bound, free);
+            bound.truncate(depth);
+        }
+        Expr::Call { func, args } => {
+            if !matches!(**func, Expr::Var { .. }) {
+                collect_free(func, bound, free);
+            }
+            args.iter().for_each(|arg| collect_free(arg, bound, free));
+        }
+        Expr::BuiltinFn { args, .. } => {
+            args.iter().for_each(|arg| collect_free(arg, bound, free));
+        }
+        _ => {}
+    }
+}
+
+fn simplify_once(expr: &Expr) -> Expr {
+    match expr {
+        Expr::BuiltinFn { name, args } => {
+            let args: Vec<Expr> = args.iter().map(simplify_once).collect();
+            return simplify_builtin(*name, args);
+        }
+        Expr::Let { name, value, body } => Expr::Let {
+            name: name.clone(),
+            value: Box::new(simplify_once(value)),
+            body: Box::new(simplify_once(body)),
+        },
+        Expr::Set { name, value } => Expr::Set {
+            name: name.clone(),
+            value: Box::new(simplify_once(value)),
+        },
+        Expr::Defn { name, params, body } => Expr::Defn {
+            name: name.clone(),
+            params: params.clone(),
+            body: Box::new(simplify_once(body)),
+        },
+        Expr::Lambda { params, body } => Expr::Lambda {
+            params: params.clone(),
+            body: Box::new(simplify_once(body)),
+        },
+        Expr::Call { func, args } => Expr::Call {
+            func: Box::new(simplify_once(func)),
+            args: args.iter().map(simplify_once).collect(),
+        },
+        atom => atom.clone(),
+    }
+}
+
+/// `args` are already simplified
+fn simplify_builtin(name: Builtin, args: Vec<Expr>) -> Expr {
+    match (name, args.as_slice()) {
+        (Builtin::Add, _) | (Builtin::Subtract, [_, _]) => simplify_sum(name, args),
+        (Builtin::Multiply, _) | (Builtin::Divide, [_, _]) => {
+            simplify_product(&Expr::BuiltinFn { name, args })
+        }
+        (Builtin::Pow, [base, exponent]) => simplify_power(base, exponent),
+
+        // Values at 0 and 1 every angle mode agrees on
+        (
+            Builtin::Sin
+            | Builtin::Tan
+            | Builtin::ArcSin
+            | Builtin::ArcTan
+            | Builtin::Sinh
+            | Builtin::Tanh
+            | Builtin::ArcSinh
+            | Builtin::ArcTanh
+            | Builtin::Sqrt
+            | Builtin::Abs,
+            [zero],
+        ) if is_zero(zero) => int(0),
+        (Builtin::Cos | Builtin::Cosh, [zero]) if is_zero(zero) => int(1),
+        (Builtin::Ln | Builtin::Log10 | Builtin::Log2, [one]) if is_one(one) => int(0),
+        (Builtin::Sqrt | Builtin::Abs, [one]) if is_one(one) => int(1),
+
+        // Odd functions pass negation through, even ones drop it
+        (
+            Builtin::Sin
+            | Builtin::Tan
+            | Builtin::ArcSin
+            | Builtin::ArcTan
+            | Builtin::Sinh
+            | Builtin::Tanh
+            | Builtin::ArcSinh
+            | Builtin::ArcTanh,
+            [arg],
+        ) if negation_of(arg).is_some() => {
+            let inner: Expr = negation_of(arg).unwrap_or_else(|| arg.clone());
+            negate(call(name, vec![inner]))
+        }
+        (Builtin::Cos | Builtin::Cosh | Builtin::Abs, [arg]) if negation_of(arg).is_some() => {
+            call(name, vec![negation_of(arg).unwrap_or_else(|| arg.clone())])
+        }
+        (
+            Builtin::Sqrt,
+            [Expr::BuiltinFn {
+                name: Builtin::Pow,
+                args: power,
+            }],
+        ) if power.len() == 2 && is_two(&power[1]) => call(Builtin::Abs, vec![power[0].clone()]),
+        (
+            Builtin::Abs,
+            [inner @ Expr::BuiltinFn {
+                name: Builtin::Abs, ..
+            }],
+        ) => inner.clone(),
+        _ => Expr::BuiltinFn { name, args },
+    }
+}
+
+fn simplify_power(base: &Expr, exponent: &Expr) -> Expr {
+    if is_one(exponent) {
+        return base.clone();
+    } else if is_zero(exponent) || is_one(base) {
+        return int(1);
+    }
+    if let Some(folded) = fold(Builtin::Pow, &[base.clone(), exponent.clone()]) {
+        return folded;
+    }
+    match base {
+        // Squaring a square root gives back what was under it, complex numbers included
+        Expr::BuiltinFn {
+            name: Builtin::Sqrt,
+            args,
+        } if args.len() == 1 && is_two(exponent) => return args[0].clone(),
+        // The square root of a square, like sqrt(x^2)
+        Expr::BuiltinFn {
+            name: Builtin::Pow,
+            args,
+        } if args.len() == 2 && is_two(&args[1]) && is_half(exponent) => {
+            return call(Builtin::Abs, vec![args[0].clone()]);
+        }
+        // (x^a)^n = x^(a n) only holds for whole n
+        Expr::BuiltinFn {
+            name: Builtin::Pow,
+            args,
+        } if args.len() == 2 && is_integer(exponent) => {
+            return simplify_power(
+                &args[0],
+                &simplify_product(&call(
+                    Builtin::Multiply,
+                    vec![args[1].clone(), exponent.clone()],
+                )),
+            );
+        }
+        _ => {}
+    }
+    let power: Expr = call(Builtin::Pow, vec![base.clone(), exponent.clone()]);
+    // Negative powers move under a division
+    if negation_of(exponent).is_some() {
+        return simplify_product(&power);
+    }
+    return power;
+}
+
+/// A sum split into number literals and terms that are a number times everything else
+#[derive(Default)]
+struct Sum {
+    constants: Vec<Expr>,
+    /// Coefficient and the rest of the term, in the order each rest first appears
+    terms: Vec<(Expr, Expr)>,
+}
+
+impl Sum {
+    fn collect(&mut self, expr: &Expr, negative: bool) {
+        match expr {
+            number if is_number(number) => self.constants.push(signed(number, negative)),
+            Expr::BuiltinFn {
+                name: Builtin::Add,
+                args,
+            } => args.iter().for_each(|arg| self.collect(arg, negative)),
+            Expr::BuiltinFn {
+                name: Builtin::Subtract,
+                args,
+            } if args.len() == 2 => {
+                self.collect(&args[0], negative);
+                self.collect(&args[1], !negative);
+            }
+            term => {
+                let (coefficient, rest) = split_coefficient(term);
+                self.add_term(signed(&coefficient, negative), rest);
+            }
+        }
+    }
+
+    fn add_term(&mut self, coefficient: Expr, rest: Expr) {
+        match self
+            .terms
+            .iter_mut()
+            .find(|(_, other)| equivalent(other, &rest))
+        {
+            Some((total, _)) => *total = add_numbers(total, &coefficient),
+            None => self.terms.push((coefficient, rest)),
+        }
+    }
+
+    /// sin(x)^2 + cos(x)^2 = 1 and cosh(x)^2 - sinh(x)^2 = 1, when the coefficients match up
+    fn apply_pythagorean_identities(&mut self) {
+        let pairs: [(Builtin, Builtin, bool); 2] = [
+            (Builtin::Sin, Builtin::Cos, false),
+            (Builtin::Cosh, Builtin::Sinh, true),
+        ];
+        for (first, second, opposite) in pairs {
+            let mut index: usize = 0;
+            while index < self.terms.len() {
+                let (coefficient, rest) = &self.terms[index];
+                let partner: Option<usize> = square_of(rest, first).and_then(|arg| {
+                    let wanted: Expr = call(Builtin::Pow, vec![call(second, vec![arg]), int(2)]);
+                    let wanted_coefficient: Expr = signed(coefficient, opposite);
+                    return self.terms.iter().position(|(other_coefficient, other)| {
+                        equivalent(other, &wanted) && *other_coefficient == wanted_coefficient
+                    });
+                });
+                match partner {
+                    Some(partner) => {
+                        self.constants.push(coefficient.clone());
+                        let (low, high) = (index.min(partner), index.max(partner));
+                        self.terms.remove(high);
+                        self.terms.remove(low);
+                    }
+                    None => index += 1,
+                }
+            }
+        }
+    }
+
+    /// c - c sin(x)^2 = c cos(x)^2 and the same the other way around, using up the constant
+    fn apply_complement_identities(&mut self) {
+        let Some(constant) = fold_all(Builtin::Add, self.constants.clone()) else {
+            return;
+        };
+        for index in 0..self.terms.len() {
+            let (coefficient, rest) = &self.terms[index];
+            if signed(coefficient, true) != constant {
+                continue;
+            }
+            let complement: Option<Expr> =
+                [(Builtin::Sin, Builtin::Cos), (Builtin::Cos, Builtin::Sin)]
+                    .into_iter()
+                    .find_map(|(function, other)| {
+                        square_of(rest, function)
+                            .map(|arg| call(Builtin::Pow, vec![call(other, vec![arg]), int(2)]))
+                    });
+            if let Some(complement) = complement {
+                self.terms.remove(index);
+                self.constants.clear();
+                self.add_term(constant, complement);
+                return;
+            }
+        }
+    }
+
+    /// Positive terms added up, minus the negative ones, with the constant last on its side
+    fn rebuild(mut self) -> Expr {
+        self.apply_pythagorean_identities();
+        self.apply_complement_identities();
+        let mut positives: Vec<Expr> = Vec::new();
+        let mut negatives: Vec<Expr> = Vec::new();
+        for (coefficient, rest) in self.terms {
+            if is_zero(&coefficient) {
+                continue;
+            } else if is_negative(&coefficient) {
+                negatives.push(scale(&signed(&coefficient, true), rest));
+            } else {
+                positives.push(scale(&coefficient, rest));
+            }
+        }
+        if let Some(constant) = fold_all(Builtin::Add, self.constants) {
+            if is_negative(&constant) {
+                negatives.push(signed(&constant, true));
+            } else if !is_zero(&constant) {
+                positives.push(constant);
+            }
+        }
+        return match (positives.is_empty(), negatives.is_empty()) {
+            (true, true) => int(0),
+            (_, true) => sum_of(positives),
+            (true, false) => negate(sum_of(negatives)),
+            (false, false) => call(
+                Builtin::Subtract,
+                vec![sum_of(positives), sum_of(negatives)],
+            ),
+        };
+    }
+}
+
+fn simplify_sum(name: Builtin, args: Vec<Expr>) -> Expr {
+    let mut sum: Sum = Sum::default();
+    sum.collect(&Expr::BuiltinFn { name, args }, false);
+    return sum.rebuild();
+}
+
+/// The leading number of a product, or 1 when there is none
+fn split_coefficient(term: &Expr) -> (Expr, Expr) {
+    match term {
+        Expr::BuiltinFn {
+            name: Builtin::Multiply,
+            args,
+        } if args.len() >= 2 && is_number(&args[0]) => {
+            (args[0].clone(), product_of(args[1..].to_vec()))
+        }
+        Expr::BuiltinFn {
+            name: Builtin::Subtract,
+            args,
+        } if is_negation(args) => {
+            let (coefficient, rest) = split_coefficient(&args[1]);
+            (signed(&coefficient, true), rest)
+        }
+        Expr::BuiltinFn {
+            name: Builtin::Divide,
+            args,
+        } if args.len() == 2 => {
+            let (coefficient, rest) = split_coefficient(&args[0]);
+            (
+                coefficient,
+                call(Builtin::Divide, vec![rest, args[1].clone()]),
+            )
+        }
+        _ => (int(1), term.clone()),
+    }
+}
+
+/// A product split into number literals above and below the line,
+/// and powers of everything else
+#[derive(Default)]
+struct Product {
+    numerator: Vec<Expr>,
+    denominator: Vec<Expr>,
+    /// Base and exponent, in the order each base first appears. Only bases that are
+    /// numbers are merged, since products of matrices depend on their order
+    powers: Vec<(Expr, Expr)>,
+    /// Divisors and bases of powers, which are numbers whenever the product evaluates
+    numeric: Vec<Expr>,
+}
+
+impl Product {
+    fn note_numeric(&mut self, expr: &Expr) {
+        match expr {
+            Expr::BuiltinFn {
+                name: Builtin::Multiply,
+                args,
+            } => args.iter().for_each(|arg| self.note_numeric(arg)),
+            Expr::BuiltinFn {
+                name: Builtin::Divide,
+                args,
+            } if args.len() == 2 => {
+                self.note_numeric(&args[0]);
+                self.note_numeric(&args[1]);
+                self.numeric.push(args[1].clone());
+            }
+            Expr::BuiltinFn {
+                name: Builtin::Subtract,
+                args,
+            } if is_negation(args) => self.note_numeric(&args[1]),
+            Expr::BuiltinFn {
+                name: Builtin::Pow,
+                args,
+            } if args.len() == 2 => self.numeric.push(args[0].clone()),
+            _ => {}
+        }
+    }
+
+    fn is_numeric(&self, base: &Expr) -> bool {
+        return is_scalar(base) || self.numeric.iter().any(|other| equivalent(other, base));
+    }
+
+    fn collect(&mut self, expr: &Expr, inverted: bool) {
+        match expr {
+            number if is_number(number) => match inverted {
+                true => self.denominator.push(number.clone()),
+                false => self.numerator.push(number.clone()),
+            },
+            Expr::BuiltinFn {
+                name: Builtin::Multiply,
+                args,
+            } => args.iter().for_each(|arg| self.collect(arg, inverted)),
+            Expr::BuiltinFn {
+                name: Builtin::Divide,
+                args,
+            } if args.len() == 2 => {
+                self.collect(&args[0], inverted);
+                self.collect(&args[1], !inverted);
+            }
+            Expr::BuiltinFn {
+                name: Builtin::Subtract,
+                args,
+            } if is_negation(args) => {
+                self.numerator.push(int(-1));
+                self.collect(&args[1], inverted);
+            }
+            Expr::BuiltinFn {
+                name: Builtin::Pow,
+                args,
+            } if args.len() == 2 => self.add_power(&args[0], signed(&args[1], inverted)),
+            factor => self.add_power(factor, int(if inverted { -1 } else { 1 })),
+        }
+    }
+
+    fn add_power(&mut self, base: &Expr, exponent: Expr) {
+        if !self.is_numeric(base) {
+            self.powers.push((base.clone(), exponent));
+            return;
+        }
+        match self
+            .powers
+            .iter_mut()
+            .find(|(other, _)| equivalent(other, base))
+        {
+            Some((_, total)) => {
+                *total = simplify_sum(Builtin::Add, vec![total.clone(), exponent]);
+            }
+            None => self.powers.push((base.clone(), exponent)),
+        }
+    }
+
+    /// sin(x)/cos(x) = tan(x)
+    fn apply_tangent_identity(&mut self) {
+        let mut index: usize = 0;
+        while index < self.powers.len() {
+            let (base, exponent) = &self.powers[index];
+            let tangent: Option<(Expr, usize)> = match base {
+                Expr::BuiltinFn {
+                    name: Builtin::Sin,
+                    args,
+                } if is_one(exponent) => {
+                    let cosine: Expr = call(Builtin::Cos, args.clone());
+                    self.powers
+                        .iter()
+                        .position(|(other, other_exponent)| {
+                            equivalent(other, &cosine) && is_minus_one(other_exponent)
+                        })
+                        .map(|partner| (call(Builtin::Tan, args.clone()), partner))
+                }
+                _ => None,
+            };
+            if let Some((tan, partner)) = tangent {
+                self.powers[index] = (tan, int(1));
+                self.powers.remove(partner);
+            }
+            index += 1;
+        }
+    }
+
+    fn rebuild(mut self) -> Expr {
+        self.apply_tangent_identity();
+        let numerator: Expr = fold_all(Builtin::Multiply, self.numerator).unwrap_or(int(1));
+        let denominator: Expr = fold_all(Builtin::Multiply, self.denominator).unwrap_or(int(1));
+        if is_zero(&numerator) && !is_zero(&denominator) {
+            return int(0);
+        }
+        let (mut top, bottom) = divide_numbers(&numerator, &denominator);
+        if let Some(half) = apply_double_angle_identity(&mut self.powers, &top) {
+            top = half;
+        }
+        let negative: bool = is_negative(&top);
+        if negative {
+            top = signed(&top, true);
+        }
+
+        let mut above: Vec<Expr> = Vec::new();
+        let mut below: Vec<Expr> = Vec::new();
+        if !is_one(&top) {
+            above.push(top);
+        }
+        if !is_one(&bottom) {
+            below.push(bottom);
+        }
+        for (base, exponent) in self.powers {
+            if is_zero(&exponent) {
+                continue;
+            }
+            match negation_of(&exponent) {
+                Some(positive) => below.push(power(base, positive)),
+                None => above.push(power(base, exponent)),
+            }
+        }
+        let quotient: Expr = match below.is_empty() {
+            true => product_of(above),
+            false => call(Builtin::Divide, vec![product_of(above), product_of(below)]),
+        };
+        if negative {
+            return negate(quotient);
+        }
+        return quotient;
+    }
+}
+
+/// 2 sin(x) cos(x) = sin(2x) among the powers of a product, when its number `top` has
+/// a factor of 2 to spare. Gives back what is left of `top`
+fn apply_double_angle_identity(powers: &mut Vec<(Expr, Expr)>, top: &Expr) -> Option<Expr> {
+    let (half, remainder) = divide_numbers(top, &int(2));
+    if !is_one(&remainder) {
+        return None;
+    }
+    for index in 0..powers.len() {
+        let angle: Expr = match &powers[index] {
+            (
+                Expr::BuiltinFn {
+                    name: Builtin::Sin,
+                    args,
+                },
+                exponent,
+            ) if args.len() == 1 && is_one(exponent) => args[0].clone(),
+            _ => continue,
+        };
+        let cosine: Expr = call(Builtin::Cos, vec![angle.clone()]);
+        let partner: Option<usize> = powers
+            .iter()
+            .position(|(other, exponent)| equivalent(other, &cosine) && is_one(exponent));
+        if let Some(partner) = partner {
+            let doubled: Expr = simplify_product(&call(Builtin::Multiply, vec![int(2), angle]));
+            powers[index] = (call(Builtin::Sin, vec![doubled]), int(1));
+            powers.remove(partner);
+            return Some(half);
+        }
+    }
+    return None;
+}
+
+fn simplify_product(expr: &Expr) -> Expr {
+    let mut product: Product = Product::default();
+    product.note_numeric(expr);
+    product.collect(expr, false);
+    return product.rebuild();
+}
+
+/// Number literals as a top and bottom with no common factor, 6/4 is 3 over 2
+fn divide_numbers(numerator: &Expr, denominator: &Expr) -> (Expr, Expr) {
+    if is_one(denominator) {
+        return (numerator.clone(), int(1));
+    }
+    let quotient: Result<Value, _> = interp_expr(&call(
+        Builtin::Divide,
+        vec![numerator.clone(), denominator.clone()],
+    ));
+    match quotient {
+        Ok(Value::RationalV { r_v }) => (
+            number_literal(Value::from_big_int(r_v.numer().clone())),
+            number_literal(Value::from_big_int(r_v.denom().clone())),
+        ),
+        Ok(value) => match literal(value) {
+            Some(folded) => (folded, int(1)),
+            None => (numerator.clone(), denominator.clone()),
+        },
+        // Division by zero is left for evaluation to report
+        Err(_) => (numerator.clone(), denominator.clone()),
+    }
+}
+
+fn power(base: Expr, exponent: Expr) -> Expr {
+    if is_one(&exponent) {
+        return base;
+    }
+    return call(Builtin::Pow, vec![base, exponent]);
+}
+
+/// A number times the rest of a term, merged into the rest when it is a product too
+fn scale(coefficient: &Expr, rest: Expr) -> Expr {
+    if is_one(coefficient) {
+        return rest;
+    }
+    return simplify_product(&call(Builtin::Multiply, vec![coefficient.clone(), rest]));
+}
+
+fn sum_of(mut terms: Vec<Expr>) -> Expr {
+    return match terms.len() {
+        0 => int(0),
+        1 => terms.remove(0),
+        _ => call(Builtin::Add, terms),
+    };
+}
+
+fn product_of(mut factors: Vec<Expr>) -> Expr {
+    return match factors.len() {
+        0 => int(1),
+        1 => factors.remove(0),
+        _ => call(Builtin::Multiply, factors),
+    };
+}
+
+/// Equal up to the order of the terms of sums and the scalar factors of products,
+/// so 2*x matches x*2 but x*y does not match y*x
+fn equivalent(a: &Expr, b: &Expr) -> bool {
+    match (a, b) {
+        (
+            Expr::BuiltinFn {
+                name: a_name,
+                args: a_args,
+            },
+            Expr::BuiltinFn {
+                name: b_name,
+                args: b_args,
+            },
+        ) if a_name == b_name && a_args.len() == b_args.len() => {
+            if !matches!(a_name, Builtin::Add | Builtin::Multiply) {
+                return a_args.iter().zip(b_args).all(|(a, b)| equivalent(a, b));
+            }
+            if *a_name == Builtin::Multiply {
+                let ordered = |args: &[Expr]| -> Vec<Expr> {
+                    return args.iter().filter(|arg| !is_scalar(arg)).cloned().collect();
+                };
+                let (a_ordered, b_ordered) = (ordered(a_args), ordered(b_args));
+                if a_ordered.len() != b_ordered.len()
+                    || !a_ordered
+                        .iter()
+                        .zip(&b_ordered)
+                        .all(|(a, b)| equivalent(a, b))
+                {
+                    return false;
+                }
+            }
+            let mut unmatched: Vec<&Expr> = b_args.iter().collect();
+            for a_arg in a_args {
+                match unmatched.iter().position(|b_arg| equivalent(a_arg, b_arg)) {
+                    Some(index) => unmatched.swap_remove(index),
+                    None => return false,
+                };
+            }
+            return true;
+        }
+        _ => a == b,
+    }
+}
+
+/// Whether `expr` is a number whatever its names hold, rather than maybe a list or matrix
+fn is_scalar(expr: &Expr) -> bool {
+    match expr {
+        Expr::Var { name } => constant_value(name).is_some(),
+        Expr::BuiltinFn {
+            name: Builtin::Add | Builtin::Subtract | Builtin::Multiply | Builtin::Divide,
+            args,
+        } => args.iter().all(is_scalar),
+        // These only take and give numbers
+        Expr::BuiltinFn { name, .. } => matches!(
+            name,
+            Builtin::Sin
+                | Builtin::Cos
+                | Builtin::Tan
+                | Builtin::ArcSin
+                | Builtin::ArcCos
+                | Builtin::ArcTan
+                | Builtin::Degrees
+                | Builtin::Radians
+                | Builtin::Sinh
+                | Builtin::Cosh
+                | Builtin::Tanh
+                | Builtin::ArcSinh
+                | Builtin::ArcCosh
+                | Builtin::ArcTanh
+                | Builtin::Ln
+                | Builtin::Log10
+                | Builtin::Log2
+                | Builtin::Pow
+                | Builtin::Sqrt
+                | Builtin::Abs
+        ),
+        number => is_number(number),
+    }
+}
+
+/// The argument of `function(arg)^2`
+fn square_of(expr: &Expr, function: Builtin) -> Option<Expr> {
+    match expr {
+        Expr::BuiltinFn {
+            name: Builtin::Pow,
+            args,
+        } if args.len() == 2 && is_two(&args[1]) => match &args[0] {
+            Expr::BuiltinFn { name, args } if *name == function && args.len() == 1 => {
+                Some(args[0].clone())
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// None when there are no numbers, or they do not fold
+fn fold_all(name: Builtin, numbers: Vec<Expr>) -> Option<Expr> {
+    return match numbers.len() {
+        0 => None,
+        1 => numbers.into_iter().next(),
+        _ => fold(name, &numbers),
+    };
+}
+
+/// Evaluates a builtin applied to number literals, when the result is a literal too.
+/// Fractions like div(1, 3) are left alone since they read better unevaluated
+fn fold(name: Builtin, args: &[Expr]) -> Option<Expr> {
+    if !args.iter().all(is_number) {
+        return None;
+    }
+    return literal(interp_expr(&call(name, args.to_vec())).ok()?);
+}
+
+fn add_numbers(a: &Expr, b: &Expr) -> Expr {
+    return fold(Builtin::Add, &[a.clone(), b.clone()])
+        .unwrap_or_else(|| call(Builtin::Add, vec![a.clone(), b.clone()]));
+}
+
+fn literal(value: Value) -> Option<Expr> {
+    match value {
+        Value::IntV { i_v } => Some(int(i_v)),
+        Value::BigIntV { b_v } => Some(Expr::BigInteger { b: b_v }),
+        Value::FloatV { f_v } if f_v.is_finite() => Some(Expr::Float { f: f_v }),
+        _ => None,
+    }
+}
+
+fn number_literal(value: Value) -> Expr {
+    return literal(value).unwrap_or(int(1));
+}
+
+/// The number or expression negated when `negative` is set
+fn signed(expr: &Expr, negative: bool) -> Expr {
+    if !negative {
+        return expr.clone();
+    }
+    if let Some(inner) = negation_of(expr) {
+        return inner;
+    }
+    return match fold(Builtin::Subtract, &[int(0), expr.clone()]) {
+        Some(folded) => folded,
+        None => negate(expr.clone()),
+    };
+}
+
+/// `x` for sub(0, x) and the magnitude of a negative number
+fn negation_of(expr: &Expr) -> Option<Expr> {
+    match expr {
+        Expr::BuiltinFn {
+            name: Builtin::Subtract,
+            args,
+        } if is_negation(args) => Some(args[1].clone()),
+        number if is_negative(number) => fold(Builtin::Subtract, &[int(0), number.clone()]),
+        _ => None,
+    }
+}
+
+/// Arguments of `sub` written as a negation, sub(0, x)
+fn is_negation(sub_args: &[Expr]) -> bool {
+    return sub_args.len() == 2 && is_zero(&sub_args[0]);
+}
+
+fn negate(expr: Expr) -> Expr {
+    return call(Builtin::Subtract, vec![int(0), expr]);
+}
+
+fn call(name: Builtin, args: Vec<Expr>) -> Expr {
+    return Expr::BuiltinFn { name, args };
+}
+
+fn int(i: i64) -> Expr {
+    return Expr::Integer { i };
+}
+
+fn is_number(expr: &Expr) -> bool {
+    return matches!(
+        expr,
+        Expr::Integer { .. } | Expr::BigInteger { .. } | Expr::Float { .. }
+    );
+}
+
+fn is_negative(expr: &Expr) -> bool {
+    match expr {
+        Expr::Integer { i } => *i < 0,
+        Expr::BigInteger { b } => b.is_negative(),
+        Expr::Float { f } => *f < 0.0,
+        _ => false,
+    }
+}
+
+fn is_integer(expr: &Expr) -> bool {
+    return matches!(expr, Expr::Integer { .. } | Expr::BigInteger { .. });
+}
+
+fn is_zero(expr: &Expr) -> bool {
+    return matches!(expr, Expr::Integer { i: 0 })
+        || matches!(expr, Expr::Float { f } if *f == 0.0);
+}
+
+fn is_one(expr: &Expr) -> bool {
+    return matches!(expr, Expr::Integer { i: 1 })
+        || matches!(expr, Expr::Float { f } if *f == 1.0);
+}
+
+fn is_minus_one(expr: &Expr) -> bool {
+    return matches!(expr, Expr::Integer { i: -1 })
+        || matches!(expr, Expr::Float { f } if *f == -1.0);
+}
+
+fn is_two(expr: &Expr) -> bool {
+    return matches!(expr, Expr::Integer { i: 2 })
+        || matches!(expr, Expr::Float { f } if *f == 2.0);
+}
+
+fn is_half(expr: &Expr) -> bool {
+    return matches!(expr, Expr::Float { f } if *f == 0.5)
+        || matches!(expr, Expr::BuiltinFn { name: Builtin::Divide, args }
+            if args.len() == 2 && is_one(&args[0]) && is_two(&args[1]));
+}
+
+#[cfg(test)]
+mod simplify_tests {
+    use super::*;
+    use crate::func_expr_util::{
+        evaluate_prefix_expression, evaluate_prefix_expression_in_env, expr_types::Env,
+        parse_expr::parse_expr,
+    };
+
+    fn simplified(source: &str) -> String {
+        return simplify(&parse_expr(source).unwrap()).to_string();
+    }
+
+    #[test]
+    fn identities() {
+        assert_eq!("x", simplified("add(x, 0)"));
+        assert_eq!("x", simplified("mult(1, x)"));
+        assert_eq!("0", simplified("mult(x, 0, y)"));
+        assert_eq!("x", simplified("pow(x, 1)"));
+        assert_eq!("1", simplified("pow(x, 0)"));
+        assert_eq!("x", simplified("div(x, 1)"));
+        assert_eq!("0", simplified("sub(x, x)"));
+        assert_eq!("x", simplified("sub(0, sub(0, x))"));
+    }
+
+    #[test]
+    fn folding() {
+        assert_eq!("add(x, 5)", simplified("add(2, x, 3)"));
+        assert_eq!("mult(6, x, y)", simplified("mult(2, x, mult(3, y))"));
+        assert_eq!("sub(0, x)", simplified("mult(-1, x)"));
+        assert_eq!("mult(2, x)", simplified("mult(sub(0, x), -2)"));
+        assert_eq!("pow(x, 2)", simplified("pow(x, sub(3, 1))"));
+        assert_eq!("div(1, 3)", simplified("div(1, 3)"));
+        assert_eq!("div(3, 2)", simplified("div(6, 4)"));
+        assert_eq!("sin(x)", simplified("sin(mult(x, 1))"));
+        assert_eq!("div(x, 0)", simplified("div(x, 0)"));
+    }
+
+    #[test]
+    fn like_terms() {
+        assert_eq!("mult(3, x)", simplified("add(x, mult(2, x))"));
+        assert_eq!("add(mult(2, x), y)", simplified("add(x, y, x)"));
+        assert_eq!("sub(x, 2)", simplified("add(x, -2)"));
+        assert_eq!("sub(y, x)", simplified("sub(add(y, 1), add(x, 1))"));
+        assert_eq!("0", simplified("sub(mult(2, x, y), mult(x, 2, y))"));
+        // x and y could be matrices, which do not commute
+        assert_eq!(
+            "sub(mult(x, y), mult(y, x))",
+            simplified("sub(mult(x, y), mult(y, x))")
+        );
+        assert_eq!("sub(0, add(x, y))", simplified("sub(sub(0, x), y)"));
+    }
+
+    #[test]
+    fn powers() {
+        assert_eq!("mult(x, x)", simplified("mult(x, x)"));
+        assert_eq!("mult(pow(pi, 2), x)", simplified("mult(pi, x, pi)"));
+        assert_eq!("pow(x, 3)", simplified("mult(x, pow(x, 2))"));
+        assert_eq!("pow(x, 5)", simplified("mult(pow(x, 2), pow(x, 3))"));
+        assert_eq!("pow(x, 2)", simplified("div(pow(x, 3), x)"));
+        assert_eq!("1", simplified("div(x, x)"));
+        assert_eq!("div(1, x)", simplified("pow(x, -1)"));
+        assert_eq!("pow(x, 6)", simplified("pow(pow(x, 2), 3)"));
+        assert_eq!("abs(x)", simplified("pow(pow(x, 2), 0.5)"));
+        assert_eq!("abs(x)", simplified("pow(pow(x, 2), div(1, 2))"));
+        assert_eq!(
+            "pow(pow(x, 2), div(1, 3))",
+            simplified("pow(pow(x, 2), div(1, 3))")
+        );
+        assert_eq!("x", simplified("pow(sqrt(x), 2)"));
+        assert_eq!("abs(x)", simplified("sqrt(pow(x, 2))"));
+        assert_eq!("x", simplified("div(mult(2, x), 2)"));
+        assert_eq!("div(mult(3, x), y)", simplified("mult(3, div(x, y))"));
+    }
+
+    #[test]
+    fn trig_identities() {
+        assert_eq!("1", simplified("add(pow(sin(x), 2), pow(cos(x), 2))"));
+        assert_eq!(
+            "add(y, 3)",
+            simplified("add(mult(3, pow(cos(t), 2)), y, mult(3, pow(sin(t), 2)))")
+        );
+        assert_eq!("1", simplified("sub(pow(cosh(x), 2), pow(sinh(x), 2))"));
+        assert_eq!("tan(x)", simplified("div(sin(x), cos(x))"));
+        assert_eq!("pow(cos(x), 2)", simplified("sub(1, pow(sin(x), 2))"));
+        assert_eq!(
+            "mult(3, pow(sin(x), 2))",
+            simplified("sub(3, mult(3, pow(cos(x), 2)))")
+        );
+        assert_eq!("sin(mult(2, x))", simplified("mult(2, sin(x), cos(x))"));
+        assert_eq!(
+            "mult(3, sin(mult(2, y)))",
+            simplified("mult(cos(y), 6, sin(y))")
+        );
+        assert_eq!("mult(sin(x), cos(x))", simplified("mult(sin(x), cos(x))"));
+        assert_eq!("sub(0, sin(x))", simplified("sin(sub(0, x))"));
+        assert_eq!("cos(x)", simplified("cos(sub(0, x))"));
+        assert_eq!("0", simplified("sin(0)"));
+        assert_eq!("1", simplified("cos(mult(0, x))"));
+    }
+
+    #[test]
+    fn free_variables_in_order() {
+        let expr: Expr = parse_expr("add(y, let(z, 2, mult(x, z)), lambda((w), w), y)").unwrap();
+        assert_eq!(
+            vec!["y".to_string(), "x".to_string()],
+            free_variables(&expr)
+        );
+        let expr: Expr = parse_expr("ln(exp(x))").unwrap();
+        assert_eq!(vec!["x".to_string()], free_variables(&expr));
+    }
+
+    #[test]
+    fn simplify_builtin() {
+        let mut env = Env::new();
+        assert_eq!(
+            "lambda((x, y), add(mult(3, x), y))",
+            evaluate_prefix_expression_in_env("simplify(add(x, y, mult(2, x)))", &mut env)
+        );
+        // Bound variables and constants are not parameters
+        evaluate_prefix_expression_in_env("set(k, 2)", &mut env);
+        assert_eq!(
+            "lambda((x), mult(pow(pi, 2), x, k, x))",
+            evaluate_prefix_expression_in_env("simplify(mult(pi, x, k, pi, x))", &mut env)
+        );
+        assert_eq!(
+            "lambda((x), tan(x))",
+            evaluate_prefix_expression_in_env(
+                "simplify(lambda((x), div(mult(2, sin(x)), mult(cos(x), 2))))",
+                &mut env
+            )
+        );
+        assert_eq!(
+            "7",
+            evaluate_prefix_expression_in_env("simplify(add(3, 4))", &mut env)
+        );
+        // Expressions with unbound names are not run, so nothing in them happens yet
+        assert_eq!(
+            "lambda((y), add(set(q, 5), y))",
+            evaluate_prefix_expression_in_env("simplify(add(set(q, 5), y))", &mut env)
+        );
+        assert_eq!(
+            "q: Variable Is Not Defined",
+            evaluate_prefix_expression_in_env("q", &mut env)
+        );
+        // An unbound name inside a called function is an error, not a parameter
+        evaluate_prefix_expression_in_env("defn(h, (), add(zz, 1))", &mut env);
+        assert_eq!(
+            "zz: Variable Is Not Defined",
+            evaluate_prefix_expression_in_env("simplify(h())", &mut env)
+        );
+        // Called functions are not parameters either
+        assert_eq!(
+            "lambda((x), ln(exp(x)))",
+            evaluate_prefix_expression("simplify(ln(exp(x)))")
+        );
+        assert_eq!(
+            "(x, y) -> 2 * x * y + x - y * x",
+            crate::expression_util::calc_expr("simplify(x*y + x - y*x + x*y)")
+        );
+    }
+
+    #[test]
+    fn simplified_functions_of_matrices() {
+        let mut env = Env::new();
+        evaluate_prefix_expression_in_env("set(m, matrix(list(1, 2), list(3, 4)))", &mut env);
+        evaluate_prefix_expression_in_env("set(n, matrix(list(0, 1), list(1, 0)))", &mut env);
+        let commutator: &str = "lambda((a, b), sub(mult(a, b), mult(b, a)))";
+        let expected: String =
+            evaluate_prefix_expression_in_env(&format!("let(f, {commutator}, f(m, n))"), &mut env);
+        assert_eq!(
+            expected,
+            evaluate_prefix_expression_in_env(
+                &format!("let(f, simplify({commutator}), f(m, n))"),
+                &mut env
+            )
+        );
+        assert_eq!(
+            "5",
+            evaluate_prefix_expression_in_env(
+                "let(f, simplify(lambda((a), mult(a, a))), f(list(1, 2)))",
+                &mut env
+            )
+        );
+    }
+}
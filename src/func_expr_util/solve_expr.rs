@@ -0,0 +1,578 @@
+use std::f64::consts::TAU;
+
+use num_complex::Complex64;
+
+use super::{
+    expr_types::{Builtin, Expr},
+    simplify_expr::free_variables,
+};
+
+/// Steps Newton's method takes from a guess before giving up on it
+const MAX_NEWTON_STEPS: usize = 100;
+/// Times a Newton step is halved when it makes the function larger
+const MAX_HALVINGS: usize = 30;
+/// Enough for Brent's method to bisect any bracket down to adjacent floats
+const MAX_BRENT_STEPS: usize = 2000;
+const MAX_ABERTH_STEPS: usize = 1000;
+/// Relative distance within which estimates might be one repeated root
+const CLUSTER_RADIUS: f64 = 0.05;
+/// How close to 0, relative to the size of its terms, the polynomial has to be at a repeated root
+const REPEATED_ROOT_TOLERANCE: f64 = 1e-12;
+/// Highest power `polynomial_coefficients` will expand, so pow(x, 1000000) is not
+const MAX_DEGREE: usize = 256;
+
+/// A root of `f` by Newton's method from `guess`, where `f_df` gives the value and derivative.
+/// Steps that overshoot are halved. None when it does not settle on a root
+pub fn newton<E>(
+    f_df: &mut dyn FnMut(f64) -> Result<(f64, f64), E>,
+    guess: f64,
+) -> Result<Option<f64>, E> {
+    let mut x: f64 = guess;
+    let (mut fx, mut dfx) = f_df(x)?;
+    let mut previous_step: f64 = f64::NAN;
+    for _ in 0..MAX_NEWTON_STEPS {
+        if fx == 0.0 {
+            return Ok(Some(x));
+        } else if !fx.is_finite() || !dfx.is_finite() || dfx == 0.0 {
+            return Ok(None);
+        }
+        let newton_step: f64 = fx / dfx;
+        if newton_step.abs() <= 4.0 * f64::EPSILON * x.abs() {
+            return Ok(Some(x));
+        }
+        // At a root of multiplicity m each step is (m - 1) / m of the last one, which only
+        // creeps up on it. Taking m times the step gets back to converging quickly
+        let ratio: f64 = newton_step / previous_step;
+        let multiplicity: f64 = match ratio > 0.4 && ratio < 0.95 {
+            true => (1.0 / (1.0 - ratio)).round(),
+            false => 1.0,
+        };
+        previous_step = newton_step;
+        let mut step: f64 = multiplicity * newton_step;
+        let mut halvings: usize = 0;
+        loop {
+            let (f_next, df_next) = f_df(x - step)?;
+            if f_next.abs() < fx.abs() || halvings == MAX_HALVINGS {
+                (x, fx, dfx) = (x - step, f_next, df_next);
+                break;
+            }
+            step *= 0.5;
+            halvings += 1;
+        }
+    }
+    return Ok(None);
+}
+
+/// A root of `f` between `a` and `b` by Brent's method, which keeps the safety of bisection
+/// but usually converges as fast as the secant method. None when `f` has the same sign at both
+pub fn brent<E>(
+    f: &mut dyn FnMut(f64) -> Result<f64, E>,
+    a: f64,
+    b: f64,
+) -> Result<Option<f64>, E> {
+    let (mut a, mut b) = (a, b);
+    let (mut fa, mut fb) = (f(a)?, f(b)?);
+    if fa == 0.0 {
+        return Ok(Some(a));
+    } else if fb == 0.0 {
+        return Ok(Some(b));
+    } else if fa.is_nan() || fb.is_nan() || fa.signum() == fb.signum() {
+        return Ok(None);
+    }
+    let (mut c, mut fc) = (a, fa);
+    let mut step: f64 = b - a;
+    let mut previous_step: f64 = step;
+    for _ in 0..MAX_BRENT_STEPS {
+        if fb.signum() == fc.signum() {
+            (c, fc) = (a, fa);
+            step = b - a;
+            previous_step = step;
+        }
+        // Keeps b the best estimate, with the root between b and c
+        if fc.abs() < fb.abs() {
+            (a, fa) = (b, fb);
+            (b, fb) = (c, fc);
+            (c, fc) = (a, fa);
+        }
+        let tolerance: f64 = 2.0 * f64::EPSILON * b.abs() + f64::MIN_POSITIVE;
+        let half_bracket: f64 = 0.5 * (c - b);
+        if half_bracket.abs() <= tolerance || fb == 0.0 {
+            return Ok(Some(b));
+        }
+        if previous_step.abs() >= tolerance && fa.abs() > fb.abs() {
+            // Secant when there are two points, inverse quadratic interpolation with three
+            let s: f64 = fb / fa;
+            let (mut p, mut q): (f64, f64) = if a == c {
+                (2.0 * half_bracket * s, 1.0 - s)
+            } else {
+                let (q, r) = (fa / fc, fb / fc);
+                (
+                    s * (2.0 * half_bracket * q * (q - r) - (b - a) * (r - 1.0)),
+                    (q - 1.0) * (r - 1.0) * (s - 1.0),
+                )
+            };
+            if p > 0.0 {
+                q = -q;
+            } else {
+                p = -p;
+            }
+            let bisection_bound: f64 = 3.0 * half_bracket * q - (tolerance * q).abs();
+            if 2.0 * p < bisection_bound.min((previous_step * q).abs()) {
+                previous_step = step;
+                step = p / q;
+            } else {
+                step = half_bracket;
+                previous_step = step;
+            }
+        } else {
+            step = half_bracket;
+            previous_step = step;
+        }
+        (a, fa) = (b, fb);
+        b += if step.abs() > tolerance {
+            step
+        } else {
+            tolerance.copysign(half_bracket)
+        };
+        fb = f(b)?;
+    }
+    return Ok(Some(b));
+}
+
+/// The coefficients of `expr` as a polynomial in `var`, lowest power first.
+/// Parts without `var` are worked out by `constant`.
+/// None when `var` appears anywhere other than sums, products, division by a constant
+/// and whole powers
+pub fn polynomial_coefficients<E>(
+    expr: &Expr,
+    var: &str,
+    constant: &mut dyn FnMut(&Expr) -> Result<Complex64, E>,
+) -> Result<Option<Vec<Complex64>>, E> {
+    if !free_variables(expr).iter().any(|name| name == var) {
+        return Ok(Some(vec![constant(expr)?]));
+    }
+    let (name, args) = match expr {
+        Expr::Var { .. } => {
+            return Ok(Some(vec![
+                Complex64::new(0.0, 0.0),
+                Complex64::new(1.0, 0.0),
+            ]))
+        }
+        Expr::BuiltinFn { name, args } => (*name, args),
+        _ => return Ok(None),
+    };
+    let mut terms: Vec<Vec<Complex64>> = Vec::with_capacity(args.len());
+    match (name, args.as_slice()) {
+        (Builtin::Add | Builtin::Multiply, _) | (Builtin::Subtract, [_, _, ..]) => {
+            for arg in args {
+                match polynomial_coefficients(arg, var, constant)? {
+                    Some(coefficients) => terms.push(coefficients),
+                    None => return Ok(None),
+                }
+            }
+        }
+        (Builtin::Divide, [numerator, denominator]) => {
+            if free_variables(denominator).iter().any(|name| name == var) {
+                return Ok(None);
+            }
+            let scale: Complex64 = constant(denominator)?;
+            return Ok(polynomial_coefficients(numerator, var, constant)?
+                .filter(|_| scale != Complex64::new(0.0, 0.0))
+                .map(|coefficients| coefficients.iter().map(|c| c / scale).collect()));
+        }
+        (Builtin::Pow, [base, exponent]) => {
+            if free_variables(exponent).iter().any(|name| name == var) {
+                return Ok(None);
+            }
+            let power: Complex64 = constant(exponent)?;
+            let is_whole: bool = power.im == 0.0 && power.re.fract() == 0.0;
+            if !is_whole || !(0.0..=MAX_DEGREE as f64).contains(&power.re) {
+                return Ok(None);
+            }
+            let base: Vec<Complex64> = match polynomial_coefficients(base, var, constant)? {
+                Some(base) => base,
+                None => return Ok(None),
+            };
+            terms = vec![base; power.re as usize];
+            return Ok(multiply_all(&terms));
+        }
+        _ => return Ok(None),
+    }
+    return Ok(match name {
+        Builtin::Multiply => multiply_all(&terms),
+        Builtin::Subtract => Some(
+            terms[1..]
+                .iter()
+                .fold(terms[0].clone(), |difference, term| {
+                    add(&difference, term, -1.0)
+                }),
+        ),
+        _ => Some(
+            terms
+                .iter()
+                .fold(Vec::new(), |sum, term| add(&sum, term, 1.0)),
+        ),
+    });
+}
+
+/// `a + sign * b`
+fn add(a: &[Complex64], b: &[Complex64], sign: f64) -> Vec<Complex64> {
+    let mut sum: Vec<Complex64> = vec![Complex64::new(0.0, 0.0); a.len().max(b.len())];
+    for (index, c) in a.iter().enumerate() {
+        sum[index] += c;
+    }
+    for (index, c) in b.iter().enumerate() {
+        sum[index] += sign * c;
+    }
+    return sum;
+}
+
+/// None past `MAX_DEGREE`
+fn multiply_all(factors: &[Vec<Complex64>]) -> Option<Vec<Complex64>> {
+    let mut product: Vec<Complex64> = vec![Complex64::new(1.0, 0.0)];
+    for factor in factors {
+        if product.len() + factor.len() > MAX_DEGREE + 2 {
+            return None;
+        }
+        let mut next: Vec<Complex64> =
+            vec![Complex64::new(0.0, 0.0); product.len() + factor.len() - 1];
+        for (i, a) in product.iter().enumerate() {
+            for (j, b) in factor.iter().enumerate() {
+                next[i + j] += a * b;
+            }
+        }
+        product = next;
+    }
+    return Some(product);
+}
+
+/// Every root of the polynomial with these coefficients, lowest power first, repeated as often
+/// as they occur, by the Aberth-Ehrlich method. Zero coefficients on the highest powers are
+/// ignored
+pub fn polynomial_roots(coefficients: &[Complex64]) -> Vec<Complex64> {
+    let degree: usize = match coefficients
+        .iter()
+        .rposition(|c| *c != Complex64::new(0.0, 0.0))
+    {
+        Some(degree) => degree,
+        None => return Vec::new(),
+    };
+    // Factors of x are exact roots at 0
+    let zeros: usize = coefficients
+        .iter()
+        .position(|c| *c != Complex64::new(0.0, 0.0))
+        .unwrap();
+    let coefficients: &[Complex64] = &coefficients[zeros..=degree];
+    let mut roots: Vec<Complex64> = vec![Complex64::new(0.0, 0.0); zeros];
+    let degree: usize = coefficients.len() - 1;
+    if degree == 0 {
+        return roots;
+    } else if degree == 1 {
+        roots.push(-coefficients[0] / coefficients[1]);
+        return roots;
+    }
+
+    // Starts on a circle as large as the geometric mean of the roots, turned off the real axis
+    let radius: f64 = (coefficients[0] / coefficients[degree])
+        .norm()
+        .powf(1.0 / degree as f64);
+    let mut estimates: Vec<Complex64> = (0..degree)
+        .map(|k| Complex64::from_polar(radius, TAU * k as f64 / degree as f64 + 0.4))
+        .collect();
+    for _ in 0..MAX_ABERTH_STEPS {
+        let mut converged: bool = true;
+        for k in 0..degree {
+            let z: Complex64 = estimates[k];
+            let (p, dp) = evaluate(coefficients, z);
+            if p == Complex64::new(0.0, 0.0) {
+                continue;
+            }
+            let newton: Complex64 = p / dp;
+            let repulsion: Complex64 = estimates
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != k)
+                .map(|(_, other)| 1.0 / (z - other))
+                .sum();
+            let correction: Complex64 = newton / (1.0 - newton * repulsion);
+            if !correction.is_finite() {
+                continue;
+            }
+            estimates[k] = z - correction;
+            if correction.norm() > 4.0 * f64::EPSILON * z.norm() {
+                converged = false;
+            }
+        }
+        if converged {
+            break;
+        }
+    }
+
+    // A repeated root comes out as a small ring of estimates around it
+    let mut clustered: Vec<bool> = vec![false; degree];
+    for k in 0..degree {
+        let center: Complex64 = estimates[k];
+        let members: Vec<usize> = (k..degree)
+            .filter(|j| {
+                !clustered[*j]
+                    && (estimates[*j] - center).norm() <= CLUSTER_RADIUS * center.norm().max(1.0)
+            })
+            .collect();
+        let ring: Vec<Complex64> = members.iter().map(|j| estimates[*j]).collect();
+        if let Some(root) = repeated_root(coefficients, &ring) {
+            for j in members {
+                estimates[j] = root;
+                clustered[j] = true;
+            }
+        }
+    }
+    // Real and imaginary roots come out with a trace of the other part
+    for root in &mut estimates {
+        let noise: f64 = 1e-12 * root.norm();
+        if root.re.abs() <= noise {
+            root.re = 0.0;
+        } else if root.im.abs() <= noise {
+            root.im = 0.0;
+        }
+    }
+    roots.extend(estimates);
+    return roots;
+}
+
+/// The root a ring of estimates is around, if they really are one root repeated.
+/// A root of multiplicity m is a simple root of the (m - 1)th derivative, where Newton's method
+/// finds it accurately, and every lower derivative is 0 there up to rounding
+fn repeated_root(coefficients: &[Complex64], ring: &[Complex64]) -> Option<Complex64> {
+    if ring.len() < 2 {
+        return None;
+    }
+    let mut derivatives: Vec<Vec<Complex64>> = vec![coefficients.to_vec()];
+    for order in 1..ring.len() {
+        derivatives.push(differentiate(&derivatives[order - 1]));
+    }
+    let mut root: Complex64 = ring.iter().sum::<Complex64>() / ring.len() as f64;
+    let last: &[Complex64] = &derivatives[ring.len() - 1];
+    for _ in 0..MAX_NEWTON_STEPS {
+        let (p, dp) = evaluate(last, root);
+        let step: Complex64 = p / dp;
+        if !step.is_finite() {
+            break;
+        }
+        root -= step;
+        if step.norm() <= 4.0 * f64::EPSILON * root.norm() {
+            break;
+        }
+    }
+    let is_root = |derivative: &Vec<Complex64>| -> bool {
+        let scale: f64 = derivative
+            .iter()
+            .rev()
+            .fold(0.0, |sum, c| sum * root.norm() + c.norm());
+        return evaluate(derivative, root).0.norm() <= REPEATED_ROOT_TOLERANCE * scale;
+    };
+    return match derivatives[..ring.len() - 1].iter().all(is_root) {
+        true => Some(root),
+        false => None,
+    };
+}
+
+fn differentiate(coefficients: &[Complex64]) -> Vec<Complex64> {
+    return coefficients
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(power, c)| c * power as f64)
+        .collect();
+}
+
+/// The polynomial and its derivative at `z` by Horner's method
+fn evaluate(coefficients: &[Complex64], z: Complex64) -> (Complex64, Complex64) {
+    let mut p: Complex64 = Complex64::new(0.0, 0.0);
+    let mut dp: Complex64 = Complex64::new(0.0, 0.0);
+    for c in coefficients.iter().rev() {
+        dp = dp * z + p;
+        p = p * z + c;
+    }
+    return (p, dp);
+}
+
+#[cfg(test)]
+mod solve_tests {
+    use super::*;
+    use crate::func_expr_util::{
+        evaluate_prefix_expression, evaluate_prefix_expression_in_env, expr_types::Env,
+        parse_expr::parse_expr,
+    };
+
+    fn sorted(mut roots: Vec<Complex64>) -> Vec<Complex64> {
+        roots.sort_by(|a, b| a.re.total_cmp(&b.re).then(a.im.total_cmp(&b.im)));
+        return roots;
+    }
+
+    fn real(coefficients: &[f64]) -> Vec<Complex64> {
+        return coefficients
+            .iter()
+            .map(|c| Complex64::new(*c, 0.0))
+            .collect();
+    }
+
+    #[test]
+    fn newton_and_brent() {
+        let root: Option<f64> = newton::<()>(&mut |x| Ok((x * x - 2.0, 2.0 * x)), 1.0).unwrap();
+        assert_eq!(Some(2f64.sqrt()), root);
+        // The first step overshoots far past the root without halving
+        let root: Option<f64> =
+            newton::<()>(&mut |x| Ok((x.atan(), 1.0 / (1.0 + x * x))), 3.0).unwrap();
+        assert_eq!(Some(0.0), root);
+        assert_eq!(
+            None,
+            newton::<()>(&mut |x| Ok((x * x + 1.0, 2.0 * x)), 0.5).unwrap()
+        );
+        // Double and triple roots, where plain Newton steps only halve or shrink by a third
+        assert_eq!(
+            Some(0.0),
+            newton::<()>(&mut |x| Ok((x * x, 2.0 * x)), 1.0).unwrap()
+        );
+        assert_eq!(
+            Some(0.0),
+            newton::<()>(&mut |x| Ok((x.powi(3), 3.0 * x * x)), -2.0).unwrap()
+        );
+        let root: f64 = newton::<()>(
+            &mut |x| Ok(((x - 1.5).powi(2) * (x + 2.0), (x - 1.5) * (3.0 * x + 2.5))),
+            4.0,
+        )
+        .unwrap()
+        .unwrap();
+        assert!((root - 1.5).abs() < 1e-7);
+
+        let root: f64 = brent::<()>(&mut |x| Ok(x.cos() - x), 0.0, 1.0)
+            .unwrap()
+            .unwrap();
+        assert!((root - 0.739_085_133_215_160_6).abs() < 1e-15);
+        let root: f64 = brent::<()>(&mut |x| Ok(x.powi(3)), -1.0, 2.0)
+            .unwrap()
+            .unwrap();
+        assert!(root.abs() < 1e-100);
+        assert_eq!(
+            None,
+            brent::<()>(&mut |x| Ok(x * x + 1.0), -1.0, 1.0).unwrap()
+        );
+    }
+
+    #[test]
+    fn roots_of_polynomials() {
+        // (x - 1)(x - 2)(x + 3)
+        let roots: Vec<Complex64> = sorted(polynomial_roots(&real(&[6.0, -7.0, 0.0, 1.0])));
+        for (root, expected) in roots.iter().zip([-3.0, 1.0, 2.0]) {
+            assert_eq!(0.0, root.im);
+            assert!((root.re - expected).abs() < 1e-14);
+        }
+        // x^4 + x^2 has a double root at 0 and the pair +-i
+        let roots: Vec<Complex64> = sorted(polynomial_roots(&real(&[0.0, 0.0, 1.0, 0.0, 1.0])));
+        assert_eq!(Complex64::new(0.0, 0.0), roots[1]);
+        assert!((roots[0] - Complex64::new(0.0, -1.0)).norm() < 1e-14);
+        assert!((roots[3] - Complex64::new(0.0, 1.0)).norm() < 1e-14);
+        assert_eq!(
+            vec![Complex64::new(-2.5, 0.0)],
+            polynomial_roots(&real(&[5.0, 2.0, 0.0]))
+        );
+        assert!(polynomial_roots(&real(&[3.0])).is_empty());
+    }
+
+    #[test]
+    fn coefficients_of_expressions() {
+        let coefficients = |source: &str| -> Option<Vec<f64>> {
+            let expr: Expr = parse_expr(source).unwrap();
+            return polynomial_coefficients::<()>(&expr, "x", &mut |constant| match constant {
+                Expr::Integer { i } => Ok(Complex64::new(*i as f64, 0.0)),
+                Expr::Var { .. } => Ok(Complex64::new(10.0, 0.0)),
+                _ => Ok(Complex64::new(f64::NAN, 0.0)),
+            })
+            .unwrap()
+            .map(|coefficients| coefficients.iter().map(|c| c.re).collect());
+        };
+        assert_eq!(Some(vec![1.0, 2.0, 1.0]), coefficients("pow(add(x, 1), 2)"));
+        assert_eq!(
+            Some(vec![-10.0, 0.0, 0.5]),
+            coefficients("sub(div(mult(x, x), 2), a)")
+        );
+        assert_eq!(
+            Some(vec![6.0, 11.0, -6.0, 1.0]),
+            coefficients("sub(pow(x, 3), mult(6, pow(x, 2)), mult(-11, x), -6)")
+        );
+        assert_eq!(None, coefficients("sin(x)"));
+        assert_eq!(None, coefficients("div(1, x)"));
+        assert_eq!(None, coefficients("pow(x, -1)"));
+    }
+
+    #[test]
+    fn equations() {
+        assert_eq!(
+            "[-2, 2]",
+            evaluate_prefix_expression("solve(pow(x, 2), 4, x)")
+        );
+        assert_eq!(
+            "[-1, -i, i]",
+            evaluate_prefix_expression("root(add(pow(x, 3), pow(x, 2), x, 1), x)")
+        );
+        assert_eq!(
+            "[-2, 1, 1]",
+            crate::expression_util::calc_expr("solve((x - 1)^2 (x + 2), 0, x)")
+        );
+        assert_eq!(
+            "3.141592653589793",
+            evaluate_prefix_expression("root(sin(x), x, 3)")
+        );
+        assert_eq!(
+            "0.7390851332151607",
+            crate::expression_util::calc_expr("solve(cos(t), t, t, 0, 1)")
+        );
+        // Repeated roots
+        assert_eq!("0", evaluate_prefix_expression("root(pow(x, 2), x, 1)"));
+        assert_eq!("0", evaluate_prefix_expression("root(pow(x, 3), x, 1)"));
+        assert_eq!(
+            "true",
+            evaluate_prefix_expression("lt(abs(sub(root(pow(sub(x, 2), 2), x, 5), 2)), 1e-7)")
+        );
+        // max has no derivative, so Newton's method falls back on a finite difference
+        assert_eq!(
+            "1",
+            evaluate_prefix_expression("root(sub(max(x, 0), 1), x, 3)")
+        );
+        let mut env = Env::new();
+        evaluate_prefix_expression_in_env("defn(f, (x), sub(pow(e, x), 2))", &mut env);
+        assert_eq!(
+            "true",
+            evaluate_prefix_expression_in_env(
+                "lt(abs(sub(root(f(x), x, 0), ln(2))), 1e-15)",
+                &mut env
+            )
+        );
+        // Two arguments are still a linear system
+        assert_eq!(
+            "[1, 1]",
+            evaluate_prefix_expression("solve(matrix(list(2, 0), list(0, 4)), list(2, 4))")
+        );
+
+        assert_eq!(
+            "root: Arg 1 Is Not a Polynomial, So Needs a Starting Guess",
+            evaluate_prefix_expression("root(sin(x), x)")
+        );
+        assert_eq!(
+            "solve: Arg 1 Holds for Every Value of the Variable",
+            evaluate_prefix_expression("solve(mult(2, x), add(x, x), x)")
+        );
+        assert_eq!(
+            "root: Arg 3 Did Not Lead to a Root",
+            evaluate_prefix_expression("root(add(pow(x, 2), 1), x, 1)")
+        );
+        assert_eq!(
+            "root: Arg 3 And the Next Arg Must Bracket a Sign Change",
+            evaluate_prefix_expression("root(sin(x), x, 1, 2)")
+        );
+        assert_eq!(
+            "solve: Expected 3 to 5 Args, Found 6",
+            evaluate_prefix_expression("solve(x, 1, x, 0, 1, 2)")
+        );
+    }
+}
@@ -0,0 +1,702 @@
+use std::{collections::HashMap, fmt};
+
+use super::{
+    diff_expr::substitute, expr_types::*, interp_expr::interp_expr_with_env,
+    print_expr::print_prefix,
+};
+
+/// One reduction while evaluating, like `mult(3, 4)` becoming `12`.
+/// Values appear as the expressions that read as them, see `Value::as_expr`
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceStep {
+    /// The part that was worked out
+    pub reduced: Expr,
+    /// What it became
+    pub result: Expr,
+    /// The whole expression after the step
+    pub expression: Expr,
+}
+
+/// Every step from an expression to its value, or as far as evaluation got before an error
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trace {
+    pub expression: Expr,
+    pub steps: Vec<TraceStep>,
+    pub result: Result<Value, EvalError>,
+    /// What `Display` writes the expressions in
+    pub syntax: Syntax,
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", print_prefix(&self.expression, self.syntax))?;
+        for step in &self.steps {
+            write!(f, "\n= {}", print_prefix(&step.expression, self.syntax))?;
+        }
+        if let Err(e) = &self.result {
+            write!(f, "\n{}", e)?;
+        }
+        return Ok(());
+    }
+}
+
+/// How many steps a trace keeps, each one holds the whole expression.
+/// Past this the rest is worked out as one step
+pub const MAX_TRACE_STEPS: usize = 256;
+
+/// Evaluates `expr` one reduction at a time, innermost and leftmost first, for display in
+/// `syntax`. Builtins that take expressions rather than values, like diff, and `set`, `defn`
+/// and `lambda` are one step each. `if` works out its condition, then becomes the branch it
+/// picks. `let` and calls to user functions work out their values, then become their body with
+/// the values in place of the names
+pub fn trace_expr(expr: &Expr, env: &mut Env, config: &EvalConfig, syntax: Syntax) -> Trace {
+    let mut tracer = Tracer { env, config };
+    let mut node: Node = tracer.node(expr);
+    let mut trace = Trace {
+        expression: node.as_expr(),
+        steps: Vec::new(),
+        result: Ok(Value::BoolV { bool_v: false }),
+        syntax,
+    };
+    loop {
+        if let Node::Done(value) = node {
+            trace.result = Ok(value);
+            return trace;
+        }
+        if trace.steps.len() == MAX_TRACE_STEPS {
+            let reduced: Expr = node.as_expr();
+            trace.result = tracer.finish(node);
+            if let Ok(value) = &trace.result {
+                trace.steps.push(TraceStep {
+                    reduced,
+                    result: value.as_expr(),
+                    expression: value.as_expr(),
+                });
+            }
+            return trace;
+        }
+        match tracer.step(&mut node, &Shown::new()) {
+            Ok(Some((reduced, result))) => {
+                let expression: Expr = node.as_expr();
+                // Looking up a function or building a list reads the same before and after
+                if trace
+                    .steps
+                    .last()
+                    .map_or(&trace.expression, |step| &step.expression)
+                    != &expression
+                {
+                    trace.steps.push(TraceStep {
+                        reduced,
+                        result,
+                        expression,
+                    });
+                }
+            }
+            Ok(None) => unreachable!("only values have nothing left to reduce"),
+            Err(e) => {
+                trace.result = Err(e);
+                return trace;
+            }
+        }
+    }
+}
+
+/// An expression partway through evaluation
+enum Node {
+    Done(Value),
+    /// Worked out in a single step
+    Whole(Expr),
+    Builtin {
+        name: Builtin,
+        args: Vec<Node>,
+    },
+    /// The condition is reduced before a branch is picked
+    If {
+        condition: Box<Node>,
+        then: Expr,
+        otherwise: Expr,
+    },
+    Call {
+        func: Expr,
+        args: Vec<Node>,
+    },
+    /// The value is reduced before the body is entered
+    Let {
+        name: String,
+        value: Box<Node>,
+        body: Expr,
+    },
+    /// The body of a `let` or a user function call. `scope` is pushed while it is reduced,
+    /// on top of `captured` in place of the caller's local scopes for a call
+    Scope {
+        captured: Option<Vec<Scope>>,
+        scope: Scope,
+        body: Box<Node>,
+    },
+}
+
+/// Names the already evaluated arguments of a reduction are bound to, no variable can be
+/// written with a `#`
+fn temp_name(index: usize) -> String {
+    return format!("#{}", index);
+}
+
+fn temp_args(count: usize) -> Vec<Expr> {
+    return (0..count)
+        .map(|i| Expr::Var { name: temp_name(i) })
+        .collect();
+}
+
+/// What names read as in the displayed expression, the values of enclosing `let`s and parameters
+type Shown = HashMap<String, Expr>;
+
+fn substitute_shown(expr: &Expr, shown: &Shown) -> Expr {
+    let bindings: HashMap<&str, &Expr> = shown
+        .iter()
+        .map(|(name, value)| (name.as_str(), value))
+        .collect();
+    return substitute(expr, &bindings);
+}
+
+impl Node {
+    fn as_expr(&self) -> Expr {
+        return self.shown(&Shown::new());
+    }
+
+    /// The expression with the bound values in place of their names
+    fn shown(&self, shown: &Shown) -> Expr {
+        match self {
+            Node::Done(value) => value.as_expr(),
+            Node::Whole(expr) => substitute_shown(expr, shown),
+            Node::Builtin { name, args } => Expr::BuiltinFn {
+                name: *name,
+                args: args.iter().map(|arg| arg.shown(shown)).collect(),
+            },
+            Node::If {
+                condition,
+                then,
+                otherwise,
+            } => Expr::BuiltinFn {
+                name: Builtin::If,
+                args: vec![
+                    condition.shown(shown),
+                    substitute_shown(then, shown),
+                    substitute_shown(otherwise, shown),
+                ],
+            },
+            Node::Call { func, args } => Expr::Call {
+                func: Box::new(substitute_shown(func, shown)),
+                args: args.iter().map(|arg| arg.shown(shown)).collect(),
+            },
+            Node::Let { name, value, body } => {
+                let mut inner: Shown = shown.clone();
+                inner.remove(name);
+                Expr::Let {
+                    name: name.clone(),
+                    value: Box::new(value.shown(shown)),
+                    body: Box::new(substitute_shown(body, &inner)),
+                }
+            }
+            Node::Scope {
+                captured,
+                scope,
+                body,
+            } => body.shown(&shown_in_scope(shown, captured.is_some(), scope)),
+        }
+    }
+}
+
+/// What names read as inside a scope. A function body starts afresh, since the caller's names
+/// are not visible in it. Functions keep their names, a `lambda` can't be called where it is written
+fn shown_in_scope(outer: &Shown, is_call: bool, scope: &Scope) -> Shown {
+    let mut shown: Shown = if is_call { Shown::new() } else { outer.clone() };
+    for (name, value) in scope {
+        if let Value::FnV { .. } = value {
+            shown.remove(name);
+        } else {
+            shown.insert(name.clone(), value.as_expr());
+        }
+    }
+    return shown;
+}
+
+struct Tracer<'a> {
+    env: &'a mut Env,
+    config: &'a EvalConfig,
+}
+
+impl Tracer<'_> {
+    fn node(&mut self, expr: &Expr) -> Node {
+        match expr {
+            Expr::Integer { .. }
+            | Expr::BigInteger { .. }
+            | Expr::Float { .. }
+            | Expr::Imaginary { .. }
+            | Expr::Boolean { .. } => match interp_expr_with_env(expr, self.env, self.config) {
+                Ok(value) => Node::Done(value),
+                Err(_) => Node::Whole(expr.clone()),
+            },
+            Expr::BuiltinFn { name, args } => match (name, args.as_slice()) {
+                (Builtin::If, [condition, then, otherwise]) => Node::If {
+                    condition: Box::new(self.node(condition)),
+                    then: then.clone(),
+                    otherwise: otherwise.clone(),
+                },
+                (
+                    Builtin::If
+                    | Builtin::And
+                    | Builtin::Or
+                    | Builtin::Diff
+                    | Builtin::Simplify
+                    | Builtin::Integrate
+                    | Builtin::Root,
+                    _,
+                ) => Node::Whole(expr.clone()),
+                // Equations, unlike linear systems, are solved from their expressions
+                (Builtin::Solve, [_, _, _, ..]) => Node::Whole(expr.clone()),
+                _ => Node::Builtin {
+                    name: *name,
+                    args: args.iter().map(|arg| self.node(arg)).collect(),
+                },
+            },
+            Expr::Call { func, args } => Node::Call {
+                func: *func.clone(),
+                args: args.iter().map(|arg| self.node(arg)).collect(),
+            },
+            Expr::Let { name, value, body } => Node::Let {
+                name: name.clone(),
+                value: Box::new(self.node(value)),
+                body: *body.clone(),
+            },
+            Expr::Var { .. } | Expr::Set { .. } | Expr::Defn { .. } | Expr::Lambda { .. } => {
+                Node::Whole(expr.clone())
+            }
+        }
+    }
+
+    /// Makes the innermost, leftmost reduction in `node`, returning what was reduced and what to,
+    /// as displayed with `shown`. None when `node` is already a value
+    fn step(&mut self, node: &mut Node, shown: &Shown) -> Result<Option<(Expr, Expr)>, EvalError> {
+        let reduced: Expr = node.shown(shown);
+        let next: Node = match node {
+            Node::Done(_) => return Ok(None),
+            Node::Whole(expr) => Node::Done(interp_expr_with_env(expr, self.env, self.config)?),
+            Node::Builtin { name, args } => {
+                for arg in args.iter_mut() {
+                    if let Some(step) = self.step(arg, shown)? {
+                        return Ok(Some(step));
+                    }
+                }
+                let expr: Expr = Expr::BuiltinFn {
+                    name: *name,
+                    args: temp_args(args.len()),
+                };
+                Node::Done(self.apply(&expr, args)?)
+            }
+            Node::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                if let Some(step) = self.step(condition, shown)? {
+                    return Ok(Some(step));
+                }
+                match &**condition {
+                    Node::Done(Value::BoolV { bool_v: true }) => self.node(&then.clone()),
+                    Node::Done(Value::BoolV { bool_v: false }) => self.node(&otherwise.clone()),
+                    // Not a boolean, so this reports the error
+                    _ => Node::Done(self.apply_if(then, otherwise, condition)?),
+                }
+            }
+            Node::Call { func, args } => {
+                for arg in args.iter_mut() {
+                    if let Some(step) = self.step(arg, shown)? {
+                        return Ok(Some(step));
+                    }
+                }
+                match self.enter(func, args) {
+                    Some(scope) => scope,
+                    // Not a function that can be entered, so this reports the error
+                    None => {
+                        let expr: Expr = Expr::Call {
+                            func: Box::new(func.clone()),
+                            args: temp_args(args.len()),
+                        };
+                        Node::Done(self.apply(&expr, args)?)
+                    }
+                }
+            }
+            Node::Let { name, value, body } => {
+                if let Some(step) = self.step(value, shown)? {
+                    return Ok(Some(step));
+                }
+                let Node::Done(value) = &**value else {
+                    unreachable!("a reduced value is done");
+                };
+                Node::Scope {
+                    captured: None,
+                    scope: Scope::from([(name.clone(), value.clone())]),
+                    body: Box::new(self.node(&body.clone())),
+                }
+            }
+            Node::Scope {
+                captured,
+                scope,
+                body,
+            } => {
+                if let Node::Done(value) = &**body {
+                    Node::Done(value.clone())
+                } else {
+                    let inner: Shown = shown_in_scope(shown, captured.is_some(), scope);
+                    let caller_locals: Option<Vec<Scope>> =
+                        captured.take().map(|captured| self.enter_locals(captured));
+                    self.env.push_scope();
+                    for (bound, value) in scope.drain() {
+                        self.env.define(&bound, value);
+                    }
+                    let stepped = self.step(body, &inner);
+                    // Keeps what `set` and `defn` did in the body
+                    *scope = self.env.pop_scope().unwrap_or_default();
+                    *captured = caller_locals.map(|caller| self.leave_locals(caller));
+                    return stepped;
+                }
+            }
+        };
+        *node = next;
+        return Ok(Some((reduced, node.shown(shown))));
+    }
+
+    /// The body of the called function with the argument values bound to its parameters.
+    /// None when `func` is not a function or the call would fail
+    fn enter(&mut self, func: &Expr, args: &[Node]) -> Option<Node> {
+        let Ok(Value::FnV { fn_v }) = interp_expr_with_env(func, self.env, self.config) else {
+            return None;
+        };
+        if fn_v.params.len() != args.len() || self.env.call_depth() >= self.config.max_call_depth {
+            return None;
+        }
+        let mut scope: Scope = Scope::new();
+        if let Some(name) = &fn_v.name {
+            scope.insert(name.clone(), Value::FnV { fn_v: fn_v.clone() });
+        }
+        for (param, arg) in fn_v.params.iter().zip(args) {
+            if let Node::Done(value) = arg {
+                scope.insert(param.clone(), value.clone());
+            }
+        }
+        return Some(Node::Scope {
+            captured: Some(fn_v.captured.clone()),
+            scope,
+            body: Box::new(self.node(&fn_v.body)),
+        });
+    }
+
+    /// Puts a called function's local scopes in place of the caller's, returning the caller's
+    fn enter_locals(&mut self, captured: Vec<Scope>) -> Vec<Scope> {
+        self.env.set_call_depth(self.env.call_depth() + 1);
+        return self.env.swap_locals(captured);
+    }
+
+    /// Puts the caller's local scopes back, returning the called function's
+    fn leave_locals(&mut self, caller: Vec<Scope>) -> Vec<Scope> {
+        self.env.set_call_depth(self.env.call_depth() - 1);
+        return self.env.swap_locals(caller);
+    }
+
+    /// Works out the rest of `node` at once, without keeping any steps
+    fn finish(&mut self, node: Node) -> Result<Value, EvalError> {
+        match node {
+            Node::Done(value) => Ok(value),
+            Node::Whole(expr) => interp_expr_with_env(&expr, self.env, self.config),
+            Node::Builtin { name, args } => {
+                let args: Vec<Node> = self.finish_all(args)?;
+                let expr: Expr = Expr::BuiltinFn {
+                    name,
+                    args: temp_args(args.len()),
+                };
+                self.apply(&expr, &args)
+            }
+            Node::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                let condition: Node = Node::Done(self.finish(*condition)?);
+                self.apply_if(&then, &otherwise, &condition)
+            }
+            Node::Call { func, args } => {
+                let args: Vec<Node> = self.finish_all(args)?;
+                let expr: Expr = Expr::Call {
+                    func: Box::new(func),
+                    args: temp_args(args.len()),
+                };
+                self.apply(&expr, &args)
+            }
+            Node::Let { name, value, body } => {
+                let value: Value = self.finish(*value)?;
+                self.env.push_scope();
+                self.env.define(&name, value);
+                let result: Result<Value, EvalError> =
+                    interp_expr_with_env(&body, self.env, self.config);
+                self.env.pop_scope();
+                result
+            }
+            Node::Scope {
+                captured,
+                scope,
+                body,
+                ..
+            } => {
+                let caller_locals: Option<Vec<Scope>> =
+                    captured.map(|captured| self.enter_locals(captured));
+                self.env.push_scope();
+                for (bound, value) in scope {
+                    self.env.define(&bound, value);
+                }
+                let result: Result<Value, EvalError> = self.finish(*body);
+                self.env.pop_scope();
+                if let Some(caller) = caller_locals {
+                    self.leave_locals(caller);
+                }
+                result
+            }
+        }
+    }
+
+    fn finish_all(&mut self, nodes: Vec<Node>) -> Result<Vec<Node>, EvalError> {
+        return nodes
+            .into_iter()
+            .map(|node| self.finish(node).map(Node::Done))
+            .collect();
+    }
+
+    /// `if` on an evaluated condition, reporting the error when it is not a boolean
+    fn apply_if(
+        &mut self,
+        then: &Expr,
+        otherwise: &Expr,
+        condition: &Node,
+    ) -> Result<Value, EvalError> {
+        let expr: Expr = Expr::BuiltinFn {
+            name: Builtin::If,
+            args: vec![
+                Expr::Var { name: temp_name(0) },
+                then.clone(),
+                otherwise.clone(),
+            ],
+        };
+        return self.apply(&expr, std::slice::from_ref(condition));
+    }
+
+    /// Evaluates `expr` with the values of `args` bound to the temporary names
+    fn apply(&mut self, expr: &Expr, args: &[Node]) -> Result<Value, EvalError> {
+        self.env.push_scope();
+        for (index, arg) in args.iter().enumerate() {
+            if let Node::Done(value) = arg {
+                self.env.define(&temp_name(index), value.clone());
+            }
+        }
+        let result: Result<Value, EvalError> = interp_expr_with_env(expr, self.env, self.config);
+        self.env.pop_scope();
+        return result;
+    }
+}
+
+#[cfg(test)]
+mod trace_tests {
+    use super::*;
+    use crate::func_expr_util::{
+        evaluate_prefix_expression_in_env,
+        parse_expr::{parse_expr, parse_expr_with_syntax},
+        trace_prefix_expression,
+    };
+
+    fn steps(source: &str, syntax: Syntax) -> Vec<String> {
+        let expr: Expr = parse_expr_with_syntax(source, syntax).unwrap();
+        let trace: Trace = trace_expr(&expr, &mut Env::new(), &EvalConfig::default(), syntax);
+        let mut lines: Vec<String> = vec![print_prefix(&trace.expression, syntax)];
+        lines.extend(
+            trace
+                .steps
+                .iter()
+                .map(|step| print_prefix(&step.expression, syntax)),
+        );
+        return lines;
+    }
+
+    #[test]
+    fn reductions_in_order() {
+        assert_eq!(
+            vec!["add(2, mult(3, 4))", "add(2, 12)", "14"],
+            steps("add(2, mult(3, 4))", Syntax::Call)
+        );
+        assert_eq!(
+            vec!["(1 + 2) * (3 + 4)", "3 * (3 + 4)", "3 * 7", "21"],
+            steps("(1 + 2) * (3 + 4)", Syntax::Infix)
+        );
+        // Constants are looked up as a step, like variables
+        assert_eq!(
+            vec![
+                "mult(2, pi)",
+                "mult(2, 3.141592653589793)",
+                "6.283185307179586"
+            ],
+            steps("mult(2, pi)", Syntax::Call)
+        );
+        // Exact fractions read as a division
+        assert_eq!(
+            vec!["div(1, add(1, 2))", "div(1, 3)"],
+            steps("div(1, add(1, 2))", Syntax::Call)
+        );
+    }
+
+    #[test]
+    fn branches_and_functions() {
+        assert_eq!(
+            vec![
+                "if(lt(1, 2), add(1, 1), div(1, 0))",
+                "if(true, add(1, 1), div(1, 0))",
+                "add(1, 1)",
+                "2"
+            ],
+            steps("if(lt(1, 2), add(1, 1), div(1, 0))", Syntax::Call)
+        );
+        let mut env = Env::new();
+        let config = EvalConfig::default();
+        let defn: Expr = parse_expr("defn(sq, (x), mult(x, x))").unwrap();
+        interp_expr_with_env(&defn, &mut env, &config).unwrap();
+        let call: Expr = parse_expr("sq(add(1, 2))").unwrap();
+        let trace: Trace = trace_expr(&call, &mut env, &config, Syntax::Call);
+        assert_eq!(
+            vec![
+                TraceStep {
+                    reduced: parse_expr("add(1, 2)").unwrap(),
+                    result: Expr::Integer { i: 3 },
+                    expression: parse_expr("sq(3)").unwrap(),
+                },
+                TraceStep {
+                    reduced: parse_expr("sq(3)").unwrap(),
+                    result: parse_expr("mult(3, 3)").unwrap(),
+                    expression: parse_expr("mult(3, 3)").unwrap(),
+                },
+                TraceStep {
+                    reduced: parse_expr("mult(3, 3)").unwrap(),
+                    result: Expr::Integer { i: 9 },
+                    expression: Expr::Integer { i: 9 },
+                },
+            ],
+            trace.steps
+        );
+        assert_eq!(Ok(Value::IntV { i_v: 9 }), trace.result);
+    }
+
+    #[test]
+    fn inside_let_and_calls() {
+        assert_eq!(
+            vec![
+                "let(x, add(1, 2), mult(x, add(x, 1)))",
+                "let(x, 3, mult(x, add(x, 1)))",
+                "mult(3, add(3, 1))",
+                "mult(3, 4)",
+                "12"
+            ],
+            steps("let(x, add(1, 2), mult(x, add(x, 1)))", Syntax::Call)
+        );
+        assert_eq!(
+            vec![
+                "let(f, lambda((x, y), sub(x, y)), f(5, 2))",
+                "f(5, 2)",
+                "sub(5, 2)",
+                "3"
+            ],
+            steps("let(f, lambda((x, y), sub(x, y)), f(5, 2))", Syntax::Call)
+        );
+        // An inner `let` of the same name keeps its own value in the shown steps
+        assert_eq!(
+            vec![
+                "let(x, 1, add(x, let(x, 5, mult(x, 2))))",
+                "add(1, let(x, 5, mult(x, 2)))",
+                "add(1, mult(5, 2))",
+                "add(1, 10)",
+                "11"
+            ],
+            steps("let(x, 1, add(x, let(x, 5, mult(x, 2))))", Syntax::Call)
+        );
+        // A session variable is not shadowed by a caller's `let` of the same name
+        let mut env = Env::new();
+        let config = EvalConfig::default();
+        for line in ["set(k, 10)", "defn(addk, (y), add(k, y))"] {
+            interp_expr_with_env(&parse_expr(line).unwrap(), &mut env, &config).unwrap();
+        }
+        let expr: Expr = parse_expr("let(k, 1, addk(k))").unwrap();
+        let trace: Trace = trace_expr(&expr, &mut env, &config, Syntax::Call);
+        assert_eq!(
+            "let(k, 1, addk(k))\n= addk(1)\n= add(k, 1)\n= add(10, 1)\n= 11",
+            trace.to_string()
+        );
+        // `set` in a let body changes the bound value for the rest of the body
+        assert_eq!(
+            "9",
+            steps("let(x, 1, add(set(x, 3), x, x))", Syntax::Call)
+                .last()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn long_traces_are_cut_short() {
+        let mut env = Env::new();
+        let config = EvalConfig::default();
+        let defn: Expr =
+            parse_expr("defn(count, (n), if(eq(n, 0), 0, add(1, count(sub(n, 1)))))").unwrap();
+        interp_expr_with_env(&defn, &mut env, &config).unwrap();
+        let trace: Trace = trace_expr(
+            &parse_expr("count(200)").unwrap(),
+            &mut env,
+            &config,
+            Syntax::Call,
+        );
+        assert_eq!(MAX_TRACE_STEPS + 1, trace.steps.len());
+        assert_eq!(Ok(Value::IntV { i_v: 200 }), trace.result);
+        assert_eq!(
+            Expr::Integer { i: 200 },
+            trace.steps.last().unwrap().expression
+        );
+        assert_eq!(0, env.call_depth());
+    }
+
+    #[test]
+    fn errors_keep_the_steps_before_them() {
+        let expr: Expr = parse_expr("add(mult(2, 3), div(1, 0))").unwrap();
+        let trace: Trace = trace_expr(&expr, &mut Env::new(), &EvalConfig::default(), Syntax::Call);
+        assert_eq!(
+            "add(mult(2, 3), div(1, 0))\n= add(6, div(1, 0))\ndiv: Arg 2 Divides by Zero",
+            trace.to_string()
+        );
+        let expr: Expr = parse_expr("if(1, 2, 3)").unwrap();
+        let trace: Trace = trace_expr(&expr, &mut Env::new(), &EvalConfig::default(), Syntax::Call);
+        assert_eq!(
+            Err(EvalError::TypeMismatch {
+                builtin: Builtin::If,
+                arg_index: 0,
+                expected: "a Boolean",
+            }),
+            trace.result
+        );
+    }
+
+    #[test]
+    fn traces_leave_the_session_alone() {
+        let mut env = Env::new();
+        evaluate_prefix_expression_in_env("set(x, 1)", &mut env);
+        let config = EvalConfig::default();
+        assert_eq!(
+            "set(x, add(x, 1))\n= 2",
+            trace_prefix_expression("set(x, add(x, 1))", &env, &config)
+        );
+        assert_eq!(
+            "mult(x, 3)\n= mult(1, 3)\n= 3",
+            trace_prefix_expression("mult(x, 3)", &env, &config)
+        );
+    }
+}
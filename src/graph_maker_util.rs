@@ -1,9 +1,26 @@
-use crate::{statistics_util::data_to_vector, GraphInfo};
+use crate::statistics_util::data_to_vector;
 use plotpy::{Curve, Plot, StrError};
 
+#[cfg(feature = "gui")]
 use crate::file_util::get_save_path;
 
-pub fn make_2d_curve_from_data(x_data: &Vec<f64>, y_data: &Vec<f64>) -> Result<Curve, StrError> {
+/// Mirrors the `GraphInfo` struct of the Slint front end
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GraphInfo {
+    pub title: String,
+
+    pub x_axis_label: String,
+    pub y_axis_label: String,
+    pub z_axis_label: String,
+
+    pub x_data: String,
+    pub y_data: String,
+    pub z_data: String,
+
+    pub is_2d: bool,
+}
+
+pub fn make_2d_curve_from_data(x_data: &[f64], y_data: &[f64]) -> Result<Curve, StrError> {
     // configure curve
     let mut curve = Curve::new();
     curve.set_line_width(2.0);
@@ -19,9 +36,9 @@ pub fn make_2d_curve_from_data(x_data: &Vec<f64>, y_data: &Vec<f64>) -> Result<C
 }
 
 pub fn make_3d_curve_from_data(
-    x_data: &Vec<f64>,
-    y_data: &Vec<f64>,
-    z_data: &Vec<f64>,
+    x_data: &[f64],
+    y_data: &[f64],
+    z_data: &[f64],
 ) -> Result<Curve, StrError> {
     let mut curve = Curve::new();
     curve.set_line_width(2.0);
@@ -35,20 +52,20 @@ pub fn make_3d_curve_from_data(
     return Ok(curve);
 }
 
-pub fn make_graph(graph_info: GraphInfo) -> Result<(), StrError> {
+pub fn build_plot(graph_info: &GraphInfo) -> Result<Plot, StrError> {
     // add curve to plot
     let mut plot = Plot::new();
 
-    let x_data = data_to_vector(graph_info.x_data.as_str());
-    let y_data = data_to_vector(graph_info.y_data.as_str());
+    let x_data = data_to_vector(&graph_info.x_data);
+    let y_data = data_to_vector(&graph_info.y_data);
     if graph_info.is_2d {
         // configure curve
         let curve = make_2d_curve_from_data(&x_data, &y_data)?;
-        plot.set_title(graph_info.title.as_str())
+        plot.set_title(&graph_info.title)
             .grid_and_labels(&graph_info.x_axis_label, &graph_info.y_axis_label);
         plot.add(&curve);
     } else {
-        let z_data = data_to_vector(graph_info.y_data.as_str());
+        let z_data = data_to_vector(&graph_info.z_data);
         let curve = make_3d_curve_from_data(&x_data, &y_data, &z_data)?;
         plot.set_subplot_3d(1, 1, 1)
             .set_label_x(&graph_info.x_axis_label)
@@ -57,6 +74,12 @@ pub fn make_graph(graph_info: GraphInfo) -> Result<(), StrError> {
             .set_title(&graph_info.title)
             .add(&curve);
     }
+    return Ok(plot);
+}
+
+#[cfg(feature = "gui")]
+pub fn make_graph(graph_info: GraphInfo) -> Result<(), StrError> {
+    let plot = build_plot(&graph_info)?;
 
     // save figure
     match get_save_path() {
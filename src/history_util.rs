@@ -0,0 +1,263 @@
+use std::{
+    fmt,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{file_util::config_dir, func_expr_util::expr_types::Syntax};
+
+/// One calculation from the expression calculator
+#[derive(Clone, Debug, PartialEq)]
+pub struct HistoryEntry {
+    /// What `expression` was typed in, prefix lines may be either call or s-expression syntax
+    pub syntax: Syntax,
+    pub expression: String,
+    /// The answer as shown, or the error message
+    pub answer: String,
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub is_error: bool,
+}
+
+impl HistoryEntry {
+    /// An entry for a calculation made just now
+    pub fn new(syntax: Syntax, expression: &str, result: Result<String, String>) -> HistoryEntry {
+        let timestamp: u64 = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        let (answer, is_error) = match result {
+            Ok(answer) => (answer, false),
+            Err(message) => (message, true),
+        };
+        return HistoryEntry {
+            syntax,
+            expression: expression.to_string(),
+            answer,
+            timestamp,
+            is_error,
+        };
+    }
+
+    /// "infix" or "prefix", the calculator line the expression belongs in
+    pub fn mode(&self) -> &'static str {
+        match self.syntax {
+            Syntax::Infix => "infix",
+            Syntax::Call | Syntax::SExpr => "prefix",
+        }
+    }
+
+    /// Whether the expression or answer contains `query`, ignoring case
+    pub fn matches(&self, query: &str) -> bool {
+        let query: String = query.to_lowercase();
+        return self.expression.to_lowercase().contains(&query)
+            || self.answer.to_lowercase().contains(&query);
+    }
+
+    /// One line of the history file, tab separated with tabs, newlines and backslashes escaped
+    fn to_line(&self) -> String {
+        return format!(
+            "{}\t{}\t{}\t{}\t{}",
+            self.timestamp,
+            self.mode(),
+            if self.is_error { "error" } else { "ok" },
+            escape_field(&self.expression),
+            escape_field(&self.answer)
+        );
+    }
+
+    /// None for lines that are not entries, like ones cut short by a crash
+    fn from_line(line: &str) -> Option<HistoryEntry> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let [timestamp, mode, status, expression, answer] = fields.as_slice() else {
+            return None;
+        };
+        return Some(HistoryEntry {
+            syntax: match *mode {
+                "infix" => Syntax::Infix,
+                "prefix" => Syntax::Call,
+                _ => return None,
+            },
+            expression: unescape_field(expression),
+            answer: unescape_field(answer),
+            timestamp: timestamp.parse().ok()?,
+            is_error: match *status {
+                "ok" => false,
+                "error" => true,
+                _ => return None,
+            },
+        });
+    }
+}
+
+impl fmt::Display for HistoryEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Multi-line answers like matrices start on their own line
+        if self.answer.contains('\n') {
+            return write!(f, "{} =\n{}", self.expression, self.answer);
+        }
+        return write!(f, "{} = {}", self.expression, self.answer);
+    }
+}
+
+fn escape_field(field: &str) -> String {
+    return field
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n");
+}
+
+fn unescape_field(field: &str) -> String {
+    let mut unescaped: String = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    return unescaped;
+}
+
+/// Where the history is kept between sessions, None when there is no config directory
+pub fn history_path() -> Option<PathBuf> {
+    return config_dir().map(|dir| dir.join("math-calc").join("history.tsv"));
+}
+
+/// The entries saved at `path`, oldest first. A missing file is an empty history
+pub fn load_history(path: &Path) -> io::Result<Vec<HistoryEntry>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(contents
+            .lines()
+            .filter_map(HistoryEntry::from_line)
+            .collect()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Adds `entry` to the end of the file at `path`, creating it and its directory if needed
+pub fn append_history(path: &Path, entry: &HistoryEntry) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    return writeln!(file, "{}", entry.to_line());
+}
+
+/// Empties the history saved at `path`
+pub fn clear_history(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// The entries matching `query`, oldest first, all of them for an empty query
+pub fn search_history<'a>(entries: &'a [HistoryEntry], query: &str) -> Vec<&'a HistoryEntry> {
+    let query: &str = query.trim();
+    return entries
+        .iter()
+        .filter(|entry| entry.matches(query))
+        .collect();
+}
+
+/// The timestamp as a UTC date and time, like "2024-05-17 09:30 UTC"
+pub fn format_timestamp(timestamp: u64) -> String {
+    let (days, seconds) = (timestamp / 86_400, timestamp % 86_400);
+    // Days to a civil date, from Howard Hinnant's date algorithms
+    let shifted: u64 = days + 719_468;
+    let era: u64 = shifted / 146_097;
+    let day_of_era: u64 = shifted % 146_097;
+    let year_of_era: u64 =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year: u64 = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index: u64 = (5 * day_of_year + 2) / 153;
+    let day: u64 = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month: u64 = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year: u64 = year_of_era + era * 400 + u64::from(month <= 2);
+    return format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60
+    );
+}
+
+#[cfg(test)]
+mod history_tests {
+    use super::*;
+
+    fn entry(syntax: Syntax, expression: &str, result: Result<&str, &str>) -> HistoryEntry {
+        return HistoryEntry {
+            timestamp: 1_700_000_000,
+            ..HistoryEntry::new(
+                syntax,
+                expression,
+                result.map(str::to_string).map_err(str::to_string),
+            )
+        };
+    }
+
+    #[test]
+    fn saved_entries_load_back() {
+        let path: PathBuf = std::env::temp_dir()
+            .join(format!("math-calc-history-{}", std::process::id()))
+            .join("history.tsv");
+        let entries: Vec<HistoryEntry> = vec![
+            entry(Syntax::Infix, "identity(2)", Ok("[1  0]\n[0  1]")),
+            entry(
+                Syntax::Call,
+                "add(1,\t\\x)",
+                Err("x: Variable Is Not Defined"),
+            ),
+        ];
+        for saved in &entries {
+            append_history(&path, saved).unwrap();
+        }
+        assert_eq!(entries, load_history(&path).unwrap());
+        // Lines that are not entries are left out
+        fs::write(&path, "garbage\n1700000000\tinfix\tok\t1 + 1\t2\n").unwrap();
+        assert_eq!(
+            vec![entry(Syntax::Infix, "1 + 1", Ok("2"))],
+            load_history(&path).unwrap()
+        );
+        clear_history(&path).unwrap();
+        assert!(load_history(&path).unwrap().is_empty());
+        fs::remove_dir(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn searches_expressions_and_answers() {
+        let entries: Vec<HistoryEntry> = vec![
+            entry(Syntax::Infix, "sin(pi / 2)", Ok("1")),
+            entry(Syntax::Call, "mult(6, 7)", Ok("42")),
+            entry(Syntax::Infix, "1 / 0", Err("div: Arg 2 Divides by Zero")),
+        ];
+        assert_eq!(3, search_history(&entries, " ").len());
+        assert_eq!(vec![&entries[0]], search_history(&entries, "SIN"));
+        assert_eq!(vec![&entries[1]], search_history(&entries, "42"));
+        assert_eq!(vec![&entries[2]], search_history(&entries, "divides"));
+        assert_eq!("prefix", entries[1].mode());
+    }
+
+    #[test]
+    fn timestamps_in_utc() {
+        assert_eq!("1970-01-01 00:00 UTC", format_timestamp(0));
+        assert_eq!("2023-11-14 22:13 UTC", format_timestamp(1_700_000_000));
+        assert_eq!("2024-02-29 12:00 UTC", format_timestamp(1_709_208_000));
+    }
+}
@@ -0,0 +1,532 @@
+use crate::{
+    func_expr_util::{
+        expr_types::{format_float, Builtin, Env, EvalConfig, Expr, ParseError, Syntax},
+        parse_expr::{parse_expr, parse_expr_with_syntax},
+        trace_expr::{trace_expr, Trace},
+    },
+    history_util::HistoryEntry,
+};
+
+const COMPARISON: u8 = 1;
+const SUM: u8 = 2;
+const PRODUCT: u8 = 3;
+const NEGATION: u8 = 4;
+const POWER: u8 = 5;
+const ATOM: u8 = 6;
+
+/// Names LaTeX has a symbol for
+const GREEK_LETTERS: [&str; 35] = [
+    "alpha", "beta", "gamma", "delta", "epsilon", "zeta", "eta", "theta", "iota", "kappa",
+    "lambda", "mu", "nu", "xi", "pi", "rho", "sigma", "tau", "upsilon", "phi", "chi", "psi",
+    "omega", "Gamma", "Delta", "Theta", "Lambda", "Xi", "Pi", "Sigma", "Upsilon", "Phi", "Psi",
+    "Omega", "hbar",
+];
+
+/// Builtins LaTeX writes as upright function names, like `\sin`
+const LATEX_FUNCTIONS: [(Builtin, &str); 15] = [
+    (Builtin::Sin, "\\sin"),
+    (Builtin::Cos, "\\cos"),
+    (Builtin::Tan, "\\tan"),
+    (Builtin::ArcSin, "\\arcsin"),
+    (Builtin::ArcCos, "\\arccos"),
+    (Builtin::ArcTan, "\\arctan"),
+    (Builtin::Sinh, "\\sinh"),
+    (Builtin::Cosh, "\\cosh"),
+    (Builtin::Tanh, "\\tanh"),
+    (Builtin::Ln, "\\ln"),
+    (Builtin::Log10, "\\log_{10}"),
+    (Builtin::Log2, "\\log_{2}"),
+    (Builtin::Max, "\\max"),
+    (Builtin::Min, "\\min"),
+    (Builtin::GCD, "\\gcd"),
+];
+
+/// Math mode LaTeX for an expression, like `\frac{1}{2} \cdot x^{2}` for mult(div(1, 2), pow(x, 2))
+pub fn print_latex(expr: &Expr) -> String {
+    return latex(expr).0;
+}
+
+/// The LaTeX and how tightly it binds, so operands know when they need parentheses
+fn latex(expr: &Expr) -> (String, u8) {
+    match expr {
+        Expr::Integer { i } => (i.to_string(), if *i < 0 { NEGATION } else { ATOM }),
+        Expr::BigInteger { b } => {
+            let text: String = b.to_string();
+            let precedence: u8 = if text.starts_with('-') {
+                NEGATION
+            } else {
+                ATOM
+            };
+            (text, precedence)
+        }
+        Expr::Float { f } => float_latex(*f),
+        Expr::Imaginary { im } => match *im {
+            1.0 => ("i".to_string(), ATOM),
+            -1.0 => ("-i".to_string(), NEGATION),
+            im => {
+                let (coefficient, precedence) = float_latex(im);
+                (format!("{}i", coefficient), precedence.min(PRODUCT))
+            }
+        },
+        Expr::Boolean { b } => (format!("\\mathrm{{{}}}", b), ATOM),
+        Expr::Var { name } => (name_latex(name), ATOM),
+        Expr::Let { name, value, body } => (
+            format!(
+                "{} \\quad \\text{{where }} {} = {}",
+                print_latex(body),
+                name_latex(name),
+                print_latex(value)
+            ),
+            COMPARISON,
+        ),
+        Expr::Set { name, value } => (
+            format!("{} := {}", name_latex(name), print_latex(value)),
+            COMPARISON,
+        ),
+        Expr::Defn { name, params, body } => (
+            format!(
+                "{}\\left({}\\right) := {}",
+                name_latex(name),
+                names_latex(params),
+                print_latex(body)
+            ),
+            COMPARISON,
+        ),
+        Expr::Lambda { params, body } => {
+            let params: String = match params.len() {
+                1 => name_latex(&params[0]),
+                _ => format!("\\left({}\\right)", names_latex(params)),
+            };
+            (
+                format!("{} \\mapsto {}", params, print_latex(body)),
+                COMPARISON,
+            )
+        }
+        Expr::Call { func, args } => (
+            format!("{}\\left({}\\right)", operand(func, ATOM), args_latex(args)),
+            ATOM,
+        ),
+        Expr::BuiltinFn { name, args } => builtin_latex(*name, args),
+    }
+}
+
+fn builtin_latex(name: Builtin, args: &[Expr]) -> (String, u8) {
+    let join = |separator: &str, precedence: u8| -> (String, u8) {
+        let operands: Vec<String> = args
+            .iter()
+            .enumerate()
+            .map(|(index, arg)| match index {
+                0 => operand(arg, precedence),
+                _ => right_operand(arg, precedence),
+            })
+            .collect();
+        return (operands.join(separator), precedence);
+    };
+    let call = |function: &str| -> (String, u8) {
+        return (
+            format!("{}\\left({}\\right)", function, args_latex(args)),
+            ATOM,
+        );
+    };
+    if let Some((_, function)) = LATEX_FUNCTIONS.iter().find(|(builtin, _)| *builtin == name) {
+        return call(function);
+    }
+    match (name, args) {
+        (Builtin::Add, [_, _, ..]) => join(" + ", SUM),
+        (Builtin::Subtract, [Expr::Integer { i: 0 }, negated]) => (
+            format!("-{}", operand(negated, PRODUCT)),
+            NEGATION,
+        ),
+        (Builtin::Subtract, [left, right]) => (
+            format!(
+                "{} - {}",
+                operand(left, SUM),
+                right_operand(right, PRODUCT)
+            ),
+            SUM,
+        ),
+        (Builtin::Multiply, [_, _, ..]) => join(" \\cdot ", PRODUCT),
+        (Builtin::Divide, [numerator, denominator]) => (
+            format!(
+                "\\frac{{{}}}{{{}}}",
+                print_latex(numerator),
+                print_latex(denominator)
+            ),
+            PRODUCT,
+        ),
+        (Builtin::Modulus, [_, _]) => join(" \\bmod ", PRODUCT),
+        (Builtin::Cross, [_, _]) => join(" \\times ", PRODUCT),
+        (Builtin::Pow, [base, exponent]) => (
+            format!("{}^{{{}}}", operand(base, ATOM), print_latex(exponent)),
+            POWER,
+        ),
+        (Builtin::Sqrt, [radicand]) => (format!("\\sqrt{{{}}}", print_latex(radicand)), ATOM),
+        (Builtin::Abs, [value]) => (
+            format!("\\left|{}\\right|", print_latex(value)),
+            ATOM,
+        ),
+        (Builtin::Factorial, [n]) => (format!("{}!", operand(n, ATOM)), POWER),
+        (Builtin::Choose, [n, k]) => (
+            format!("\\binom{{{}}}{{{}}}", print_latex(n), print_latex(k)),
+            ATOM,
+        ),
+        (Builtin::Equal, [_, _]) => join(" = ", COMPARISON + 1),
+        (Builtin::NotEqual, [_, _]) => join(" \\neq ", COMPARISON + 1),
+        (Builtin::Less, [_, _]) => join(" < ", COMPARISON + 1),
+        (Builtin::LessEqual, [_, _]) => join(" \\leq ", COMPARISON + 1),
+        (Builtin::Greater, [_, _]) => join(" > ", COMPARISON + 1),
+        (Builtin::GreaterEqual, [_, _]) => join(" \\geq ", COMPARISON + 1),
+        (Builtin::If, [condition, then, otherwise]) => (
+            format!(
+                "\\begin{{cases}} {} & \\text{{if }} {} \\\\ {} & \\text{{otherwise}} \\end{{cases}}",
+                print_latex(then),
+                print_latex(condition),
+                print_latex(otherwise)
+            ),
+            ATOM,
+        ),
+        // Just the value of an integral, which is how `I(...)` reads in infix
+        (Builtin::Get, [integral @ Expr::BuiltinFn { name: Builtin::Integrate, .. }, Expr::Integer { i: 0 }]) => {
+            latex(integral)
+        }
+        (Builtin::Get, [list, index]) => (
+            format!("{}_{{{}}}", operand(list, ATOM), print_latex(index)),
+            ATOM,
+        ),
+        (Builtin::List, _) => (format!("\\left[{}\\right]", args_latex(args)), ATOM),
+        (Builtin::Matrix, rows) if rows.iter().all(is_list) => {
+            let rows: Vec<String> = rows
+                .iter()
+                .map(|row| match row {
+                    Expr::BuiltinFn { args: cells, .. } => {
+                        let cells: Vec<String> = cells.iter().map(print_latex).collect();
+                        cells.join(" & ")
+                    }
+                    _ => unreachable!("rows were checked to be lists"),
+                })
+                .collect();
+            (
+                format!("\\begin{{pmatrix}} {} \\end{{pmatrix}}", rows.join(" \\\\ ")),
+                ATOM,
+            )
+        }
+        (Builtin::Diff, [body, Expr::Var { name }]) => (
+            format!(
+                "\\frac{{d}}{{d{}}} {}",
+                name_latex(name),
+                operand(body, POWER)
+            ),
+            PRODUCT,
+        ),
+        (Builtin::Diff, [body, Expr::Var { name }, at]) => (
+            format!(
+                "\\left. \\frac{{d}}{{d{}}} {} \\right|_{{{} = {}}}",
+                name_latex(name),
+                operand(body, POWER),
+                name_latex(name),
+                print_latex(at)
+            ),
+            ATOM,
+        ),
+        (Builtin::Integrate, [body, Expr::Var { name }, from, to]) => (
+            format!(
+                "\\int_{{{}}}^{{{}}} {} \\, d{}",
+                print_latex(from),
+                print_latex(to),
+                operand(body, SUM),
+                name_latex(name)
+            ),
+            SUM,
+        ),
+        _ => call(&format!("\\operatorname{{{}}}", name.name().replace('_', "\\_"))),
+    }
+}
+
+/// An operand, in parentheses when it binds looser than `precedence`
+fn operand(expr: &Expr, precedence: u8) -> String {
+    let (text, own) = latex(expr);
+    if own < precedence {
+        return format!("\\left({}\\right)", text);
+    }
+    return text;
+}
+
+/// An operand after an operator, where a leading minus sign also needs parentheses,
+/// as in 2 - (-3)
+fn right_operand(expr: &Expr, precedence: u8) -> String {
+    let (text, own) = latex(expr);
+    if own < precedence || own == NEGATION {
+        return format!("\\left({}\\right)", text);
+    }
+    return text;
+}
+
+fn is_list(expr: &Expr) -> bool {
+    return matches!(
+        expr,
+        Expr::BuiltinFn {
+            name: Builtin::List,
+            ..
+        }
+    );
+}
+
+fn args_latex(args: &[Expr]) -> String {
+    let args: Vec<String> = args.iter().map(print_latex).collect();
+    return args.join(", ");
+}
+
+fn names_latex(names: &[String]) -> String {
+    let names: Vec<String> = names.iter().map(|name| name_latex(name)).collect();
+    return names.join(", ");
+}
+
+/// Greek letters as symbols, longer names upright, and anything after an underscore as a
+/// subscript, so k_B becomes k_{B} and epsilon_0 becomes \epsilon_{0}
+fn name_latex(name: &str) -> String {
+    if let Some((base, subscript)) = name.split_once('_') {
+        if !base.is_empty() && !subscript.is_empty() {
+            return format!("{}_{{{}}}", name_latex(base), name_latex(subscript));
+        }
+    }
+    if GREEK_LETTERS.contains(&name) {
+        return format!("\\{}", name);
+    } else if name.chars().count() == 1 {
+        return name.to_string();
+    }
+    return format!("\\mathrm{{{}}}", name.replace('_', "\\_"));
+}
+
+/// Scientific notation as a power of ten
+fn float_latex(f: f64) -> (String, u8) {
+    if f.is_nan() {
+        return ("\\mathrm{NaN}".to_string(), ATOM);
+    } else if f.is_infinite() {
+        let sign: &str = if f < 0.0 { "-" } else { "" };
+        return (
+            format!("{}\\infty", sign),
+            if f < 0.0 { NEGATION } else { ATOM },
+        );
+    }
+    let precedence: u8 = if f.is_sign_negative() { NEGATION } else { ATOM };
+    let text: String = format_float(f);
+    return match text.split_once('e') {
+        Some((mantissa, exponent)) => (
+            format!("{} \\times 10^{{{}}}", mantissa, exponent),
+            precedence.min(PRODUCT),
+        ),
+        None => (text, precedence),
+    };
+}
+
+/// Escapes characters LaTeX treats specially in text
+fn escape_text(text: &str) -> String {
+    let mut escaped: String = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("; "),
+            _ => escaped.push(c),
+        }
+    }
+    return escaped;
+}
+
+/// The expression and each step towards its value, one per line of an `align*`,
+/// ending with the error if evaluation stopped at one
+pub fn trace_latex(trace: &Trace) -> String {
+    let mut lines: Vec<String> = vec![print_latex(&trace.expression)];
+    for (index, step) in trace.steps.iter().enumerate() {
+        let line: String = format!("&= {}", print_latex(&step.expression));
+        match index {
+            0 => lines[0] = format!("{} {}", lines[0], line),
+            _ => lines.push(line),
+        }
+    }
+    if let Err(e) = &trace.result {
+        lines.push(format!("&\\text{{{}}}", escape_text(&e.to_string())));
+    }
+    return format!(
+        "\\begin{{align*}}\n{}\n\\end{{align*}}",
+        lines.join(" \\\\\n")
+    );
+}
+
+/// Prefix expressions are read in whichever of their syntaxes they were typed in
+fn parse_calculation(raw: &str, syntax: Syntax) -> Result<Expr, ParseError> {
+    match syntax {
+        Syntax::Infix => parse_expr_with_syntax(raw, Syntax::Infix),
+        Syntax::Call | Syntax::SExpr => parse_expr(raw),
+    }
+}
+
+/// The `align*` for a calculation typed in `syntax`, evaluated in a copy of `env`
+pub fn calculation_latex(raw: &str, syntax: Syntax, env: &Env, config: &EvalConfig) -> String {
+    match parse_calculation(raw, syntax) {
+        Ok(expr) => trace_latex(&trace_expr(&expr, &mut env.clone(), config, syntax)),
+        Err(e) => format!(
+            "\\begin{{verbatim}}\n{}\n\\end{{verbatim}}\n{}",
+            raw,
+            escape_text(&e.to_string())
+        ),
+    }
+}
+
+/// Every calculation in the history as `expression = answer`, one per line of an `align*`
+pub fn history_latex(entries: &[HistoryEntry]) -> String {
+    if entries.is_empty() {
+        return String::new();
+    }
+    let lines: Vec<String> = entries
+        .iter()
+        .map(|entry| {
+            let expression: String = match parse_calculation(&entry.expression, entry.syntax) {
+                Ok(expr) => print_latex(&expr),
+                Err(_) => format!("\\text{{{}}}", escape_text(&entry.expression)),
+            };
+            match answer_latex(&entry.answer).filter(|_| !entry.is_error) {
+                Some(answer) => format!("{} &= {}", expression, answer),
+                // Errors and anything else that is not a value
+                None => format!(
+                    "{} &\\quad \\text{{{}}}",
+                    expression,
+                    escape_text(&entry.answer)
+                ),
+            }
+        })
+        .collect();
+    return format!(
+        "\\begin{{align*}}\n{}\n\\end{{align*}}",
+        lines.join(" \\\\\n")
+    );
+}
+
+/// Answers are written so infix reads them back, apart from matrices, which are one row a line
+fn answer_latex(answer: &str) -> Option<String> {
+    if !answer.contains('\n') {
+        return parse_expr_with_syntax(answer, Syntax::Infix)
+            .ok()
+            .map(|expr| print_latex(&expr));
+    }
+    // Matrices are one bracketed row per line, like "[1  0]"
+    let mut rows: Vec<String> = Vec::new();
+    for line in answer.lines() {
+        let row: &str = line.trim().strip_prefix('[')?.strip_suffix(']')?;
+        let mut cells: Vec<String> = Vec::new();
+        for cell in row.split_whitespace() {
+            cells.push(print_latex(
+                &parse_expr_with_syntax(cell, Syntax::Infix).ok()?,
+            ));
+        }
+        rows.push(cells.join(" & "));
+    }
+    return Some(format!(
+        "\\begin{{pmatrix}} {} \\end{{pmatrix}}",
+        rows.join(" \\\\ ")
+    ));
+}
+
+/// A complete document around LaTeX from the functions above, ready for pdflatex
+pub fn latex_document(body: &str) -> String {
+    return format!(
+        "\\documentclass{{article}}\n\\usepackage{{amsmath}}\n\n\\begin{{document}}\n\n{}\n\n\\end{{document}}\n",
+        body
+    );
+}
+
+#[cfg(test)]
+mod latex_tests {
+    use super::*;
+    use crate::func_expr_util::evaluate_prefix_expression;
+
+    fn infix_latex(source: &str) -> String {
+        return print_latex(&parse_expr_with_syntax(source, Syntax::Infix).unwrap());
+    }
+
+    #[test]
+    fn operators_and_precedence() {
+        assert_eq!("\\frac{1}{2} \\cdot x^{2}", infix_latex("1/2 * x^2"));
+        assert_eq!("\\left(a + b\\right)^{2}", infix_latex("(a + b)^2"));
+        assert_eq!("a - \\left(b + c\\right)", infix_latex("a - (b + c)"));
+        assert_eq!("-x^{2}", infix_latex("-x^2"));
+        assert_eq!(
+            "2 \\cdot \\left(-3\\right)",
+            print_latex(&parse_expr("mult(2, -3)").unwrap())
+        );
+        assert_eq!("x \\leq 2", infix_latex("x <= 2"));
+        assert_eq!(
+            "6.02 \\times 10^{23} \\cdot k_{B}",
+            infix_latex("6.02e23 * k_B")
+        );
+    }
+
+    #[test]
+    fn functions_and_names() {
+        assert_eq!(
+            "\\sin\\left(\\theta\\right) + \\sqrt{\\left|\\epsilon_{0}\\right|}",
+            infix_latex("sin(theta) + sqrt(abs(epsilon_0))")
+        );
+        assert_eq!(
+            "\\int_{0}^{\\pi} \\sin\\left(x\\right) \\, dx",
+            infix_latex("integrate(sin(x), x, 0, pi)")
+        );
+        assert_eq!("\\frac{d}{dx} x^{3}", infix_latex("diff(x^3, x)"));
+        assert_eq!(
+            "\\operatorname{percent\\_of}\\left(10, 50\\right)",
+            infix_latex("percent_of(10, 50)")
+        );
+        assert_eq!(
+            "\\mathrm{area}\\left(r\\right) := \\pi \\cdot r^{2}",
+            print_latex(&parse_expr("defn(area, (r), mult(pi, pow(r, 2)))").unwrap())
+        );
+        assert_eq!(
+            "\\begin{pmatrix} 1 & 2 \\\\ 3 & 4 \\end{pmatrix}",
+            print_latex(&parse_expr("matrix(list(1, 2), list(3, 4))").unwrap())
+        );
+    }
+
+    #[test]
+    fn calculations_and_history() {
+        let config = EvalConfig::default();
+        assert_eq!(
+            "\\begin{align*}\n2 + 3 \\cdot 4 &= 2 + 12 \\\\\n&= 14\n\\end{align*}",
+            calculation_latex("2 + 3 * 4", Syntax::Infix, &Env::new(), &config)
+        );
+        assert_eq!(
+            "\\begin{align*}\n\\frac{1}{0} \\\\\n&\\text{div: Arg 2 Divides by Zero}\n\\end{align*}",
+            calculation_latex("div(1, 0)", Syntax::Call, &Env::new(), &config)
+        );
+        let entries: Vec<HistoryEntry> = vec![
+            HistoryEntry::new(Syntax::SExpr, "(+ 1 (* 2 x))", Ok("7/2".to_string())),
+            HistoryEntry::new(
+                Syntax::Infix,
+                "identity(2)",
+                Ok(evaluate_prefix_expression("identity(2)")),
+            ),
+            HistoryEntry::new(
+                Syntax::Infix,
+                "y",
+                Err("y: Variable Is Not Defined".to_string()),
+            ),
+        ];
+        assert_eq!(
+            "\\begin{align*}\n1 + 2 \\cdot x &= \\frac{7}{2} \\\\\n\
+            \\operatorname{identity}\\left(2\\right) &= \\begin{pmatrix} 1 & 0 \\\\ 0 & 1 \\end{pmatrix} \\\\\n\
+            y &\\quad \\text{y: Variable Is Not Defined}\n\\end{align*}",
+            history_latex(&entries)
+        );
+        assert_eq!(
+            Some("\\begin{pmatrix} 1 & -2.5 \\\\ 30 & 4 \\end{pmatrix}".to_string()),
+            answer_latex(&evaluate_prefix_expression(
+                "matrix(list(1, -2.5), list(30, 4))"
+            ))
+        );
+        assert!(latex_document("x").contains("\\begin{document}\n\nx\n\n\\end{document}"));
+    }
+}
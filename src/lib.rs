@@ -0,0 +1,25 @@
+#![allow(clippy::needless_return)]
+//! Math-Calc's calculator engines, usable without the Slint front end.
+//!
+//! The `gui` feature adds the file dialogs and Slint conversions used by the app,
+//! and the `graph` feature adds plotting through plotpy. Both are on by default;
+//! build with `default-features = false` for just the math.
+//!
+//! ```
+//! use math_calc::func_expr_util::evaluate_prefix_expression;
+//! use math_calc::unit_conversion_util::value_as_unit;
+//!
+//! assert_eq!("14", evaluate_prefix_expression("add(2, mult(3, 4))"));
+//! assert_eq!("100", value_as_unit("m", "1", "cm"));
+//! ```
+
+pub mod expression_util;
+pub mod file_util;
+pub mod func_expr_util;
+#[cfg(feature = "graph")]
+pub mod graph_maker_util;
+pub mod history_util;
+pub mod latex_util;
+pub mod number_conversion_util;
+pub mod statistics_util;
+pub mod unit_conversion_util;
@@ -1,38 +1,238 @@
 #![windows_subsystem = "windows"]
+#![allow(clippy::needless_return)]
+
+use std::{cell::RefCell, rc::Rc};
 
 use slint::{SharedString, VecModel};
 
 slint::include_modules!();
 
-mod expression_util;
-mod file_util;
-mod func_expr_util;
-mod graph_maker_util;
-mod number_conversion_util;
-mod statistics_util;
-mod unit_conversion_util;
+mod cli_util;
+
+use math_calc::*;
+
+impl From<GraphInfo> for graph_maker_util::GraphInfo {
+    fn from(value: GraphInfo) -> Self {
+        return graph_maker_util::GraphInfo {
+            title: value.title.into(),
+            x_axis_label: value.x_axis_label.into(),
+            y_axis_label: value.y_axis_label.into(),
+            z_axis_label: value.z_axis_label.into(),
+            x_data: value.x_data.into(),
+            y_data: value.y_data.into(),
+            z_data: value.z_data.into(),
+            is_2d: value.is_2d,
+        };
+    }
+}
+
+impl From<&history_util::HistoryEntry> for HistoryItem {
+    fn from(value: &history_util::HistoryEntry) -> Self {
+        return HistoryItem {
+            time: history_util::format_timestamp(value.timestamp).into(),
+            mode: value.mode().into(),
+            expression: value.expression.clone().into(),
+            text: value.to_string().into(),
+            is_error: value.is_error,
+        };
+    }
+}
+
+/// Adds a calculation to the history, on disk too so it is there next time
+fn record_calculation(
+    history: &RefCell<Vec<history_util::HistoryEntry>>,
+    entry: history_util::HistoryEntry,
+) {
+    if let Some(path) = history_util::history_path() {
+        if let Err(e) = history_util::append_history(&path, &entry) {
+            eprintln!("Could Not Save History: {}", e);
+        }
+    }
+    history.borrow_mut().push(entry);
+}
+
+/// A GUI subsystem program starts without a console, so output from the command line
+/// goes to the console of the shell it was run from
+#[cfg(windows)]
+fn attach_parent_console() {
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    // Fails harmlessly when there is no parent console, like when output is redirected
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
 
 fn main() -> Result<(), slint::PlatformError> {
+    // Command Line
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        #[cfg(windows)]
+        attach_parent_console();
+        match cli_util::run_cli(&args) {
+            Ok(output) => println!("{}", output),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
     let ui = AppWindow::new()?;
     use file_util::*;
 
     // Expression Calculator
 
-    use expression_util::calc_expr;
-    use func_expr_util::evaluate_prefix_expression;
+    use expression_util::{infix_as_prefix, trace_infix_expression, try_calc_expr};
+    use func_expr_util::{
+        constants::constants_table,
+        expr_types::{AngleMode, Env, EvalConfig, Syntax},
+        prefix_as_infix, trace_prefix_expression, try_evaluate_prefix_expression,
+    };
+    use history_util::{history_path, load_history, search_history, HistoryEntry};
+    use latex_util::{calculation_latex, history_latex, latex_document};
+
+    // Prefix variables last until the app closes or they are cleared
+    let prefix_env: Rc<RefCell<Env>> = Rc::new(RefCell::new(Env::new()));
+    // Shared by both evaluators, so the angle mode applies to infix and prefix alike
+    let eval_config: Rc<RefCell<EvalConfig>> = Rc::new(RefCell::new(EvalConfig::default()));
+    // Starts with the calculations from earlier sessions
+    let history: Rc<RefCell<Vec<HistoryEntry>>> = Rc::new(RefCell::new(
+        history_path()
+            .and_then(|path| load_history(&path).ok())
+            .unwrap_or_default(),
+    ));
 
-    ui.on_evaluate_infix_expression(|raw_string| {
-        return calc_expr(raw_string.as_str()).into();
+    let infix_config = eval_config.clone();
+    let infix_history = history.clone();
+    ui.on_evaluate_infix_expression(move |raw_string| {
+        let entry = HistoryEntry::new(
+            Syntax::Infix,
+            raw_string.as_str(),
+            try_calc_expr(raw_string.as_str(), &infix_config.borrow()),
+        );
+        let answer: SharedString = entry.answer.as_str().into();
+        record_calculation(&infix_history, entry);
+        return answer;
+    });
+
+    let mode_config = eval_config.clone();
+    ui.on_set_angle_mode(move |mode| {
+        if let Some(angle_mode) = AngleMode::from_name(mode.as_str()) {
+            mode_config.borrow_mut().angle_mode = angle_mode;
+        }
     });
 
-    ui.on_evaluate_prefix_expression(|raw_string| {
-        return evaluate_prefix_expression(raw_string.as_str());
+    ui.on_infix_as_prefix(|raw_string| {
+        return infix_as_prefix(raw_string.as_str()).into();
     });
 
-    ui.on_append_history(|previous_history, raw_expr, raw_ans| {
-        let mut new_history = previous_history.to_string();
-        new_history.push_str(&format!("{} = {}\n", raw_expr, raw_ans));
-        return new_history.into();
+    ui.on_prefix_as_infix(|raw_string| {
+        return prefix_as_infix(raw_string.as_str()).into();
+    });
+
+    ui.on_list_constants(|| {
+        return constants_table().into();
+    });
+
+    let trace_config = eval_config.clone();
+    ui.on_trace_infix_expression(move |raw_string| {
+        return trace_infix_expression(raw_string.as_str(), &trace_config.borrow()).into();
+    });
+
+    let trace_env = prefix_env.clone();
+    let prefix_trace_config = eval_config.clone();
+    ui.on_trace_prefix_expression(move |raw_string| {
+        return trace_prefix_expression(
+            raw_string.as_str(),
+            &trace_env.borrow(),
+            &prefix_trace_config.borrow(),
+        )
+        .into();
+    });
+
+    let export_config = eval_config.clone();
+    ui.on_export_infix_latex(move |raw_string| {
+        let latex: String = calculation_latex(
+            raw_string.as_str(),
+            Syntax::Infix,
+            &Env::new(),
+            &export_config.borrow(),
+        );
+        return save_tex("calculation.tex", &latex_document(&latex)).into();
+    });
+
+    let export_env = prefix_env.clone();
+    let prefix_export_config = eval_config.clone();
+    ui.on_export_prefix_latex(move |raw_string| {
+        let latex: String = calculation_latex(
+            raw_string.as_str(),
+            Syntax::Call,
+            &export_env.borrow(),
+            &prefix_export_config.borrow(),
+        );
+        return save_tex("calculation.tex", &latex_document(&latex)).into();
+    });
+
+    let evaluate_env = prefix_env.clone();
+    let prefix_history = history.clone();
+    ui.on_evaluate_prefix_expression(move |raw_string| {
+        let entry = HistoryEntry::new(
+            Syntax::Call,
+            raw_string.as_str(),
+            try_evaluate_prefix_expression(
+                raw_string.as_str(),
+                &mut evaluate_env.borrow_mut(),
+                &eval_config.borrow(),
+            ),
+        );
+        let answer: SharedString = entry.answer.as_str().into();
+        record_calculation(&prefix_history, entry);
+        return answer;
+    });
+
+    ui.on_clear_prefix_variables(move || {
+        prefix_env.borrow_mut().clear();
+    });
+
+    let search_entries = history.clone();
+    ui.on_search_history(move |query| {
+        return slint::ModelRc::new(VecModel::from(
+            search_history(&search_entries.borrow(), query.as_str())
+                .into_iter()
+                .map(HistoryItem::from)
+                .collect::<Vec<HistoryItem>>(),
+        ));
+    });
+
+    let text_entries = history.clone();
+    ui.on_history_as_text(move |query| {
+        return search_history(&text_entries.borrow(), query.as_str())
+            .into_iter()
+            .map(|entry| format!("{}\n", entry))
+            .collect::<String>()
+            .into();
+    });
+
+    let clear_entries = history.clone();
+    ui.on_clear_history(move || {
+        clear_entries.borrow_mut().clear();
+        if let Some(path) = history_path() {
+            if let Err(e) = history_util::clear_history(&path) {
+                eprintln!("Could Not Clear History: {}", e);
+            }
+        }
+    });
+
+    ui.on_export_history(move || {
+        if history.borrow().is_empty() {
+            return "The History Is Empty".into();
+        }
+        let latex: String = history_latex(&history.borrow());
+        return save_tex("history.tex", &latex_document(&latex)).into();
     });
 
     // Number Conversion
@@ -109,7 +309,7 @@ fn main() -> Result<(), slint::PlatformError> {
         }
     });
 
-    ui.on_make_graph(|graph_info| match make_graph(graph_info) {
+    ui.on_make_graph(|graph_info| match make_graph(graph_info.into()) {
         Ok(_) => {}
         Err(e) => {
             println!("Error Occured {}", e)
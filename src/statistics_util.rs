@@ -31,20 +31,20 @@ pub fn data_to_vector(raw_data: &str) -> Vec<f64> {
     return result;
 }
 
-pub fn one_dimensional_statistics(data: &mut Vec<f64>) -> Result<String, ErrorMessage> {
+pub fn one_dimensional_statistics(data: &mut [f64]) -> Result<String, ErrorMessage> {
     // Averages
     if data.contains(&f64::NAN) {
         return Err(ErrorMessage::new("Data Contains NaN"));
     }
     data.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
-    let sum: f64 = data.into_iter().fold(0.0, |acc, x| acc + *x);
+    let sum: f64 = data.iter().fold(0.0, |acc, x| acc + *x);
     let mean: f64 = sum / (data.len() as f64);
     let median: f64 = median(data)?;
     let mode: f64 = mode(data)?;
     let max: f64 = *data.last().unwrap_or(&f64::NAN);
     let min: f64 = *data.first().unwrap_or(&f64::NAN);
     let variance: f64 = data
-        .into_iter()
+        .iter()
         .map(|d| (*d - mean).powi(2))
         .fold(0.0, |acc, d| acc + d)
         / (data.len() as f64 - 1.0);
@@ -73,11 +73,11 @@ Std Deviation: {}",
 }
 
 /// Assumes sorted data
-fn median(data: &Vec<f64>) -> Result<f64, ErrorMessage> {
+fn median(data: &[f64]) -> Result<f64, ErrorMessage> {
     // Even: Mean Average of 2 Middle Values
     // [0 1 2 3]
-    if data.len() % 2 == 0 {
-        if data.len() == 0 {
+    if data.len().is_multiple_of(2) {
+        if data.is_empty() {
             return Err(ErrorMessage::new("No Data"));
         } else {
             return Ok((data[data.len() / 2 - 1] + data[data.len() / 2]) / 2.0);
@@ -92,8 +92,8 @@ fn median(data: &Vec<f64>) -> Result<f64, ErrorMessage> {
 
 ///
 /// Assumes sorted data
-fn mode(data: &Vec<f64>) -> Result<f64, ErrorMessage> {
-    if let Some(mut mode) = data.get(0) {
+fn mode(data: &[f64]) -> Result<f64, ErrorMessage> {
+    if let Some(mut mode) = data.first() {
         let mut max_count: usize = 0;
         let mut current_value: f64 = *mode;
         let mut count: usize = 0;
@@ -127,6 +127,7 @@ fn mode(data: &Vec<f64>) -> Result<f64, ErrorMessage> {
 }
 
 #[cfg(test)]
+#[allow(clippy::useless_vec)]
 mod statistics_tests {
     use super::mode;
 
@@ -91,7 +91,7 @@ fn from_grams_conversion_factor(right_type: &str) -> f64 {
 
 fn to_celcius_conversion_factor(left_type: &str) -> f64 {
     match left_type {
-        "Fahrenheit" => 0.5555555555555555555555555,
+        "Fahrenheit" => 5.0 / 9.0,
         "Celcius" => 1.0,
         "Kelvin" => 1.0,
         _ => f64::NAN,